@@ -1,9 +1,10 @@
-use crate::{Keyed, Path, Query, RespondMany, RespondOne};
-use alloc::boxed::Box;
-use core::ops::Bound;
+use crate::{Keyed, MoveQuery, Path, PruneFn, Query, RespondEntry, RespondMany, RespondOne};
+use alloc::{boxed::Box, sync::Arc};
+use core::{fmt, iter::Sum, ops::Bound};
 use edfsm::Input;
 use edfsm_machine::{adapter::Adapter, error::Result};
-use tokio::sync::oneshot;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
 
 /// Create a handle for async queries on the given channel or adapter
 pub fn requester<T>(sender: T) -> Requester<T> {
@@ -31,6 +32,18 @@ where
         self.dispatch(Query::Get(path, remote), receiver).await
     }
 
+    /// Get a copy of the value at the given path, or the default value if there is none.
+    ///
+    /// The default value is not inserted in the store. Use `upsert` for that.
+    pub async fn get_or_default(&mut self, path: Path) -> Result<V>
+    where
+        V: Clone + Default + Send,
+    {
+        let (remote, receiver) = respond_one(|v: Option<&V>| (v.cloned().unwrap_or_default(), ()));
+        self.dispatch(Query::GetOrDefault(path, remote), receiver)
+            .await
+    }
+
     /// Get the entries whose path starts with the given path,
     /// including the entry for the path itself.
     /// Apply `func` to these and return the result.
@@ -43,6 +56,41 @@ where
         self.dispatch(Query::GetTree(path, remote), receiver).await
     }
 
+    /// Fold the entries whose path starts with the given path, including the entry
+    /// for the path itself, and return the result.
+    ///
+    /// The fold runs in the store's task over borrowed values, so only the result
+    /// is sent back. As for any query, a slow fold delays the store.
+    pub async fn fold_tree<B, F>(&mut self, path: Path, init: B, mut func: F) -> Result<B>
+    where
+        F: FnMut(B, &Path, &V) -> B + Send + 'static,
+        B: Send + 'static,
+    {
+        self.get_tree(path, move |vs| vs.fold(init, |b, (p, v)| func(b, p, v)))
+            .await
+    }
+
+    /// Sum a quantity of the entries whose path starts with the given path,
+    /// including the entry for the path itself. See `fold_tree`.
+    pub async fn sum_by<N, F>(&mut self, path: Path, func: F) -> Result<N>
+    where
+        F: Fn(&V) -> N + Send + 'static,
+        N: Sum + Send + 'static,
+    {
+        self.get_tree(path, move |vs| vs.map(|(_, v)| func(v)).sum())
+            .await
+    }
+
+    /// Count the entries whose path starts with the given path, including the entry
+    /// for the path itself, for which `func` returns true. See `fold_tree`.
+    pub async fn count_where<F>(&mut self, path: Path, func: F) -> Result<usize>
+    where
+        F: Fn(&Path, &V) -> bool + Send + 'static,
+    {
+        self.get_tree(path, move |vs| vs.filter(|(p, v)| func(p, v)).count())
+            .await
+    }
+
     /// Get the entries in the given range
     /// Apply `func` to these and return the result.
     pub async fn get_range<F, R>(&mut self, range: (Bound<Path>, Bound<Path>), func: F) -> Result<R>
@@ -81,6 +129,19 @@ where
         self.dispatch(Query::Upsert(path, remote), receiver).await
     }
 
+    /// As for `upsert`, but without waiting for the event to be applied.
+    ///
+    /// This returns once the query is accepted by the channel and does
+    /// not report whether an extant value was found.
+    pub async fn upsert_nowait<F>(&mut self, path: Path, func: F) -> Result<()>
+    where
+        F: FnOnce(Option<&V>) -> E + Send + 'static,
+    {
+        self.0
+            .notify(Input::Command(Query::Upsert(path, Box::new(func))))
+            .await
+    }
+
     /// Get all the entries and apply a function that produces an event.
     ///
     /// The event is keyed for a particular path. Usually this would be a new path
@@ -97,14 +158,123 @@ where
         self.dispatch(Query::Insert(remote), receiver).await
     }
 
+    /// Get the version of the subtree at the given path, or None if it has never had entries.
+    ///
+    /// A client may retain this and only get the subtree again when the version increases.
+    pub async fn tree_version(&mut self, path: Path) -> Result<Option<u64>> {
+        let (remote, receiver) = respond_one(|v: Option<&u64>| (v.copied(), ()));
+        self.dispatch(Query::TreeVersion(path, remote), receiver)
+            .await
+    }
+
+    /// Get a copy of the entries whose path starts with the given path,
+    /// including the entry for the path itself, through a channel.
+    ///
+    /// The channel holds up to `buffer` entries, or one entry if `buffer` is zero.
+    /// Entries beyond that are dropped rather than stalling the store. The receiver is
+    /// returned with the number of entries dropped. All other entries are in the
+    /// channel by the time this returns.
+    pub async fn stream_tree(
+        &mut self,
+        path: Path,
+        buffer: usize,
+    ) -> Result<(mpsc::Receiver<(Path, V)>, usize)>
+    where
+        V: Clone + Send,
+    {
+        let (sender, entries) = mpsc::channel(buffer.max(1));
+        let send = Box::new(move |p: &Path, v: &V| sender.try_send((p.clone(), v.clone())).is_ok());
+        let (remote, receiver) = respond_one(|n: Option<&usize>| (n.copied().unwrap_or(0), ()));
+        let dropped = self
+            .dispatch(Query::StreamTree(path, send, remote), receiver)
+            .await?;
+        Ok((entries, dropped))
+    }
+
+    /// Get a copy of the entry at the given path or, failing that, at its nearest ancestor.
+    ///
+    /// The path of the entry found is returned with its value, or None if neither
+    /// the path nor any ancestor has an entry.
+    pub async fn get_ancestor(&mut self, path: Path) -> Result<Option<(Path, V)>>
+    where
+        V: Clone + Send,
+    {
+        let (remote, receiver) = respond_entry(|entry: Option<(&Path, &V)>| {
+            (entry.map(|(p, v)| (p.clone(), v.clone())), ())
+        });
+        self.dispatch(Query::GetAncestor(path, remote), receiver)
+            .await
+    }
+
+    /// Remove the entries for which `func` returns an event, returning the number removed.
+    ///
+    /// The event is emitted for the entry's path and should be a terminating event,
+    /// so that the entry is removed. An entry given any other event is updated by it,
+    /// and is not counted. Entries are visited in path order with one
+    /// query dispatched per selected entry, so other queries may be interleaved.
+    pub async fn prune<F>(&mut self, func: F) -> Result<usize>
+    where
+        F: Fn(&Path, &V) -> Option<E> + Send + Sync + 'static,
+    {
+        let func: PruneFn<V, E> = Arc::new(func);
+        let mut from = Bound::Unbounded;
+        let mut count = 0;
+        loop {
+            let (remote, receiver) = respond_one(|p: Option<&(Path, bool)>| (p.cloned(), ()));
+            let query = Query::Prune(from, func.clone(), remote);
+            match self.dispatch(query, receiver).await? {
+                Some((path, removed)) => {
+                    count += usize::from(removed);
+                    from = Bound::Excluded(path);
+                }
+                None => return Ok(count),
+            }
+        }
+    }
+
     async fn dispatch<R>(&mut self, query: Query<V, E>, rx: oneshot::Receiver<R>) -> Result<R> {
         self.0.notify(Input::Command(query)).await?;
         Ok(rx.await?)
     }
 }
 
+impl<T, V, E> Requester<T>
+where
+    T: Adapter<Item = Input<MoveQuery<V, E>, Keyed<E>>>,
+    V: 'static,
+    E: 'static,
+{
+    /// A handle for the other queries, sent on a clone of this handle's channel or adapter.
+    pub fn queries(&self) -> Requester<impl Adapter<Item = Input<Query<V, E>, Keyed<E>>>>
+    where
+        T: Clone,
+        V: Send,
+        E: Send,
+    {
+        requester(self.0.clone().with_map(|i| match i {
+            Input::Command(query) => Input::Command(MoveQuery::Query(query)),
+            Input::Event(e) => Input::Event(e),
+        }))
+    }
+
+    /// Move the entry at one path to another, preserving its state, as a single event.
+    ///
+    /// Returns true if the entry is moved. It is not moved if there is no entry at `from`,
+    /// or if there is already an entry at `to`.
+    pub async fn move_entry(&mut self, from: Path, to: Path) -> Result<bool> {
+        let (remote, receiver) = respond_one(|v: Option<&()>| (v.is_some(), ()));
+        self.0
+            .notify(Input::Command(MoveQuery::Move(from, to, remote)))
+            .await?;
+        Ok(receiver.await?)
+    }
+}
+
 /// Indicates whether an extant (ie existing) value is found in a `KvStore`
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// This serializes as the name of the variant, `"Found"` or `"NotFound"`,
+/// and displays as `found` or `not found`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Extant {
     Found,
     NotFound,
@@ -120,6 +290,15 @@ impl From<bool> for Extant {
     }
 }
 
+impl fmt::Display for Extant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Extant::Found => f.write_str("found"),
+            Extant::NotFound => f.write_str("not found"),
+        }
+    }
+}
+
 fn respond_one<F, V, R, E>(func: F) -> (RespondOne<V, E>, oneshot::Receiver<R>)
 where
     F: FnOnce(Option<&V>) -> (R, E) + Send + 'static,
@@ -147,3 +326,34 @@ where
     });
     (remote, receiver)
 }
+
+fn respond_entry<F, V, R, E>(func: F) -> (RespondEntry<V, E>, oneshot::Receiver<R>)
+where
+    F: FnOnce(Option<(&Path, &V)>) -> (R, E) + Send + 'static,
+    R: Send + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+    let remote = Box::new(|entry: Option<(&Path, &V)>| {
+        let (r, e) = func(entry);
+        let _ = sender.send(r);
+        e
+    });
+    (remote, receiver)
+}
+
+#[cfg(test)]
+mod test {
+    use super::Extant;
+    use alloc::string::ToString;
+
+    #[test]
+    fn extant_display_serde() {
+        assert_eq!(Extant::Found.to_string(), "found");
+        assert_eq!(Extant::NotFound.to_string(), "not found");
+
+        let s = serde_json::to_string(&Extant::Found).unwrap();
+        assert_eq!(s, r#""Found""#);
+        let e: Extant = serde_json::from_str(r#""NotFound""#).unwrap();
+        assert_eq!(e, Extant::NotFound);
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{Effect, Event, Keyed, KvEffects, KvStore, Path, State};
+use alloc::collections::BTreeMap;
+use core::ops::Deref;
+use edfsm::{Change, Drain, Fsm, Input, Terminating};
+
+/// The event that removes an entry evicted from a `BoundedKvStore`.
+pub trait Evict: Fsm {
+    /// A terminating event for the entry with the given state.
+    fn evict(s: &Self::S) -> Self::E;
+}
+
+/// A `KvStore` holding at most `N` entries, evicting the least recently used
+/// entry when an event adds an entry beyond that.
+///
+/// An entry is used when an event changes it. Queries, including `Get`, do not count
+/// as a use: they cannot change the store and, as recency is derived from events alone,
+/// it is reproduced when the store is rehydrated from its event log.
+///
+/// An entry is evicted by following up the event that exceeded the capacity with the
+/// terminating event given by `Evict`. The eviction is therefore logged and published
+/// like any other removal, keeping projections of the event log consistent with the store.
+/// The least recently used entry is found from an index of the entries by version,
+/// costing a path and a `u64` per entry in addition to those of the `KvStore`.
+pub struct BoundedKvStore<M, const N: usize, SE = Keyed<Effect<M>>>
+where
+    M: Fsm,
+{
+    store: KvStore<M, SE>,
+    recency: BTreeMap<u64, Path>,
+}
+
+impl<M, const N: usize, SE> BoundedKvStore<M, N, SE>
+where
+    M: Fsm,
+{
+    /// The path of the least recently used entry, or None if the store is empty.
+    pub fn least_recent(&self) -> Option<&Path> {
+        self.recency.values().next()
+    }
+
+    fn is_over(&self) -> bool {
+        self.store.len() > N
+    }
+
+    fn touch(&mut self, path: &Path, before: Option<u64>) {
+        if let Some(v) = before {
+            self.recency.remove(&v);
+        }
+        if let Some(v) = self.store.version(path) {
+            self.recency.insert(v, path.clone());
+        }
+    }
+}
+
+impl<M, const N: usize, SE> Deref for BoundedKvStore<M, N, SE>
+where
+    M: Fsm,
+{
+    type Target = KvStore<M, SE>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.store
+    }
+}
+
+impl<M, const N: usize, SE> Default for BoundedKvStore<M, N, SE>
+where
+    M: Fsm,
+{
+    fn default() -> Self {
+        Self {
+            store: KvStore::default(),
+            recency: BTreeMap::new(),
+        }
+    }
+}
+
+impl<M, const N: usize, SE> Fsm for BoundedKvStore<M, N, SE>
+where
+    M: Evict + 'static,
+    State<M>: Default,
+    Event<M>: Terminating,
+    Effect<M>: Drain,
+    SE: KvEffects<Effect<M>>,
+{
+    type S = Self;
+    type C = <KvStore<M, SE> as Fsm>::C;
+    type E = <KvStore<M, SE> as Fsm>::E;
+    type SE = SE;
+
+    fn for_command(s: &Self::S, c: Self::C, se: &mut Self::SE) -> Option<Self::E> {
+        KvStore::<M, SE>::for_command(&s.store, c, se)
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        let before = s.store.version(&e.key);
+        let change = KvStore::<M, SE>::on_event(&mut s.store, e);
+        if change.is_some() {
+            s.touch(&e.key, before);
+        }
+        change
+    }
+
+    fn on_change(s: &Self::S, e: &Self::E, se: &mut Self::SE, change: Change) {
+        KvStore::<M, SE>::on_change(&s.store, e, se, change)
+    }
+
+    fn follow_up(s: &Self::S, _se: &mut Self::SE) -> Option<Self::E> {
+        if !s.is_over() {
+            return None;
+        }
+        let key = s.least_recent()?;
+        let state = s.store.get(key)?;
+        Some(Keyed {
+            key: key.clone(),
+            item: M::evict(state),
+        })
+    }
+
+    fn step_change(
+        s: &mut Self::S,
+        i: Input<Self::C, Self::E>,
+        se: &mut Self::SE,
+    ) -> Option<(Self::E, Option<Change>)> {
+        let e = match i {
+            Input::Command(c) => Self::for_command(s, c, se),
+            Input::Event(e) => Some(e),
+        }?;
+        let c = Self::apply(s, &e).change()?;
+        Self::on_change(s, &e, se, c);
+
+        // Exceeding the capacity is reported as a transition of the store,
+        // so that `step_all` follows up with an eviction.
+        if s.is_over() {
+            Some((e, Some(Change::Transitioned)))
+        } else {
+            Some((e, Some(c)))
+        }
+    }
+}
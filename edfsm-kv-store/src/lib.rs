@@ -1,21 +1,34 @@
 #![doc = include_str!("../README.md")]
 #![no_std]
 
+pub mod bounded;
+pub use bounded::{BoundedKvStore, Evict};
+
 pub mod path;
-pub use path::Path;
+pub use path::{Interner, Path};
+
+pub mod relocatable;
+pub use relocatable::{MoveQuery, Relocatable, Relocating};
 
 #[cfg(feature = "tokio")]
 pub mod async_query;
 #[cfg(feature = "tokio")]
 pub use async_query::{requester, Requester};
 
+#[cfg(feature = "tokio")]
+pub mod sharded;
+#[cfg(feature = "tokio")]
+pub use sharded::ShardedAdapter;
+
 extern crate alloc;
 use alloc::{
     boxed::Box,
-    collections::{btree_map::Entry, BTreeMap},
+    collections::{btree_map::Entry, BTreeMap, BTreeSet},
+    sync::Arc,
+    vec::Vec,
 };
-use core::{clone::Clone, ops::Bound};
-use edfsm::{Change, Drain, Fsm, Init, Input, Terminating};
+use core::{clone::Clone, marker::PhantomData, ops::Bound};
+use edfsm::{Change, CompactionKey, Drain, Fsm, Init, Input, Terminating};
 use serde::{Deserialize, Serialize};
 
 /// The event type of an Fsm
@@ -51,6 +64,11 @@ pub enum Query<V, E> {
     /// Get the value at the given path, or None.
     Get(Path, RespondOne<V, ()>),
 
+    /// Get the value at the given path, or the default value if there is none.
+    /// The response is always `Some`. Unlike `Upsert`, the default value is
+    /// not inserted and no event is emitted.
+    GetOrDefault(Path, RespondOne<V, ()>),
+
     /// Get the entries whose path starts with the given path,
     /// including the entry for the path itself.
     GetTree(Path, RespondMany<V, ()>),
@@ -66,14 +84,52 @@ pub enum Query<V, E> {
 
     /// Get all the entries and emit an event for a particular (usually new) path.
     Insert(RespondMany<V, Keyed<E>>),
+
+    /// Find the first entry after the given bound for which the function returns an event,
+    /// usually a terminating event, and emit that event for the entry's path.
+    /// The path is responded with, and whether the event is terminating and so removes
+    /// the entry, or None if there is no such entry.
+    ///
+    /// Removals are therefore logged as events like any other, keeping projections of
+    /// the event log consistent with the store. As one event is emitted per command,
+    /// a prune of many entries is a sequence of these queries. See `Requester::prune`.
+    Prune(Bound<Path>, PruneFn<V, E>, RespondOne<(Path, bool), ()>),
+
+    /// Get the version of the subtree at the given path, or None if it has never had entries.
+    /// The version increases whenever an entry in the subtree is changed or removed.
+    TreeVersion(Path, RespondOne<u64, ()>),
+
+    /// Pass each entry whose path starts with the given path, including the entry for
+    /// the path itself, to a function that sends it on, typically to a channel.
+    /// The number of entries that could not be sent is responded with.
+    ///
+    /// Unlike `GetTree`, the entries are processed by the receiver after the query,
+    /// while the store continues. The function should not wait for space in a channel,
+    /// as this would stall the store. Instead, an entry is dropped if it cannot be sent.
+    StreamTree(Path, SendEach<V>, RespondOne<usize, ()>),
+
+    /// Get the entry at the given path or, failing that, at its nearest ancestor,
+    /// or None if neither the path nor any ancestor has an entry.
+    /// This suits settings that are inherited from further up the tree unless overridden.
+    GetAncestor(Path, RespondEntry<V, ()>),
 }
 
+/// Type of a function that selects entries to prune by returning an event for them.
+pub type PruneFn<V, E> = Arc<dyn Fn(&Path, &V) -> Option<E> + Send + Sync>;
+
 /// Type of a function that will respond to an many-valued query.
 pub type RespondMany<V, E> = Box<dyn FnOnce(&mut dyn Iterator<Item = (&Path, &V)>) -> E + Send>;
 
+/// Type of a function that sends each entry of a streaming query, returning false if
+/// the entry is dropped.
+pub type SendEach<V> = Box<dyn FnMut(&Path, &V) -> bool + Send>;
+
 /// Type of a function that will respond to a single valued query.
 pub type RespondOne<V, E> = Box<dyn FnOnce(Option<&V>) -> E + Send>;
 
+/// Type of a function that will respond to a query for a single entry.
+pub type RespondEntry<V, E> = Box<dyn FnOnce(Option<(&Path, &V)>) -> E + Send>;
+
 /// `KvStore<M>` represents the collection of state machines of type `M`.
 ///
 /// `KvStore<M>` implements `Fsm` by distributing events to
@@ -82,37 +138,102 @@ pub type RespondOne<V, E> = Box<dyn FnOnce(Option<&V>) -> E + Send>;
 /// for each event or type `Path`.
 ///
 /// Commands are used to query and manager the store.  
-pub struct KvStore<M>(BTreeMap<Path, State<M>>)
+///
+/// By default, a single effector of type `Keyed<Effect<M>>` is shared by all entries and
+/// its key is set for each event. This costs nothing per entry, but the effector cannot hold
+/// state for a particular entry across events. Where that is needed, use `KvStore<M, PerPath<Effect<M>>>`,
+/// which holds an effector for each entry at the cost of memory per entry. The effector
+/// type is chosen by the second type parameter, which implements `KvEffects`.
+///
+/// Events are applied one at a time, in the order they are received on the machine's
+/// input channel, so each entry sees its events in that order. Where several tasks send
+/// through clones of the input sender, the events of each sender are received in the
+/// order sent, but the events of different senders may interleave in any order, including
+/// events for the same path. Senders needing an order between them must coordinate,
+/// for example by waiting on the response to a query such as `Requester::upsert`.
+///
+/// Each applied event is given a version from a counter, and the latest version of
+/// each entry is retained. Versions are derived from events alone and so are
+/// reproduced when the store is rehydrated from its event log.
+pub struct KvStore<M, SE = Keyed<Effect<M>>>(
+    BTreeMap<Path, State<M>>,
+    Versions,
+    PhantomData<fn() -> SE>,
+)
 where
     M: Fsm;
 
-impl<M> Fsm for KvStore<M>
+/// The versions of the entries in a `KvStore`.
+///
+/// A version is retained per entry, costing a second copy of the entry's path and a `u64`.
+/// When an entry is removed its version is recorded against its path instead, so that the
+/// removal is reflected in the version of any enclosing subtree. A removal record is
+/// dropped once an entry at the path is updated, as its newer version supersedes the
+/// record. The removal records are therefore bounded by the number of distinct paths
+/// removed and not since updated, again costing a path and a `u64` each. The version of
+/// a subtree is computed on demand, visiting the versions and removal records of each
+/// path in the subtree.
+#[derive(Debug, Default)]
+struct Versions {
+    latest: u64,
+    entries: BTreeMap<Path, u64>,
+    removed: BTreeMap<Path, u64>,
+}
+
+impl Versions {
+    fn update(&mut self, path: &Path) {
+        self.latest += 1;
+        self.removed.remove(path);
+        self.entries.insert(path.clone(), self.latest);
+    }
+
+    fn remove(&mut self, path: &Path) {
+        self.latest += 1;
+        self.entries.remove(path);
+        self.removed.insert(path.clone(), self.latest);
+    }
+
+    fn tree(&self, prefix: &Path) -> Option<u64> {
+        let within = |versions: &'_ BTreeMap<Path, u64>| {
+            versions
+                .range((Bound::Included(prefix), Bound::Unbounded))
+                .take_while(|(p, _)| p.starts_with(prefix))
+                .map(|(_, v)| *v)
+                .max()
+        };
+        within(&self.entries).max(within(&self.removed))
+    }
+}
+
+impl<M, SE> Fsm for KvStore<M, SE>
 where
     M: Fsm + 'static,
     State<M>: Default,
     Event<M>: Terminating,
     Effect<M>: Drain,
+    SE: KvEffects<Effect<M>>,
 {
     type S = Self;
     type C = Query<State<M>, Event<M>>;
     type E = Keyed<Event<M>>;
-    type SE = Keyed<Effect<M>>;
+    type SE = SE;
 
     fn for_command(store: &Self::S, command: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
-        use Bound::*;
         use Query::*;
         match command {
             Get(path, respond) => {
-                respond(store.0.get(&path));
+                respond(store.get(&path));
+                None
+            }
+            GetOrDefault(path, respond) => {
+                match store.get(&path) {
+                    Some(v) => respond(Some(v)),
+                    None => respond(Some(&Default::default())),
+                }
                 None
             }
             GetTree(path, respond) => {
-                respond(
-                    &mut (store
-                        .0
-                        .range((Included(&path), Unbounded))
-                        .take_while(|(p, _)| p.len() > path.len() || *p == &path)),
-                );
+                respond(&mut store.iter_prefix(&path));
                 None
             }
             GetRange(bounds, respond) => {
@@ -131,12 +252,43 @@ where
                 let e = respond(&mut store.0.iter());
                 Some(e)
             }
+            Prune(from, select, respond) => {
+                let e = store
+                    .0
+                    .range((from, Bound::Unbounded))
+                    .find_map(|(path, v)| {
+                        select(path, v).map(|item| Keyed {
+                            key: path.clone(),
+                            item,
+                        })
+                    });
+                let pruned = e.as_ref().map(|e| (e.key.clone(), e.item.terminating()));
+                respond(pruned.as_ref());
+                e
+            }
+            TreeVersion(path, respond) => {
+                respond(store.tree_version(&path).as_ref());
+                None
+            }
+            StreamTree(path, mut send, respond) => {
+                let dropped = store
+                    .iter_prefix(&path)
+                    .filter(|(p, v)| !send(p, v))
+                    .count();
+                respond(Some(&dropped));
+                None
+            }
+            GetAncestor(path, respond) => {
+                respond(store.get_ancestor(&path));
+                None
+            }
         }
     }
 
     fn on_event(r: &mut Self::S, e: &Self::E) -> Option<Change> {
         use Entry::*;
-        match (r.0.entry(e.key.clone()), e.item.terminating()) {
+        let terminating = e.item.terminating();
+        let change = match (r.0.entry(e.key.clone()), terminating) {
             (Occupied(entry), false) => {
                 let s = entry.into_mut();
                 M::on_event(s, &e.item)
@@ -150,17 +302,117 @@ where
                 Some(Change::Transitioned)
             }
             (Vacant(_), true) => None,
+        };
+        if change.is_some() {
+            if terminating {
+                r.1.remove(&e.key);
+            } else {
+                r.1.update(&e.key);
+            }
         }
+        change
     }
 
     fn on_change(r: &Self::S, e: &Self::E, se: &mut Self::SE, change: Change) {
-        if let Some(s) = r.0.get(&e.key) {
-            se.key = e.key.clone();
-            M::on_change(s, &e.item, &mut se.item, change);
+        match r.0.get(&e.key) {
+            Some(s) => M::on_change(s, &e.item, se.effects(&e.key), change),
+            None => se.remove(&e.key),
         }
     }
 }
 
+impl<M, SE> KvStore<M, SE>
+where
+    M: Fsm,
+{
+    /// Get the value at the given path, or None.
+    ///
+    /// This provides direct read access for code running in the same task
+    /// as the store. Other tasks should use `Query::Get`.
+    pub fn get(&self, path: &Path) -> Option<&State<M>> {
+        self.0.get(path)
+    }
+
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// The store has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Get the entry at the given path or at its nearest ancestor, or None.
+    ///
+    /// Successively shorter prefixes of the path are looked up, ending with the root path.
+    /// This has the same semantics as `Query::GetAncestor`.
+    pub fn get_ancestor(&self, path: &Path) -> Option<(&Path, &State<M>)> {
+        let mut prefix = path.clone();
+        loop {
+            if let Some(entry) = self.0.get_key_value(&prefix) {
+                return Some(entry);
+            }
+            prefix.pop()?;
+        }
+    }
+
+    /// Iterate the entries whose path starts with the given prefix,
+    /// including the entry for the prefix itself.
+    ///
+    /// This has the same semantics as `Query::GetTree`, but without
+    /// a channel round trip.
+    pub fn iter_prefix<'a>(
+        &'a self,
+        prefix: &'a Path,
+    ) -> impl Iterator<Item = (&'a Path, &'a State<M>)> + 'a {
+        self.0
+            .range((Bound::Included(prefix), Bound::Unbounded))
+            .take_while(move |(p, _)| p.starts_with(prefix))
+    }
+
+    /// Copy all the entries of the store, in path order.
+    ///
+    /// Unlike the event log, which reconstructs the store from its history, a snapshot
+    /// captures the materialised state directly, for backup and restore. A snapshot is
+    /// serializable given `Serialize` for the state, and so can be encoded as a single blob
+    /// by any serde codec. For a snapshot to survive evolution of the state type, encode it
+    /// with a versioned codec, such as `edfsm_machine::versioned::Versioned`, and register
+    /// a migration for each earlier version of the state type.
+    pub fn to_snapshot(&self) -> Vec<(Path, State<M>)>
+    where
+        State<M>: Clone,
+    {
+        self.0.iter().map(|(p, s)| (p.clone(), s.clone())).collect()
+    }
+
+    /// Restore a store from a snapshot, as produced by `to_snapshot`.
+    ///
+    /// No events are involved. Entry versions begin afresh, with each entry
+    /// versioned in the order given.
+    pub fn from_snapshot(entries: impl IntoIterator<Item = (Path, State<M>)>) -> Self {
+        let mut store = Self::default();
+        for (path, s) in entries {
+            store.1.update(&path);
+            store.0.insert(path, s);
+        }
+        store
+    }
+
+    /// The version of the entry at the given path, or None.
+    pub fn version(&self, path: &Path) -> Option<u64> {
+        self.1.entries.get(path).copied()
+    }
+
+    /// The version of the subtree at the given path, or None if it has never had entries.
+    ///
+    /// This is the greatest version of the entries in the subtree, including those removed.
+    /// It has the same semantics as `Query::TreeVersion`.
+    pub fn tree_version(&self, prefix: &Path) -> Option<u64> {
+        self.1.tree(prefix)
+    }
+}
+
 /// This type pairs a `Path` with another value.
 /// This may be an event or output of a state machine
 /// in the KvStore.
@@ -170,15 +422,169 @@ pub struct Keyed<A> {
     pub item: A,
 }
 
-impl<M> Default for KvStore<M>
+impl<A> Keyed<A> {
+    /// Transform the item, retaining the key.
+    pub fn map<B>(self, f: impl FnOnce(A) -> B) -> Keyed<B> {
+        Keyed {
+            key: self.key,
+            item: f(self.item),
+        }
+    }
+}
+
+impl<A> From<(Path, A)> for Keyed<A> {
+    fn from((key, item): (Path, A)) -> Self {
+        Self { key, item }
+    }
+}
+
+impl<A> From<Keyed<A>> for (Path, A) {
+    fn from(value: Keyed<A>) -> Self {
+        (value.key, value.item)
+    }
+}
+
+/// The event inputs of a `KvStore`, in a form that can be serialized.
+///
+/// An `Input` to a `KvStore` is either a `Query` or a `Keyed` event. Only the events
+/// can be serialized and so distributed, say, to replicas or a durable feed. Events are
+/// the complete record of changes to the store and are sufficient to reconstruct it.
+/// Queries are intentionally not serializable: each carries a function that
+/// responds to the caller in the requesting process, and so it has no meaning elsewhere.
+///
+/// Use `TryFrom` to obtain a `SerializableInput` from an `Input`. The query is returned
+/// if the input is not an event.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SerializableInput<E>(pub Keyed<E>);
+
+impl<V, E> TryFrom<Input<Query<V, E>, Keyed<E>>> for SerializableInput<E> {
+    type Error = Query<V, E>;
+
+    fn try_from(value: Input<Query<V, E>, Keyed<E>>) -> Result<Self, Self::Error> {
+        match value {
+            Input::Event(e) => Ok(SerializableInput(e)),
+            Input::Command(q) => Err(q),
+        }
+    }
+}
+
+impl<V, E> From<SerializableInput<E>> for Input<Query<V, E>, Keyed<E>> {
+    fn from(value: SerializableInput<E>) -> Self {
+        Input::Event(value.0)
+    }
+}
+
+impl<E> From<(Path, E)> for SerializableInput<E> {
+    fn from(value: (Path, E)) -> Self {
+        SerializableInput(value.into())
+    }
+}
+
+impl<E> From<SerializableInput<E>> for (Path, E) {
+    fn from(value: SerializableInput<E>) -> Self {
+        value.0.into()
+    }
+}
+
+impl<M, SE> Default for KvStore<M, SE>
 where
     M: Fsm,
 {
     fn default() -> Self {
-        Self(BTreeMap::new())
+        Self(BTreeMap::new(), Versions::default(), PhantomData)
+    }
+}
+
+/// The effector of a `KvStore`, which provides an effector for the entry at each path.
+pub trait KvEffects<SE>: Drain {
+    /// The effector for the entry at the given path.
+    fn effects(&mut self, path: &Path) -> &mut SE;
+
+    /// The entry at the given path has been removed.
+    fn remove(&mut self, _path: &Path) {}
+}
+
+impl<SE> KvEffects<SE> for Keyed<SE>
+where
+    SE: Drain,
+{
+    /// Set the key of the shared effector.
+    fn effects(&mut self, path: &Path) -> &mut SE {
+        self.key = path.clone();
+        &mut self.item
+    }
+}
+
+/// A `KvStore` effector that holds an effector for each entry.
+///
+/// An effector is created with `Default` for an entry when it is first changed, and it
+/// is dropped when the entry is removed. Effectors are not restored when a store is
+/// rehydrated, so any state they hold is lost when the store is restarted.
+/// Output messages are keyed with the path of the entry producing them,
+/// and drained in path order.
+#[derive(Debug)]
+pub struct PerPath<SE> {
+    effectors: BTreeMap<Path, SE>,
+    changed: BTreeSet<Path>,
+}
+
+impl<SE> PerPath<SE> {
+    /// The effector for the entry at the given path, if any.
+    pub fn get(&self, path: &Path) -> Option<&SE> {
+        self.effectors.get(path)
+    }
+}
+
+impl<SE> Default for PerPath<SE> {
+    fn default() -> Self {
+        Self {
+            effectors: BTreeMap::new(),
+            changed: BTreeSet::new(),
+        }
+    }
+}
+
+impl<SE> KvEffects<SE> for PerPath<SE>
+where
+    SE: Drain + Default,
+    SE::Item: Send,
+{
+    fn effects(&mut self, path: &Path) -> &mut SE {
+        self.changed.insert(path.clone());
+        self.effectors.entry(path.clone()).or_default()
+    }
+
+    fn remove(&mut self, path: &Path) {
+        self.effectors.remove(path);
     }
 }
 
+impl<SE> Drain for PerPath<SE>
+where
+    SE: Drain,
+    SE::Item: Send,
+{
+    type Item = Keyed<SE::Item>;
+
+    fn drain_all(&mut self) -> impl Iterator<Item = Self::Item> + Send {
+        let mut items = Vec::new();
+        for key in core::mem::take(&mut self.changed) {
+            if let Some(se) = self.effectors.get_mut(&key) {
+                items.extend(se.drain_all().map(|item| Keyed {
+                    key: key.clone(),
+                    item,
+                }));
+            }
+        }
+        items.into_iter()
+    }
+}
+
+impl<S, SE> Init<S> for PerPath<SE> {
+    fn init(&mut self, _state: &S) {}
+}
+
 impl<SE> Drain for Keyed<SE>
 where
     SE: Drain,
@@ -199,6 +605,31 @@ impl<A> Terminating for Keyed<A> {
     }
 }
 
+/// Events of an entry that are each a complete snapshot of the entry's state, so that
+/// the latest event for a path is enough to recover the entry. A terminating event,
+/// which removes the entry, is a snapshot in this sense.
+///
+/// This is the opt-in for compacting keyed events by path. Compaction retains only the
+/// latest event for each path, so events that update part of a state, such as a counter
+/// that is incremented, are lost and the entry is recovered wrongly. The events of a
+/// `Relocatable` store are not snapshots either, as a move removes the entry at the source
+/// path without an event for that path.
+pub trait Snapshot {}
+
+/// Keyed events are compacted by path, so that a compacted log retains the latest event
+/// for each entry. See `Path::compaction_key`.
+///
+/// This is only correct when each event is a complete snapshot of its entry's state, and
+/// so requires that the event type implements `Snapshot`.
+impl<A> CompactionKey for Keyed<A>
+where
+    A: Snapshot,
+{
+    fn compaction_key(&self) -> u64 {
+        self.key.compaction_key()
+    }
+}
+
 impl<S, SE> Init<S> for Keyed<SE> {
     fn init(&mut self, _state: &S) {}
 }
@@ -1,10 +1,18 @@
-use alloc::{string::String, vec::Vec};
-use core::{ops::Div, slice::Iter};
+use alloc::{collections::BTreeSet, string::String, vec::Vec};
+use core::{
+    fmt::{self, Write},
+    ops::Div,
+    slice::Iter,
+    str::FromStr,
+};
 use derive_more::{
     derive::{Deref, IntoIterator},
     From, TryInto,
 };
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{self, Unexpected, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
 use smol_str::SmolStr;
 
 /// The key to a KV store is a pathname, `Path`, and allows heirarchical grouping of values.
@@ -47,6 +55,31 @@ impl Path {
         self.0.push(item);
     }
 
+    /// Remove the last item of this path and return it, or None if this is the root path.
+    pub fn pop(&mut self) -> Option<PathItem> {
+        self.0.pop()
+    }
+
+    /// Shorten this path to its first `len` items. This has no effect if the path
+    /// is not longer than `len`, and truncating to zero leaves the root path.
+    pub fn truncate(&mut self, len: usize) {
+        self.0.truncate(len);
+    }
+
+    /// Replace the last item of the path, or append the item to the root path.
+    pub fn with_last(mut self, item: impl Into<PathItem>) -> Self {
+        self.pop();
+        self.append(item)
+    }
+
+    /// A new path consisting of the items of this path followed by the items of another.
+    pub fn join(&self, other: &Path) -> Path {
+        let mut items = Vec::with_capacity(self.len() + other.len());
+        items.extend_from_slice(&self.0);
+        items.extend_from_slice(&other.0);
+        Path(items)
+    }
+
     /// The length of this path.
     pub fn len(&self) -> usize {
         self.0.len()
@@ -60,6 +93,224 @@ impl Path {
     pub fn iter(&self) -> Iter<'_, PathItem> {
         self.0.iter()
     }
+
+    /// Convert this path to an MQTT topic name.
+    ///
+    /// Items are separated by `/`. Within names, the MQTT separator and wildcards,
+    /// `/`, `+` and `#`, as well as NUL and `%` are escaped as `%` followed by two
+    /// hex digits. A name that would otherwise read as a number has its first digit escaped.
+    ///
+    /// Note that the root path yields an empty topic, which MQTT does not permit.
+    pub fn to_mqtt_topic(&self) -> String {
+        let mut topic = String::new();
+        let _ = self.write_levels(&mut topic);
+        topic
+    }
+
+    /// Write the items separated by `/`, escaped as for `to_mqtt_topic`.
+    fn write_levels(&self, w: &mut impl Write) -> fmt::Result {
+        for (i, item) in self.iter().enumerate() {
+            if i > 0 {
+                w.write_char('/')?;
+            }
+            match item {
+                PathItem::Number(n) => write!(w, "{n}")?,
+                PathItem::Name(name) => {
+                    let numeric = !name.is_empty() && name.bytes().all(|b| b.is_ascii_digit());
+                    for (j, c) in name.chars().enumerate() {
+                        if matches!(c, '/' | '+' | '#' | '\0' | '%') || (numeric && j == 0) {
+                            write!(w, "%{:02X}", c as u32)?;
+                        } else {
+                            w.write_char(c)?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// A stable 64 bit hash of this path, for use as the key of a compacted commit log.
+    ///
+    /// This is the FNV-1a hash of an unambiguous encoding of the path items, and so
+    /// is the same on every platform and release. Distinct paths usually have distinct
+    /// keys, but may collide. Where keys collide, compaction retains only the latest event
+    /// of the colliding paths, losing the others. The chance of this is remote for any
+    /// practical number of paths, but where it matters keys should be checked for uniqueness.
+    pub fn compaction_key(&self) -> u64 {
+        const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+        let mut hash = OFFSET;
+        let mut write = |bytes: &[u8]| {
+            for b in bytes {
+                hash = (hash ^ u64::from(*b)).wrapping_mul(PRIME);
+            }
+        };
+        for item in self.iter() {
+            match item {
+                PathItem::Number(n) => {
+                    write(&[0]);
+                    write(&n.to_le_bytes());
+                }
+                PathItem::Name(name) => {
+                    write(&[1]);
+                    write(&(name.len() as u64).to_le_bytes());
+                    write(name.as_bytes());
+                }
+            }
+        }
+        hash
+    }
+
+    /// Parse an MQTT topic name, as produced by `to_mqtt_topic`, into a path.
+    ///
+    /// Levels consisting of digits are numbers, unless they have a leading zero.
+    /// Other levels are names, with any `%` escapes decoded.
+    /// The empty topic yields the root path.
+    pub fn from_mqtt_topic(topic: &str) -> Result<Path, PathError> {
+        if topic.is_empty() {
+            Ok(Path::root())
+        } else {
+            Self::parse_levels(topic)
+        }
+    }
+
+    /// Encode this path compactly, with no escaping, for logging and storage.
+    ///
+    /// Each name is written as its length in bytes in decimal, `:` and then the name as is,
+    /// so names may contain any character, including `/`. Each number is written as `#`,
+    /// its decimal digits and `;`. For example, `root() / "CSMS" / 65` is `4:CSMS#65;`
+    /// and the root path is empty. This is parsed by `decode_compact`.
+    pub fn encode_compact(&self) -> String {
+        let mut encoded = String::new();
+        for item in self.iter() {
+            let _ = match item {
+                PathItem::Number(n) => write!(encoded, "#{n};"),
+                PathItem::Name(name) => write!(encoded, "{}:{name}", name.len()),
+            };
+        }
+        encoded
+    }
+
+    /// Parse a path encoded by `encode_compact`.
+    pub fn decode_compact(encoded: &str) -> Result<Path, PathError> {
+        let mut path = Path::root();
+        let mut rest = encoded;
+        while !rest.is_empty() {
+            let (item, tail) = match rest.strip_prefix('#') {
+                Some(number) => {
+                    let (digits, tail) = number.split_once(';').ok_or(PathError::Malformed)?;
+                    (PathItem::Number(parse_decimal(digits)?), tail)
+                }
+                None => {
+                    let (len, tail) = rest.split_once(':').ok_or(PathError::Malformed)?;
+                    let len = parse_decimal(len)? as usize;
+                    let name = tail.get(..len).ok_or(PathError::Malformed)?;
+                    (PathItem::Name(name.into()), &tail[len..])
+                }
+            };
+            path.push(item);
+            rest = tail;
+        }
+        Ok(path)
+    }
+
+    /// Parse items separated by `/`, as written by `write_levels`.
+    fn parse_levels(levels: &str) -> Result<Path, PathError> {
+        let mut path = Path::root();
+        for level in levels.split('/') {
+            let numeric = !level.is_empty() && level.bytes().all(|b| b.is_ascii_digit());
+            if numeric && (level == "0" || !level.starts_with('0')) {
+                let n = level.parse().map_err(|_| PathError::NumberOverflow)?;
+                path.push(PathItem::Number(n));
+            } else {
+                path.push(unescape(level)?.into());
+            }
+        }
+        Ok(path)
+    }
+}
+
+/// A path is displayed as each of its items preceded by `/`, e.g. `/CSMS/65/EVSE/2`,
+/// and the root path as `/`. Names are escaped as for `to_mqtt_topic`.
+///
+/// This is parsed by `FromStr`. Levels consisting of digits are numbers, unless they
+/// have a leading zero, so `/0123` is the name `"0123"`. Numbers are unsigned and so
+/// `/-1` is the name `"-1"`. A number too large for a `u64` is an error, rather than a name.
+/// The only path that does not round trip is one consisting of a single empty name,
+/// which is displayed as `/` and parsed as the root path.
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('/')?;
+        self.write_levels(f)
+    }
+}
+
+impl FromStr for Path {
+    type Err = PathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('/') {
+            Some("") => Ok(Path::root()),
+            Some(levels) => Self::parse_levels(levels),
+            None => Err(PathError::NotAbsolute),
+        }
+    }
+}
+
+/// An error converting a `Path` from some other representation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathError {
+    /// A `%` escape is not followed by two hex digits, or does not form UTF-8.
+    BadEscape,
+    /// A number does not fit in a `u64`.
+    NumberOverflow,
+    /// A character is present that must be escaped.
+    Reserved(char),
+    /// A path does not begin with `/`.
+    NotAbsolute,
+    /// A compact encoding is truncated or otherwise malformed.
+    Malformed,
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::BadEscape => f.write_str("bad escape in path"),
+            PathError::NumberOverflow => f.write_str("number in path is too large"),
+            PathError::Reserved(c) => write!(f, "reserved character {c:?} in path"),
+            PathError::NotAbsolute => f.write_str("path does not begin with /"),
+            PathError::Malformed => f.write_str("malformed compact path"),
+        }
+    }
+}
+
+/// Parse a number of decimal digits only, without a sign.
+fn parse_decimal(digits: &str) -> Result<u64, PathError> {
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(PathError::Malformed);
+    }
+    digits.parse().map_err(|_| PathError::NumberOverflow)
+}
+
+fn unescape(level: &str) -> Result<String, PathError> {
+    let mut bytes = Vec::with_capacity(level.len());
+    let mut rest = level.bytes();
+    while let Some(b) = rest.next() {
+        match b {
+            b'%' => {
+                let hex = [
+                    rest.next().ok_or(PathError::BadEscape)?,
+                    rest.next().ok_or(PathError::BadEscape)?,
+                ];
+                let hex = core::str::from_utf8(&hex).map_err(|_| PathError::BadEscape)?;
+                bytes.push(u8::from_str_radix(hex, 16).map_err(|_| PathError::BadEscape)?);
+            }
+            b'+' | b'#' | b'\0' => return Err(PathError::Reserved(b as char)),
+            b => bytes.push(b),
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| PathError::BadEscape)
 }
 
 /// Another name for the empty path, also the default path.
@@ -78,16 +329,79 @@ where
     }
 }
 
+impl Div<Path> for Path {
+    type Output = Path;
+
+    fn div(mut self, other: Path) -> Self::Output {
+        self.0.extend(other.0);
+        self
+    }
+}
+
+impl Div<&Path> for Path {
+    type Output = Path;
+
+    fn div(mut self, other: &Path) -> Self::Output {
+        self.0.extend_from_slice(&other.0);
+        self
+    }
+}
+
 /// One element of a `Path` can be a number or a name.
-#[derive(
-    PartialEq, Eq, PartialOrd, Ord, Clone, Debug, From, Serialize, Deserialize, Hash, TryInto,
-)]
+///
+/// A number is serialized as an unsigned integer and a name as a string, with no tag.
+/// Deserialization is by the type of the value and so is unambiguous: an integer is always
+/// a number and a string is always a name, even one consisting of digits. Any other value,
+/// such as a negative integer, is an error. This requires a self-describing format.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, From, Serialize, Hash, TryInto)]
 #[serde(untagged)]
 pub enum PathItem {
     Number(u64),
     Name(SmolStr),
 }
 
+impl<'de> Deserialize<'de> for PathItem {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(PathItemVisitor)
+    }
+}
+
+struct PathItemVisitor;
+
+impl Visitor<'_> for PathItemVisitor {
+    type Value = PathItem;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an unsigned integer or a string")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(PathItem::Number(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        u64::try_from(v)
+            .map(PathItem::Number)
+            .map_err(|_| E::invalid_value(Unexpected::Signed(v), &self))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(PathItem::Name(SmolStr::new(v)))
+    }
+}
+
 impl From<&'static str> for PathItem {
     fn from(value: &'static str) -> Self {
         SmolStr::new_static(value).into()
@@ -100,10 +414,65 @@ impl From<String> for PathItem {
     }
 }
 
+/// An optional interning layer for the names in paths, to reduce the memory
+/// used by a large KV store whose paths share many name segments.
+///
+/// Short names are held inline by `SmolStr` and need no interning. A longer name is
+/// allocated on the heap each time it is built from a `String` or decoded, for example.
+/// Interning a path replaces each such name with a clone of a single shared instance,
+/// which refers to the same allocation. The interned path is equal to the original and
+/// orders and hashes identically, so it can be used wherever the original would be:
+///
+/// ```rust,ignore
+/// let mut interner = Interner::new();
+/// let path = interner.intern(decode_path(bytes));
+/// ```
+///
+/// Names are retained by the interner until it is dropped.
+#[derive(Debug, Default, Clone)]
+pub struct Interner(BTreeSet<SmolStr>);
+
+impl Interner {
+    /// Create an empty interner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the names of a path with shared instances.
+    pub fn intern(&mut self, path: Path) -> Path {
+        Path(path.0.into_iter().map(|i| self.intern_item(i)).collect())
+    }
+
+    /// Replace a name with a shared instance.
+    pub fn intern_item(&mut self, item: PathItem) -> PathItem {
+        match item {
+            PathItem::Name(name) if name.is_heap_allocated() => {
+                if let Some(shared) = self.0.get(&name) {
+                    PathItem::Name(shared.clone())
+                } else {
+                    self.0.insert(name.clone());
+                    PathItem::Name(name)
+                }
+            }
+            item => item,
+        }
+    }
+
+    /// The number of distinct names held.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// No names are held.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::{root, Path, PathItem};
-    use alloc::format;
+    use super::{root, Interner, Path, PathError, PathItem};
+    use alloc::{collections::BTreeSet, format, string::ToString, vec::Vec};
     use smol_str::SmolStr;
 
     #[test]
@@ -162,10 +531,181 @@ mod test {
         assert_eq!(p, root() / "CSMS" / 65 / "EVSE" / 2);
     }
 
+    #[test]
+    fn path_compaction_key() {
+        let paths = [
+            root(),
+            root() / "a",
+            root() / "b",
+            root() / "a" / "b",
+            root() / "ab",
+            root() / 1,
+            root() / "1",
+            root() / 1 / 2,
+            root() / 2 / 1,
+        ];
+        for (i, p) in paths.iter().enumerate() {
+            assert_eq!(p.compaction_key(), p.clone().compaction_key());
+            for q in &paths[i + 1..] {
+                assert_ne!(p.compaction_key(), q.compaction_key(), "{p:?} and {q:?}");
+            }
+        }
+
+        // Keys are stable across platforms and releases
+        assert_eq!(root().compaction_key(), 0xcbf2_9ce4_8422_2325);
+        assert_eq!((root() / "a" / 1).compaction_key(), 0x6598_cace_d887_f82d);
+    }
+
+    #[test]
+    fn path_join() {
+        let base = root() / "a";
+        let relative = root() / "b" / "c";
+        assert_eq!(base.join(&relative), root() / "a" / "b" / "c");
+        assert_eq!(base.join(&root()), base);
+        assert_eq!(root().join(&relative), relative);
+        assert_eq!(base.clone() / &relative, root() / "a" / "b" / "c");
+        assert_eq!(base / relative / 1, root() / "a" / "b" / "c" / 1);
+    }
+
+    #[test]
+    fn path_mqtt_topic() {
+        let p = root() / "CSMS" / 65 / "EVSE+#" / 2 / "a/b%" / "42" / "" / 0;
+        let topic = p.to_mqtt_topic();
+        assert_eq!(topic, "CSMS/65/EVSE%2B%23/2/a%2Fb%25/%342//0");
+        assert_eq!(Path::from_mqtt_topic(&topic), Ok(p));
+
+        assert_eq!(root().to_mqtt_topic(), "");
+        assert_eq!(Path::from_mqtt_topic(""), Ok(root()));
+        assert_eq!(Path::from_mqtt_topic("007"), Ok(root() / "007"));
+        assert_eq!(
+            Path::from_mqtt_topic("CSMS/+"),
+            Err(PathError::Reserved('+'))
+        );
+        assert_eq!(Path::from_mqtt_topic("CSMS/%2"), Err(PathError::BadEscape));
+        assert_eq!(Path::from_mqtt_topic("CSMS/%zz"), Err(PathError::BadEscape));
+        assert_eq!(
+            Path::from_mqtt_topic("99999999999999999999"),
+            Err(PathError::NumberOverflow)
+        );
+    }
+
+    #[test]
+    fn path_display_from_str() {
+        let p = root() / "CSMS" / 65 / "a/b" / "-1" / "0123";
+        let s = p.to_string();
+        assert_eq!(s, "/CSMS/65/a%2Fb/-1/%30123");
+        assert_eq!(s.parse(), Ok(p));
+
+        assert_eq!(root().to_string(), "/");
+        assert_eq!("/".parse(), Ok(root()));
+        assert_eq!("/0".parse(), Ok(root() / 0));
+        assert_eq!("/0123".parse(), Ok(root() / "0123"));
+        assert_eq!("/-1".parse(), Ok(root() / "-1"));
+        assert_eq!("/18446744073709551615".parse(), Ok(root() / u64::MAX));
+        assert_eq!(
+            "/18446744073709551616".parse::<Path>(),
+            Err(PathError::NumberOverflow)
+        );
+        assert_eq!("CSMS".parse::<Path>(), Err(PathError::NotAbsolute));
+        assert_eq!("/%zz".parse::<Path>(), Err(PathError::BadEscape));
+    }
+
+    #[test]
+    fn path_item_deserialisation_unambiguous() {
+        let p: Path = serde_json::from_str(r#"["65",65,"0",0]"#).unwrap();
+        assert_eq!(
+            p.iter().cloned().collect::<Vec<_>>(),
+            [
+                PathItem::Name("65".into()),
+                PathItem::Number(65),
+                PathItem::Name("0".into()),
+                PathItem::Number(0)
+            ]
+        );
+        assert_eq!(serde_json::to_string(&p).unwrap(), r#"["65",65,"0",0]"#);
+
+        for bad in ["[-1]", "[1.5]", "[null]", "[true]", r#"[["a"]]"#] {
+            assert!(serde_json::from_str::<Path>(bad).is_err(), "{bad}");
+        }
+    }
+
     #[test]
     fn path_serialisation_qs() {
         let p = root() / "CSMS" / 65 / "EVSE" / 2;
         let s = serde_qs::to_string(&p).unwrap();
         assert_eq!(s, "0=CSMS&1=65&2=EVSE&3=2");
     }
+
+    #[test]
+    fn interner_shares_names() {
+        // a prefix-heavy dataset: many devices, each with the same long measurand names
+        let measurands = ["Energy.Active.Import.Register", "Power.Active.Import.Total"];
+        let paths: Vec<Path> = (0..100)
+            .flat_map(|device| {
+                measurands.iter().map(move |m| {
+                    root() / format!("ChargingStation-{device:04}-Connectors") / m.to_string()
+                })
+            })
+            .collect();
+
+        fn allocations(paths: &[Path]) -> usize {
+            paths
+                .iter()
+                .flat_map(|p| p.iter())
+                .filter_map(|i| match i {
+                    PathItem::Name(n) if n.is_heap_allocated() => Some(n.as_ptr()),
+                    _ => None,
+                })
+                .collect::<BTreeSet<_>>()
+                .len()
+        }
+        assert_eq!(allocations(&paths), 400);
+
+        let mut interner = Interner::new();
+        let interned: Vec<Path> = paths.iter().cloned().map(|p| interner.intern(p)).collect();
+        assert_eq!(interned, paths);
+        assert_eq!(allocations(&interned), 102);
+        assert_eq!(interner.len(), 102);
+
+        // short names are held inline and not interned
+        assert_eq!(
+            interner.intern(root() / "CSMS".to_string() / 1),
+            root() / "CSMS" / 1
+        );
+        assert_eq!(interner.len(), 102);
+    }
+
+    #[test]
+    fn path_truncate_with_last() {
+        let mut path = root() / "CSMS" / 65 / "EVSE" / 2;
+        assert_eq!(path.clone().with_last(3), root() / "CSMS" / 65 / "EVSE" / 3);
+        assert_eq!(root().with_last("CSMS"), root() / "CSMS");
+
+        path.truncate(2);
+        assert_eq!(path, root() / "CSMS" / 65);
+        path.truncate(5);
+        assert_eq!(path, root() / "CSMS" / 65);
+        assert_eq!(path.pop(), Some(PathItem::Number(65)));
+        path.truncate(0);
+        assert_eq!(path, root());
+        assert_eq!(path.pop(), None);
+    }
+
+    #[test]
+    fn path_compact() {
+        let path = root() / "CSMS" / 65 / "a/b" / "" / "#1;" / 0 / "é";
+        let encoded = path.encode_compact();
+        assert_eq!(encoded, "4:CSMS#65;3:a/b0:3:#1;#0;2:é");
+        assert_eq!(Path::decode_compact(&encoded), Ok(path));
+        assert_eq!(root().encode_compact(), "");
+        assert_eq!(Path::decode_compact(""), Ok(root()));
+
+        for bad in ["4:CSM", "#65", "#;", "#-1;", "x:a", "1:é"] {
+            assert_eq!(Path::decode_compact(bad), Err(PathError::Malformed));
+        }
+        assert_eq!(
+            Path::decode_compact("#18446744073709551616;"),
+            Err(PathError::NumberOverflow)
+        );
+    }
 }
@@ -0,0 +1,97 @@
+use crate::{Effect, Event, Keyed, KvEffects, KvStore, Path, Query, RespondOne, State};
+use core::marker::PhantomData;
+use edfsm::{Change, Drain, Fsm, Terminating};
+
+/// Events that can move an entry of a `KvStore` from one path to another, preserving its
+/// state. A move is a single event for the destination path that names the source path.
+///
+/// The moved entry is not passed the event, and its effector, if held per path, is removed
+/// as for a removal. Implement this to run the store as a `Relocatable`.
+pub trait Relocating: Sized {
+    /// An event that moves the entry at the given path to the path of the event.
+    fn relocation(from: Path) -> Self;
+
+    /// The path of the entry that this event moves to the path of the event,
+    /// or None if this is not a move.
+    fn relocated_from(&self) -> Option<&Path>;
+}
+
+/// A query to a `Relocatable` store: either a `Query` or a move.
+pub enum MoveQuery<V, E> {
+    /// Any other query, as for a `KvStore`.
+    Query(Query<V, E>),
+
+    /// Move the entry at the first path to the second, preserving its state, and respond
+    /// with `Some` if it is moved. The move is emitted as a single event for the second
+    /// path, given by `Relocating::relocation`, and so is logged atomically.
+    ///
+    /// Nothing is moved and the response is None if there is no entry at the first path,
+    /// if there is already an entry at the second path, which is not overwritten, or if the
+    /// paths are the same.
+    Move(Path, Path, RespondOne<(), ()>),
+}
+
+impl<V, E> From<Query<V, E>> for MoveQuery<V, E> {
+    fn from(query: Query<V, E>) -> Self {
+        MoveQuery::Query(query)
+    }
+}
+
+/// An `Fsm` for a `KvStore` whose entries can also be moved from one path to another,
+/// given an event type that is `Relocating`. The commands are `MoveQuery`.
+///
+/// A store of any event type runs as a `KvStore`. This is the opt-in for moves, as
+/// the `KvStore` itself places no further bounds on its event type. `BoundedKvStore`
+/// does not support moves.
+pub struct Relocatable<M, SE = Keyed<Effect<M>>>(PhantomData<fn() -> (M, SE)>);
+
+impl<M, SE> Fsm for Relocatable<M, SE>
+where
+    M: Fsm + 'static,
+    State<M>: Default,
+    Event<M>: Terminating + Relocating,
+    Effect<M>: Drain,
+    SE: KvEffects<Effect<M>>,
+{
+    type S = KvStore<M, SE>;
+    type C = MoveQuery<State<M>, Event<M>>;
+    type E = Keyed<Event<M>>;
+    type SE = SE;
+
+    fn for_command(store: &Self::S, command: Self::C, se: &mut Self::SE) -> Option<Self::E> {
+        match command {
+            MoveQuery::Query(query) => KvStore::<M, SE>::for_command(store, query, se),
+            MoveQuery::Move(from, to, respond) => {
+                let movable =
+                    from != to && store.0.contains_key(&from) && !store.0.contains_key(&to);
+                let e = movable.then(|| Keyed {
+                    key: to,
+                    item: Event::<M>::relocation(from),
+                });
+                respond(e.as_ref().map(|_| &()));
+                e
+            }
+        }
+    }
+
+    fn on_event(r: &mut Self::S, e: &Self::E) -> Option<Change> {
+        let Some(from) = e.item.relocated_from() else {
+            return KvStore::<M, SE>::on_event(r, e);
+        };
+        if from == &e.key || r.0.contains_key(&e.key) {
+            return None;
+        }
+        let s = r.0.remove(from)?;
+        r.0.insert(e.key.clone(), s);
+        r.1.remove(from);
+        r.1.update(&e.key);
+        Some(Change::Transitioned)
+    }
+
+    fn on_change(r: &Self::S, e: &Self::E, se: &mut Self::SE, change: Change) {
+        match e.item.relocated_from() {
+            Some(from) => se.remove(from),
+            None => KvStore::<M, SE>::on_change(r, e, se, change),
+        }
+    }
+}
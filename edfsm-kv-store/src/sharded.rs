@@ -0,0 +1,56 @@
+use crate::{Keyed, Path};
+use alloc::vec::Vec;
+use edfsm_machine::{adapter::Adapter, error::Result};
+
+/// An `Adapter` that routes each keyed item to exactly one of several adapters,
+/// chosen by its path, to divide a workload such as a `KvStore` between machines.
+///
+/// The shard of a path is its `Path::compaction_key` modulo the number of shards, so
+/// every item for a path goes to the same shard, in the order notified. Items for different
+/// paths may be processed by different shards in any order. As the key is stable, so is the
+/// assignment of paths to shards, provided the number of shards is unchanged. Unlike
+/// `merge`, which sends each item to every adapter, an item is sent to one.
+///
+/// Shards are typically the inputs of machines, each mapped with `Input::Event`.
+#[derive(Debug)]
+pub struct ShardedAdapter<T> {
+    shards: Vec<T>,
+}
+
+impl<T> ShardedAdapter<T> {
+    /// Route items between the given adapters, of which there must be at least one.
+    pub fn new(shards: impl IntoIterator<Item = T>) -> Self {
+        let shards: Vec<T> = shards.into_iter().collect();
+        assert!(!shards.is_empty(), "a ShardedAdapter requires a shard");
+        Self { shards }
+    }
+
+    /// The index of the shard for the given path.
+    pub fn shard(&self, path: &Path) -> usize {
+        (path.compaction_key() % self.shards.len() as u64) as usize
+    }
+}
+
+impl<T, E> Adapter for ShardedAdapter<T>
+where
+    T: Adapter<Item = Keyed<E>> + Send,
+    E: Send + 'static,
+{
+    type Item = Keyed<E>;
+
+    async fn notify(&mut self, a: Self::Item) -> Result<()>
+    where
+        Self::Item: 'static,
+    {
+        let shard = self.shard(&a.key);
+        self.shards[shard].notify(a).await
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        let mut result = Ok(());
+        for shard in &mut self.shards {
+            result = result.and(shard.flush().await);
+        }
+        result
+    }
+}
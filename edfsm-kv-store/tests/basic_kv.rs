@@ -1,3 +1,5 @@
+#![cfg(feature = "tokio")]
+
 pub mod fixtures;
 use edfsm::Input;
 use edfsm_kv_store::{requester, Keyed, KvStore, Path, Query};
@@ -78,3 +80,190 @@ async fn empty_kv_test() {
     set.spawn(ask_task);
     set.join_all().await;
 }
+
+#[tokio::test]
+async fn upsert_nowait_test() -> Result<()> {
+    let machine = machine::<KvStore<Counter>>();
+    let mut r = requester(machine.input());
+    let task = tokio::spawn(machine.task());
+
+    for _ in 0..3 {
+        r.upsert_nowait(Path::root(), |_| Event::Tick).await?;
+    }
+    let n = r.get(Path::root(), |s| s.map(|s| s.count)).await?;
+    assert_eq!(n, Some(3));
+
+    drop(r);
+    task.await.unwrap()
+}
+
+#[tokio::test]
+async fn prune_test() -> Result<()> {
+    let machine = machine::<KvStore<Counter>>();
+    let mut r = requester(machine.input());
+    let task = tokio::spawn(machine.task());
+
+    for i in 0..6u64 {
+        for _ in 0..i {
+            r.upsert_nowait(Path::root().append(i), |_| Event::Tick)
+                .await?;
+        }
+    }
+
+    // Entries 1 to 5 exist. Prune those with an odd count.
+    let n = r
+        .prune(|_, s| (s.count % 2 == 1).then_some(Event::Reset))
+        .await?;
+    assert_eq!(n, 3);
+
+    let counts = r
+        .get_all(|ss| ss.map(|(_, s)| s.count).collect::<Vec<_>>())
+        .await?;
+    assert_eq!(counts, [2, 4]);
+
+    // An entry given an event that does not terminate it is updated and not counted
+    let n = r
+        .prune(|_, s| (s.count == 2).then_some(Event::Tick))
+        .await?;
+    assert_eq!(n, 0);
+
+    let counts = r
+        .get_all(|ss| ss.map(|(_, s)| s.count).collect::<Vec<_>>())
+        .await?;
+    assert_eq!(counts, [3, 4]);
+
+    drop(r);
+    task.await.unwrap()
+}
+
+#[tokio::test]
+async fn get_or_default_test() -> Result<()> {
+    let machine = machine::<KvStore<Counter>>();
+    let mut r = requester(machine.input());
+    let task = tokio::spawn(machine.task());
+
+    let path = Path::root().append("a");
+    assert_eq!(r.get_or_default(path.clone()).await?.count, 0);
+
+    // The default is not inserted
+    let n = r.get_all(|ss| ss.count()).await?;
+    assert_eq!(n, 0);
+
+    r.upsert(path.clone(), |_| Event::Tick).await?;
+    assert_eq!(r.get_or_default(path).await?.count, 1);
+
+    drop(r);
+    task.await.unwrap()
+}
+
+#[tokio::test]
+async fn stream_tree_test() -> Result<()> {
+    let machine = machine::<KvStore<Counter>>();
+    let mut r = requester(machine.input());
+    let task = tokio::spawn(machine.task());
+
+    for i in 0..4u64 {
+        r.upsert(Path::root().append("a").append(i), |_| Event::Tick)
+            .await?;
+    }
+    r.upsert(Path::root().append("b"), |_| Event::Tick).await?;
+
+    let (mut entries, dropped) = r.stream_tree(Path::root().append("a"), 10).await?;
+    assert_eq!(dropped, 0);
+    let mut paths = Vec::new();
+    while let Ok((path, s)) = entries.try_recv() {
+        assert_eq!(s.count, 1);
+        paths.push(path);
+    }
+    assert_eq!(
+        paths,
+        (0..4u64)
+            .map(|i| Path::root().append("a").append(i))
+            .collect::<Vec<_>>()
+    );
+
+    // Entries beyond the capacity of the channel are dropped
+    let (mut entries, dropped) = r.stream_tree(Path::root(), 3).await?;
+    assert_eq!(dropped, 2);
+    let mut n = 0;
+    while entries.try_recv().is_ok() {
+        n += 1;
+    }
+    assert_eq!(n, 3);
+
+    // A buffer of zero holds one entry
+    let (_entries, dropped) = r.stream_tree(Path::root(), 0).await?;
+    assert_eq!(dropped, 4);
+
+    drop(r);
+    task.await.unwrap()
+}
+
+#[tokio::test]
+async fn get_ancestor_test() -> Result<()> {
+    let machine = machine::<KvStore<Counter>>();
+    let mut r = requester(machine.input());
+    let task = tokio::spawn(machine.task());
+
+    let device = Path::root().append("devices").append(1u64);
+    let port = device.clone().append("ports").append(2u64);
+    r.upsert(device.clone(), |_| Event::Tick).await?;
+    r.upsert(port.clone(), |_| Event::Tick).await?;
+    r.upsert(port.clone(), |_| Event::Tick).await?;
+
+    // The entry for the path itself is nearest
+    let (path, s) = r.get_ancestor(port.clone()).await?.unwrap();
+    assert_eq!(path, port);
+    assert_eq!(s.count, 2);
+
+    // Otherwise the nearest ancestor with an entry is found
+    let (path, s) = r.get_ancestor(port.clone().append("speed")).await?.unwrap();
+    assert_eq!(path, port);
+    assert_eq!(s.count, 2);
+    let (path, s) = r
+        .get_ancestor(device.clone().append("ports").append(3u64))
+        .await?
+        .unwrap();
+    assert_eq!(path, device);
+    assert_eq!(s.count, 1);
+
+    // There is none outside the tree
+    assert!(r
+        .get_ancestor(Path::root().append("devices").append(2u64))
+        .await?
+        .is_none());
+
+    drop(r);
+    task.await.unwrap()
+}
+
+#[tokio::test]
+async fn aggregate_tree_test() -> Result<()> {
+    let machine = machine::<KvStore<Counter>>();
+    let mut r = requester(machine.input());
+    let task = tokio::spawn(machine.task());
+
+    // Entry /a/i is ticked i + 1 times
+    for i in 0..4u64 {
+        for _ in 0..=i {
+            r.upsert(Path::root().append("a").append(i), |_| Event::Tick)
+                .await?;
+        }
+    }
+    r.upsert(Path::root().append("b"), |_| Event::Tick).await?;
+
+    let a = Path::root().append("a");
+    assert_eq!(r.sum_by(a.clone(), |s| s.count).await?, 10);
+    assert_eq!(r.sum_by(Path::root(), |s| s.count).await?, 11);
+    assert_eq!(r.count_where(a.clone(), |_, s| s.count > 2).await?, 2);
+    let max = r.fold_tree(a.clone(), 0, |m, _, s| m.max(s.count)).await?;
+    assert_eq!(max, 4);
+
+    // An empty subtree aggregates to the initial value
+    let c = Path::root().append("c");
+    assert_eq!(r.sum_by(c.clone(), |s| s.count).await?, 0);
+    assert_eq!(r.fold_tree(c, 7, |m, _, s| m + s.count).await?, 7);
+
+    drop(r);
+    task.await.unwrap()
+}
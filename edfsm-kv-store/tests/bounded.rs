@@ -0,0 +1,69 @@
+pub mod fixtures;
+use edfsm::{Fsm, Input};
+use edfsm_kv_store::{path::root, BoundedKvStore, Evict, Keyed, Path, Query};
+use fixtures::{Counter, Event, State};
+
+impl Evict for Counter {
+    fn evict(_s: &State) -> Event {
+        Event::Reset
+    }
+}
+
+type Store = BoundedKvStore<Counter, 2>;
+
+fn tick(store: &mut Store, se: &mut <Store as Fsm>::SE, key: &'static str) -> Vec<(Path, bool)> {
+    let e = Keyed {
+        key: root() / key,
+        item: Event::Tick,
+    };
+    Store::step_all(store, Input::Event(e), se)
+        .map(|e| (e.key, matches!(e.item, Event::Reset)))
+        .collect()
+}
+
+fn paths(store: &Store) -> Vec<Path> {
+    store.to_snapshot().into_iter().map(|(p, _)| p).collect()
+}
+
+#[test]
+fn bounded_evicts_oldest_test() {
+    let mut store = Store::default();
+    let mut se = Default::default();
+
+    assert_eq!(tick(&mut store, &mut se, "a"), [(root() / "a", false)]);
+    assert_eq!(tick(&mut store, &mut se, "b"), [(root() / "b", false)]);
+
+    // Beyond capacity, the oldest entry is evicted with a terminating event
+    assert_eq!(
+        tick(&mut store, &mut se, "c"),
+        [(root() / "c", false), (root() / "a", true)]
+    );
+    assert_eq!(paths(&store), [root() / "b", root() / "c"]);
+}
+
+#[test]
+fn bounded_recency_test() {
+    let mut store = Store::default();
+    let mut se = Default::default();
+
+    tick(&mut store, &mut se, "a");
+    tick(&mut store, &mut se, "b");
+
+    // An event for an entry makes it the most recently used
+    tick(&mut store, &mut se, "a");
+    assert_eq!(store.least_recent(), Some(&(root() / "b")));
+
+    // A query does not
+    let get = Query::Get(
+        root() / "b",
+        Box::new(|v: Option<&State>| assert!(v.is_some())),
+    );
+    Store::step_all(&mut store, Input::Command(get), &mut se);
+
+    assert_eq!(
+        tick(&mut store, &mut se, "c"),
+        [(root() / "c", false), (root() / "b", true)]
+    );
+    assert_eq!(paths(&store), [root() / "a", root() / "c"]);
+    assert_eq!(store.get(&(root() / "a")).map(|s| s.count), Some(2));
+}
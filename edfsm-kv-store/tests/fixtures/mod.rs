@@ -21,7 +21,7 @@ pub enum Output {
     Tock,
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct State {
     pub count: i32,
 }
@@ -0,0 +1,87 @@
+pub mod fixtures;
+use edfsm::CompactionKey;
+use edfsm_kv_store::{
+    path::{root, Path},
+    Keyed, SerializableInput, Snapshot,
+};
+use fixtures::Event;
+use streambed_codec::{Cbor, Codec};
+
+#[test]
+fn keyed_tuple_round_trip() {
+    let keyed: Keyed<u32> = (root() / "a" / 1, 42).into();
+    assert_eq!(keyed.key, root() / "a" / 1);
+    assert_eq!(keyed.item, 42);
+
+    let (key, item): (Path, u32) = keyed.into();
+    assert_eq!(key, root() / "a" / 1);
+    assert_eq!(item, 42);
+
+    let wire: SerializableInput<u32> = (root() / "b", 7).into();
+    assert_eq!(wire.0.key, root() / "b");
+    assert_eq!(<(Path, u32)>::from(wire), (root() / "b", 7));
+}
+
+#[test]
+fn keyed_map() {
+    let keyed = Keyed {
+        key: root() / "a",
+        item: 21,
+    }
+    .map(|n| n.to_string() + "!");
+    assert_eq!(keyed.key, root() / "a");
+    assert_eq!(keyed.item, "21!");
+}
+
+/// An event that resets the whole of an entry's state.
+struct Reset;
+
+impl Snapshot for Reset {}
+
+#[test]
+fn keyed_snapshot_compaction_key() {
+    let keyed = Keyed {
+        key: root() / "a" / 1,
+        item: Reset,
+    };
+    assert_eq!(keyed.compaction_key(), (root() / "a" / 1).compaction_key());
+}
+
+/// Paths whose items could be mistaken for one another if decoded loosely.
+fn awkward_paths() -> Vec<Path> {
+    vec![
+        root(),
+        root() / "65" / 65,
+        root() / "" / 0 / "0",
+        root() / u64::MAX / "-1" / "1.5",
+        root() / "a/b" / "null",
+    ]
+}
+
+#[test]
+fn keyed_json_round_trip() {
+    for key in awkward_paths() {
+        let keyed = Keyed {
+            key: key.clone(),
+            item: Event::Reset,
+        };
+        let json = serde_json::to_string(&keyed).unwrap();
+        let decoded: Keyed<Event> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.key, key, "{json}");
+        assert!(matches!(decoded.item, Event::Reset));
+    }
+}
+
+#[test]
+fn keyed_cbor_round_trip() {
+    for key in awkward_paths() {
+        let keyed = Keyed {
+            key: key.clone(),
+            item: Event::Tick,
+        };
+        let mut bytes = Cbor.encode(keyed).unwrap();
+        let decoded: Keyed<Event> = Cbor.decode(&mut bytes).unwrap();
+        assert_eq!(decoded.key, key);
+        assert!(matches!(decoded.item, Event::Tick));
+    }
+}
@@ -0,0 +1,106 @@
+pub mod fixtures;
+use edfsm::{Fsm, Input};
+use edfsm_kv_store::{path::root, Keyed, KvStore, Path, Query};
+use fixtures::{Counter, Event};
+use std::sync::{Arc, Mutex};
+
+fn tick(store: &mut KvStore<Counter>, key: Path) {
+    KvStore::<Counter>::on_event(
+        store,
+        &Keyed {
+            key,
+            item: Event::Tick,
+        },
+    );
+}
+
+#[test]
+fn iter_prefix_test() {
+    let mut store = KvStore::<Counter>::default();
+    tick(&mut store, root() / "a");
+    tick(&mut store, root() / "a" / 1);
+    tick(&mut store, root() / "a" / 1);
+    tick(&mut store, root() / "a" / "b" / "c");
+    tick(&mut store, root() / "b" / 1);
+    tick(&mut store, root() / "b" / 1 / 2);
+
+    let prefix = root() / "a";
+    let paths: Vec<&Path> = store.iter_prefix(&prefix).map(|(p, _)| p).collect();
+    assert_eq!(
+        paths,
+        [
+            &(root() / "a"),
+            &(root() / "a" / 1),
+            &(root() / "a" / "b" / "c")
+        ]
+    );
+
+    assert_eq!(store.get(&(root() / "a" / 1)).map(|s| s.count), Some(2));
+    assert!(store.get(&(root() / "c")).is_none());
+    assert_eq!(store.iter_prefix(&root()).count(), 5);
+}
+
+#[test]
+fn get_tree_test() {
+    let mut store = KvStore::<Counter>::default();
+    tick(&mut store, root() / "a" / 1);
+    tick(&mut store, root() / "a" / 1 / "b");
+    tick(&mut store, root() / "a" / 2 / "b");
+
+    // A longer path that follows the prefix, but is outside of it, is excluded
+    let paths = Arc::new(Mutex::new(Vec::new()));
+    let respond = {
+        let paths = paths.clone();
+        Box::new(move |it: &mut dyn Iterator<Item = (&Path, &_)>| {
+            paths.lock().unwrap().extend(it.map(|(p, _)| p.clone()))
+        })
+    };
+    KvStore::<Counter>::step(
+        &mut store,
+        Input::Command(Query::GetTree(root() / "a" / 1, respond)),
+        &mut Default::default(),
+    );
+    assert_eq!(
+        *paths.lock().unwrap(),
+        [root() / "a" / 1, root() / "a" / 1 / "b"]
+    );
+}
+
+#[test]
+fn tree_version_test() {
+    let mut store = KvStore::<Counter>::default();
+    assert_eq!(store.tree_version(&root()), None);
+
+    tick(&mut store, root() / "a" / 1);
+    tick(&mut store, root() / "b" / 1);
+    assert_eq!(store.version(&(root() / "a" / 1)), Some(1));
+    assert_eq!(store.tree_version(&(root() / "a")), Some(1));
+    assert_eq!(store.tree_version(&root()), Some(2));
+
+    // An unchanged subtree retains its version
+    tick(&mut store, root() / "b" / 2);
+    assert_eq!(store.tree_version(&(root() / "a")), Some(1));
+    assert_eq!(store.tree_version(&(root() / "b")), Some(3));
+
+    // A removal increases the version of the enclosing subtrees
+    KvStore::<Counter>::on_event(
+        &mut store,
+        &Keyed {
+            key: root() / "a" / 1,
+            item: Event::Reset,
+        },
+    );
+    assert!(store.get(&(root() / "a" / 1)).is_none());
+    assert_eq!(store.version(&(root() / "a" / 1)), None);
+    assert_eq!(store.tree_version(&(root() / "a")), Some(4));
+    assert_eq!(store.tree_version(&(root() / "a" / 1)), Some(4));
+    assert_eq!(store.tree_version(&(root() / "b")), Some(3));
+    assert_eq!(store.tree_version(&root()), Some(4));
+    assert_eq!(store.tree_version(&(root() / "c")), None);
+
+    // A removed entry that is updated again supersedes its removal
+    tick(&mut store, root() / "a" / 1);
+    assert_eq!(store.version(&(root() / "a" / 1)), Some(5));
+    assert_eq!(store.tree_version(&(root() / "a")), Some(5));
+    assert_eq!(store.tree_version(&root()), Some(5));
+}
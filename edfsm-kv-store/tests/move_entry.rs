@@ -0,0 +1,185 @@
+#![cfg(feature = "tokio")]
+
+pub mod fixtures;
+use edfsm::{Change, Fsm, Input, Terminating};
+use edfsm_kv_store::{
+    path::root, requester, Keyed, KvStore, MoveQuery, Path, Relocatable, Relocating,
+};
+use edfsm_machine::{error::Result, machine, output::OutputBuffer, Machine};
+use std::sync::{Arc, Mutex};
+
+/// Holds the setting of a device.
+struct Device;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Event {
+    Set(u32),
+    Moved(Path),
+}
+
+impl Terminating for Event {
+    fn terminating(&self) -> bool {
+        false
+    }
+}
+
+impl Relocating for Event {
+    fn relocation(from: Path) -> Self {
+        Event::Moved(from)
+    }
+
+    fn relocated_from(&self) -> Option<&Path> {
+        match self {
+            Event::Moved(from) => Some(from),
+            _ => None,
+        }
+    }
+}
+
+impl Fsm for Device {
+    type S = u32;
+    type C = ();
+    type E = Event;
+    type SE = OutputBuffer<()>;
+
+    fn for_command(_s: &Self::S, _c: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
+        None
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        match e {
+            Event::Set(n) => *s = *n,
+            _ => return None,
+        }
+        Some(Change::Updated)
+    }
+
+    fn on_change(_s: &Self::S, _e: &Self::E, _se: &mut Self::SE, _change: Change) {}
+}
+
+type Store = Relocatable<Device>;
+
+fn set(store: &mut KvStore<Device>, key: Path, n: u32) {
+    Store::on_event(
+        store,
+        &Keyed {
+            key,
+            item: Event::Set(n),
+        },
+    );
+}
+
+/// Step a move query, returning whether it was moved and the event emitted.
+fn step_move(store: &mut KvStore<Device>, from: Path, to: Path) -> (bool, Option<Keyed<Event>>) {
+    let moved = Arc::new(Mutex::new(false));
+    let respond = {
+        let moved = moved.clone();
+        Box::new(move |v: Option<&()>| *moved.lock().unwrap() = v.is_some())
+    };
+    let e = Store::step(
+        store,
+        Input::Command(MoveQuery::Move(from, to, respond)),
+        &mut Default::default(),
+    );
+    let moved = *moved.lock().unwrap();
+    (moved, e)
+}
+
+#[test]
+fn move_entry_test() {
+    let mut store = KvStore::default();
+    set(&mut store, root() / "rack" / 1, 10);
+    set(&mut store, root() / "rack" / 2, 20);
+    let before = store.tree_version(&(root() / "rack"));
+
+    // The entry is moved with its state, as one event for the destination
+    let (moved, e) = step_move(&mut store, root() / "rack" / 1, root() / "shelf" / 1);
+    assert!(moved);
+    let e = e.unwrap();
+    assert_eq!(e.key, root() / "shelf" / 1);
+    assert_eq!(e.item, Event::Moved(root() / "rack" / 1));
+    assert_eq!(store.get(&(root() / "shelf" / 1)), Some(&10));
+    assert!(store.get(&(root() / "rack" / 1)).is_none());
+    assert_eq!(store.len(), 2);
+
+    // Both subtrees reflect the move in their versions
+    assert!(store.tree_version(&(root() / "rack")) > before);
+    assert!(store.version(&(root() / "shelf" / 1)) > before);
+
+    // Replaying the event reproduces the move
+    let mut replica = KvStore::default();
+    set(&mut replica, root() / "rack" / 1, 10);
+    assert!(matches!(
+        Store::on_event(&mut replica, &e),
+        Some(Change::Transitioned)
+    ));
+    assert_eq!(replica.get(&(root() / "shelf" / 1)), Some(&10));
+    assert!(replica.get(&(root() / "rack" / 1)).is_none());
+}
+
+#[test]
+fn move_entry_collision() {
+    let mut store = KvStore::default();
+    set(&mut store, root() / "rack" / 1, 10);
+    set(&mut store, root() / "rack" / 2, 20);
+
+    // An extant destination is not overwritten
+    let (moved, e) = step_move(&mut store, root() / "rack" / 1, root() / "rack" / 2);
+    assert!(!moved);
+    assert!(e.is_none());
+    assert_eq!(store.get(&(root() / "rack" / 1)), Some(&10));
+    assert_eq!(store.get(&(root() / "rack" / 2)), Some(&20));
+
+    // Nor is it by a move event received for it
+    let e = Keyed {
+        key: root() / "rack" / 2,
+        item: Event::Moved(root() / "rack" / 1),
+    };
+    assert!(Store::on_event(&mut store, &e).is_none());
+    assert_eq!(store.get(&(root() / "rack" / 2)), Some(&20));
+
+    // An entry is not moved to itself
+    let (moved, e) = step_move(&mut store, root() / "rack" / 1, root() / "rack" / 1);
+    assert!(!moved);
+    assert!(e.is_none());
+    assert_eq!(store.get(&(root() / "rack" / 1)), Some(&10));
+}
+
+#[test]
+fn move_entry_missing_source() {
+    let mut store = KvStore::default();
+    set(&mut store, root() / "rack" / 2, 20);
+
+    // Nothing is moved and no event is emitted
+    let version = store.tree_version(&root());
+    let (moved, e) = step_move(&mut store, root() / "rack" / 1, root() / "shelf" / 1);
+    assert!(!moved);
+    assert!(e.is_none());
+    assert!(store.get(&(root() / "shelf" / 1)).is_none());
+    assert_eq!(store.tree_version(&root()), version);
+}
+
+#[tokio::test]
+async fn move_entry_requester() -> Result<()> {
+    let store = machine::<Store>();
+    let mut r = requester(store.input());
+    let task = tokio::spawn(store.task_with_state());
+
+    r.queries()
+        .upsert(root() / "rack" / 1, |_| Event::Set(10))
+        .await?;
+    assert!(
+        r.move_entry(root() / "rack" / 1, root() / "shelf" / 1)
+            .await?
+    );
+    assert!(
+        !r.move_entry(root() / "rack" / 1, root() / "shelf" / 1)
+            .await?
+    );
+    drop(r);
+
+    let (store, _) = task.await.unwrap()?;
+    assert_eq!(store.get(&(root() / "shelf" / 1)), Some(&10));
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "tokio")]
+
+use edfsm::{Change, Fsm, Input, Terminating};
+use edfsm_kv_store::{path::root, requester, Keyed, KvStore, Path};
+use edfsm_machine::{error::Result, machine, output::OutputBuffer, In, Machine};
+use tokio::{sync::mpsc::Sender, task::JoinSet};
+
+/// Records the events applied to an entry, in order.
+struct Recorder;
+
+#[derive(Debug, Clone)]
+struct Append {
+    sender: usize,
+    seq: usize,
+}
+
+impl Terminating for Append {
+    fn terminating(&self) -> bool {
+        false
+    }
+}
+
+impl Fsm for Recorder {
+    type S = Vec<(usize, usize)>;
+    type C = ();
+    type E = Append;
+    type SE = OutputBuffer<()>;
+
+    fn for_command(_s: &Self::S, _c: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
+        None
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        s.push((e.sender, e.seq));
+        Some(Change::Updated)
+    }
+
+    fn on_change(_s: &Self::S, _e: &Self::E, _se: &mut Self::SE, _change: Change) {}
+}
+
+type Store = KvStore<Recorder>;
+
+const SENDERS: usize = 4;
+const PATHS: usize = 5;
+const EVENTS: usize = 500;
+
+fn path(n: usize) -> Path {
+    root() / "entry" / n as u64
+}
+
+/// Send events to all paths in turn, numbering them in send order.
+async fn producer(sender: Sender<In<Store>>, id: usize) -> Result<()> {
+    for seq in 0..EVENTS {
+        let event = Append { sender: id, seq };
+        sender
+            .send(Input::Event(Keyed {
+                key: path((seq + id) % PATHS),
+                item: event,
+            }))
+            .await?;
+        if seq % 7 == 0 {
+            tokio::task::yield_now().await;
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn ordering_test() -> Result<()> {
+    let machine = machine::<Store>();
+    let mut r = requester(machine.input());
+
+    let mut producers = JoinSet::new();
+    for id in 0..SENDERS {
+        producers.spawn(producer(machine.input(), id));
+    }
+    let task = tokio::spawn(machine.task());
+    for result in producers.join_all().await {
+        result?;
+    }
+
+    for n in 0..PATHS {
+        let seen = r.get(path(n), |s| s.cloned()).await?.unwrap();
+
+        // Each sender's events are seen in the order sent
+        for id in 0..SENDERS {
+            let expected: Vec<_> = (0..EVENTS).filter(|seq| (seq + id) % PATHS == n).collect();
+            let actual: Vec<_> = seen
+                .iter()
+                .filter(|(sender, _)| *sender == id)
+                .map(|(_, seq)| *seq)
+                .collect();
+            assert_eq!(actual, expected);
+        }
+    }
+
+    drop(r);
+    task.await.unwrap()
+}
@@ -0,0 +1,91 @@
+use edfsm::{Change, Drain, Fsm, Input, Terminating};
+use edfsm_kv_store::{path::root, Keyed, KvStore, PerPath, Query};
+use edfsm_machine::output::OutputBuffer;
+
+/// Counts ticks, reporting every second tick seen by its effector.
+struct Pairs;
+
+#[derive(Debug, Default)]
+struct State;
+
+#[derive(Debug, Clone)]
+enum Event {
+    Tick,
+    Remove,
+}
+
+impl Terminating for Event {
+    fn terminating(&self) -> bool {
+        matches!(self, Event::Remove)
+    }
+}
+
+/// An effector holding state for a single entry.
+#[derive(Default)]
+struct Effector {
+    ticks: u32,
+    output: OutputBuffer<u32>,
+}
+
+impl Drain for Effector {
+    type Item = u32;
+
+    fn drain_all(&mut self) -> impl Iterator<Item = Self::Item> + Send {
+        self.output.drain_all()
+    }
+}
+
+impl Fsm for Pairs {
+    type S = State;
+    type C = ();
+    type E = Event;
+    type SE = Effector;
+
+    fn for_command(_s: &Self::S, _c: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
+        None
+    }
+
+    fn on_event(_s: &mut Self::S, _e: &Self::E) -> Option<Change> {
+        Some(Change::Updated)
+    }
+
+    fn on_change(_s: &Self::S, _e: &Self::E, se: &mut Self::SE, _change: Change) {
+        se.ticks += 1;
+        if se.ticks % 2 == 0 {
+            se.output.push(se.ticks);
+        }
+    }
+}
+
+type Store = KvStore<Pairs, PerPath<Effector>>;
+
+fn input(key: &'static str, item: Event) -> Input<Query<State, Event>, Keyed<Event>> {
+    Input::Event(Keyed {
+        key: root() / key,
+        item,
+    })
+}
+
+#[test]
+fn per_path_test() {
+    let mut store = Store::default();
+    let mut se = PerPath::default();
+
+    // Interleaved ticks are counted separately for each entry
+    Store::step(&mut store, input("a", Event::Tick), &mut se);
+    Store::step(&mut store, input("b", Event::Tick), &mut se);
+    Store::step(&mut store, input("a", Event::Tick), &mut se);
+    let output: Vec<_> = se.drain_all().map(|o| (o.key, o.item)).collect();
+    assert_eq!(output, [(root() / "a", 2)]);
+
+    Store::step(&mut store, input("b", Event::Tick), &mut se);
+    let output: Vec<_> = se.drain_all().map(|o| (o.key, o.item)).collect();
+    assert_eq!(output, [(root() / "b", 2)]);
+    assert_eq!(se.get(&(root() / "a")).unwrap().ticks, 2);
+
+    // Removing an entry drops its effector
+    Store::step(&mut store, input("a", Event::Remove), &mut se);
+    assert!(se.get(&(root() / "a")).is_none());
+    Store::step(&mut store, input("a", Event::Tick), &mut se);
+    assert_eq!(se.get(&(root() / "a")).unwrap().ticks, 1);
+}
@@ -0,0 +1,35 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_kv_store::{path::root, Keyed, Query, SerializableInput};
+use fixtures::{Event, State};
+
+type In = Input<Query<State, Event>, Keyed<Event>>;
+
+#[test]
+fn serializable_input_test() {
+    let input: In = Input::Event(Keyed {
+        key: root() / "a" / 1,
+        item: Event::Tick,
+    });
+    let Ok(wire) = SerializableInput::try_from(input) else {
+        panic!("an event is serializable")
+    };
+    let json = serde_json::to_string(&wire).unwrap();
+    assert_eq!(json, r#"{"key":["a",1],"item":"Tick"}"#);
+
+    let wire: SerializableInput<Event> = serde_json::from_str(&json).unwrap();
+    let input: In = wire.into();
+    assert!(matches!(
+        input,
+        Input::Event(Keyed {
+            item: Event::Tick,
+            ..
+        })
+    ));
+
+    let input: In = Input::Command(Query::GetAll(Box::new(|_| ())));
+    assert!(matches!(
+        SerializableInput::try_from(input),
+        Err(Query::GetAll(_))
+    ));
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "tokio")]
+
+use edfsm_kv_store::{path::root, Keyed, Path, ShardedAdapter};
+use edfsm_machine::{adapter::Adapter, error::Result};
+use std::collections::{BTreeMap, BTreeSet};
+use tokio::sync::mpsc::channel;
+
+#[tokio::test]
+async fn sharded_test() -> Result<()> {
+    let (senders, mut receivers): (Vec<_>, Vec<_>) = (0..3).map(|_| channel(100)).unzip();
+    let mut sharded = ShardedAdapter::new(senders);
+
+    // Interleave a sequence of events for each of several paths
+    for n in 0..4 {
+        for key in ["a", "b", "c", "d", "e", "f"] {
+            sharded.notify(Keyed::from((root() / key, n))).await?;
+        }
+    }
+    drop(sharded);
+
+    // Each path is seen by one shard only, with its events in order
+    let mut shards: BTreeMap<Path, usize> = BTreeMap::new();
+    let mut events: BTreeMap<Path, Vec<i32>> = BTreeMap::new();
+    for (i, receiver) in receivers.iter_mut().enumerate() {
+        while let Some(Keyed { key, item }) = receiver.recv().await {
+            assert_eq!(*shards.entry(key.clone()).or_insert(i), i);
+            events.entry(key).or_default().push(item);
+        }
+    }
+    assert_eq!(events.len(), 6);
+    assert!(events.values().all(|e| e == &[0, 1, 2, 3]));
+
+    // The paths are divided between more than one shard
+    let used: BTreeSet<usize> = shards.into_values().collect();
+    assert!(used.len() > 1);
+    Ok(())
+}
@@ -0,0 +1,69 @@
+pub mod fixtures;
+use edfsm::Fsm;
+use edfsm_kv_store::{path::root, Keyed, KvStore, Path};
+use edfsm_machine::versioned::Versioned;
+use fixtures::{Counter, Event, State};
+use serde::{Deserialize, Serialize};
+use streambed_codec::{Cbor, Codec};
+
+fn tick(store: &mut KvStore<Counter>, key: Path) {
+    KvStore::<Counter>::on_event(
+        store,
+        &Keyed {
+            key,
+            item: Event::Tick,
+        },
+    );
+}
+
+fn counts(store: &KvStore<Counter>) -> Vec<(Path, i32)> {
+    store
+        .to_snapshot()
+        .into_iter()
+        .map(|(p, s)| (p, s.count))
+        .collect()
+}
+
+#[test]
+fn snapshot_test() {
+    let mut store = KvStore::<Counter>::default();
+    tick(&mut store, root() / "b");
+    tick(&mut store, root() / "a" / 1);
+    tick(&mut store, root() / "a" / 1);
+
+    // Round trip through a blob
+    let codec = Versioned::new(Cbor);
+    let mut blob = codec.encode(store.to_snapshot()).unwrap();
+    let restored = KvStore::<Counter>::from_snapshot(codec.decode(&mut blob).unwrap());
+
+    assert_eq!(
+        counts(&restored),
+        [(root() / "a" / 1, 2), (root() / "b", 1)]
+    );
+    assert_eq!(restored.tree_version(&root()), Some(2));
+}
+
+/// An earlier version of the state, before the count was renamed.
+#[derive(Serialize, Deserialize)]
+struct StateV1 {
+    ticks: i32,
+}
+
+#[test]
+fn snapshot_migration_test() {
+    // A snapshot of the earlier state type, encoded as version 1
+    let old = vec![(root() / "a", StateV1 { ticks: 3 })];
+    let mut blob = Cbor.encode(old).unwrap();
+    blob.insert(0, 1);
+
+    let codec = Versioned::new(Cbor).with_migration(1, |bytes| {
+        let old: Vec<(Path, StateV1)> = Cbor.decode(bytes)?;
+        Some(
+            old.into_iter()
+                .map(|(p, s)| (p, State { count: s.ticks }))
+                .collect::<Vec<_>>(),
+        )
+    });
+    let restored = KvStore::<Counter>::from_snapshot(codec.decode(&mut blob).unwrap());
+    assert_eq!(counts(&restored), [(root() / "a", 3)]);
+}
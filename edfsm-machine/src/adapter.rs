@@ -1,5 +1,10 @@
+#[cfg(feature = "tokio")]
+use crate::clock::TokioClock;
 use crate::error::Result;
-use core::{future::Future, marker::PhantomData};
+use core::{future::Future, marker::PhantomData, pin::pin, time::Duration};
+use edfsm::Clock;
+pub use futures_util::future::Either;
+use futures_util::{Stream, StreamExt};
 
 /// A trait to intercept messages in a `Machine` for logging and outbound communication.
 ///
@@ -24,6 +29,39 @@ pub trait Adapter: Send {
         self.notify(a.clone())
     }
 
+    /// Forward any items held by this adapter, such as a buffering adapter accumulates.
+    /// A machine flushes its adapters when it stops.
+    /// Adapters that forward each item as it is notified hold nothing, and the default
+    /// does nothing. Combinators flush the adapters they contain.
+    fn flush(&mut self) -> impl Future<Output = Result<()>> + Send {
+        async { Ok(()) }
+    }
+
+    /// This adapter discards all items, and so is not connected to anything.
+    /// Only the `Placeholder` implementation returns true, and `None`
+    /// or an optional placeholder.
+    fn is_placeholder(&self) -> bool {
+        false
+    }
+
+    /// Forward each item of a stream, in order, until the stream ends.
+    /// Each notification completes before the next item is taken from the stream,
+    /// so backpressure from this adapter is applied to the source. The first error ends the loop.
+    fn notify_all<S>(&mut self, items: S) -> impl Future<Output = Result<()>> + Send
+    where
+        S: Stream<Item = Self::Item> + Send,
+        Self: Sized,
+        Self::Item: Send + 'static,
+    {
+        async move {
+            let mut items = pin!(items);
+            while let Some(a) = items.next().await {
+                self.notify(a).await?;
+            }
+            Ok(())
+        }
+    }
+
     /// Combine this with another adapter. The notify call is delegated to both adapters.
     fn merge<T>(self, other: T) -> impl Adapter<Item = Self::Item>
     where
@@ -37,6 +75,44 @@ pub trait Adapter: Send {
         }
     }
 
+    /// Combine this with a fallback adapter. The notify call is delegated to this
+    /// adapter and, only if that returns an error, to the fallback with the same item.
+    /// The error from the fallback, if any, is returned.
+    fn or_else<T>(self, fallback: T) -> impl Adapter<Item = Self::Item>
+    where
+        T: Adapter<Item = Self::Item>,
+        Self: Sized + Send,
+        Self::Item: Send + Clone,
+    {
+        OrElse {
+            primary: self,
+            fallback,
+        }
+    }
+
+    /// Create an adapter that routes each item to one of two adapters.
+    /// The classifier converts an item to `Either::Left` for this adapter,
+    /// or `Either::Right` for the other adapter, which may have a different item type.
+    fn partition<A, B, T>(
+        self,
+        classifier: impl Fn(A) -> Either<Self::Item, B> + Send,
+        other: T,
+    ) -> impl Adapter<Item = A>
+    where
+        T: Adapter<Item = B>,
+        Self: Sized + Send,
+        Self::Item: Send + 'static,
+        A: Send,
+        B: Send + 'static,
+    {
+        Partition {
+            func: classifier,
+            left: self,
+            right: other,
+            marker: PhantomData,
+        }
+    }
+
     /// Create an adapter that maps items with an optional function.
     /// `Some` values are passed on, analogous to `Iterator::filter_map`.
     fn with_filter_map<A>(
@@ -55,6 +131,26 @@ pub trait Adapter: Send {
         }
     }
 
+    /// Create an adapter that maps items with an optional asynchronous function.
+    /// `Some` values are passed on, as for `with_filter_map`.
+    ///
+    /// The function is awaited within `notify`, and so within the machine's task.
+    /// The machine processes no further input until it completes, so a slow
+    /// function, such as a lookup over a network, stalls the machine.
+    fn with_then<A, Fut>(self, func: impl Fn(A) -> Fut + Send) -> impl Adapter<Item = A>
+    where
+        Fut: Future<Output = Option<Self::Item>> + Send,
+        Self: Sized + Send,
+        Self::Item: Send + 'static,
+        A: Send,
+    {
+        Then {
+            func,
+            inner: self,
+            marker: PhantomData,
+        }
+    }
+
     /// Create an adapter that maps each item with a function.
     fn with_map<A>(self, func: impl Fn(A) -> Self::Item + Send) -> impl Adapter<Item = A>
     where
@@ -65,6 +161,21 @@ pub trait Adapter: Send {
         self.with_filter_map(move |a| Some(func(a)))
     }
 
+    /// Create an adapter that pairs each item with the wall-clock time it is notified.
+    ///
+    /// This suits audit logs of items that do not carry the time themselves. The item
+    /// type of this adapter becomes the pair, and so any codec downstream must encode it.
+    /// Wall-clock time can step backwards, say when the clock is adjusted, so times are
+    /// not guaranteed to be ordered.
+    #[cfg(feature = "std")]
+    fn with_timestamp<A>(self) -> Timestamped<Self>
+    where
+        Self: Adapter<Item = (std::time::SystemTime, A)> + Sized + Send,
+        A: Send + 'static,
+    {
+        Timestamped(self)
+    }
+
     /// Create an adapter that converts each item from another type.
     /// This relies on an `Into` implementation for the conversion.
     fn adapt<A>(self) -> impl Adapter<Item = A>
@@ -111,6 +222,10 @@ where
         Ok(())
     }
 
+    fn is_placeholder(&self) -> bool {
+        true
+    }
+
     /// Ignore the reference and avoid the clone.
     #[allow(clippy::manual_async_fn)]
     fn clone_notify(&mut self, _a: &Self::Item) -> impl Future<Output = Result<()>> + Send {
@@ -148,6 +263,126 @@ where
         self.first.notify(a.clone()).await?;
         self.next.notify(a).await
     }
+
+    async fn flush(&mut self) -> Result<()> {
+        let first = self.first.flush().await;
+        let next = self.next.flush().await;
+        first.and(next)
+    }
+}
+
+/// An `Adapter` that notifies a fallback adapter when the primary adapter fails.
+#[derive(Debug)]
+pub struct OrElse<S, T> {
+    primary: S,
+    fallback: T,
+}
+
+impl<A, S, T> Adapter for OrElse<S, T>
+where
+    S: Adapter<Item = A> + Send,
+    T: Adapter<Item = A> + Send,
+    A: Send + Clone,
+{
+    type Item = A;
+
+    async fn notify(&mut self, a: Self::Item) -> Result<()>
+    where
+        Self::Item: 'static,
+    {
+        match self.primary.notify(a.clone()).await {
+            Ok(()) => Ok(()),
+            Err(_) => self.fallback.notify(a).await,
+        }
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        let primary = self.primary.flush().await;
+        let fallback = self.fallback.flush().await;
+        primary.and(fallback)
+    }
+}
+
+/// An `Adapter` that routes each item to one of two adapters by a classifier.
+#[derive(Debug)]
+pub struct Partition<A, F, S, T> {
+    func: F,
+    left: S,
+    right: T,
+    marker: PhantomData<A>,
+}
+
+impl<A, B, C, F, S, T> Adapter for Partition<A, F, S, T>
+where
+    F: Fn(A) -> Either<B, C> + Send,
+    S: Adapter<Item = B> + Send,
+    T: Adapter<Item = C> + Send,
+    A: Send,
+    B: Send + 'static,
+    C: Send + 'static,
+{
+    type Item = A;
+
+    async fn notify(&mut self, a: Self::Item) -> Result<()>
+    where
+        Self::Item: 'static,
+    {
+        match (self.func)(a) {
+            Either::Left(b) => self.left.notify(b).await,
+            Either::Right(c) => self.right.notify(c).await,
+        }
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        let left = self.left.flush().await;
+        let right = self.right.flush().await;
+        left.and(right)
+    }
+}
+
+/// An optional `Adapter`, which forwards items when `Some` and discards them when `None`.
+/// This suits wiring in which a destination is configured conditionally.
+impl<T> Adapter for Option<T>
+where
+    T: Adapter,
+    T::Item: Send,
+{
+    type Item = T::Item;
+
+    async fn notify(&mut self, a: Self::Item) -> Result<()>
+    where
+        Self::Item: 'static,
+    {
+        match self {
+            Some(inner) => inner.notify(a).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Clone the item only if there is an adapter to forward it to.
+    fn clone_notify(&mut self, a: &Self::Item) -> impl Future<Output = Result<()>> + Send
+    where
+        Self::Item: Clone + 'static,
+    {
+        let a = self.is_some().then(|| a.clone());
+        async move {
+            match (self, a) {
+                (Some(inner), Some(a)) => inner.notify(a).await,
+                _ => Ok(()),
+            }
+        }
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        match self {
+            Some(inner) => inner.flush().await,
+            None => Ok(()),
+        }
+    }
+
+    fn is_placeholder(&self) -> bool {
+        self.as_ref().is_none_or(T::is_placeholder)
+    }
 }
 
 /// An `Adapter` that passes each item through an optional function
@@ -177,6 +412,158 @@ where
         }
         Ok(())
     }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner.flush().await
+    }
+}
+
+/// An `Adapter` that passes each item through an optional asynchronous function
+/// and passes the `Some` values on.
+#[derive(Debug)]
+pub struct Then<A, F, G> {
+    func: F,
+    inner: G,
+    marker: PhantomData<A>,
+}
+
+impl<F, Fut, G, A, B> Adapter for Then<A, F, G>
+where
+    F: Fn(A) -> Fut + Send,
+    Fut: Future<Output = Option<B>> + Send,
+    B: Send + 'static,
+    G: Adapter<Item = B> + Send,
+    A: Send,
+{
+    type Item = A;
+
+    async fn notify(&mut self, a: Self::Item) -> Result<()>
+    where
+        Self::Item: 'static,
+    {
+        if let Some(b) = (self.func)(a).await {
+            self.inner.notify(b).await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner.flush().await
+    }
+}
+
+/// An `Adapter` that retries failed notifications with exponential backoff.
+///
+/// A notification is attempted up to a number of times. Between attempts the delay
+/// doubles, starting from an initial delay, and the total of delays is bounded so
+/// that a failing adapter stalls the machine for a limited time. The last error
+/// is returned once attempts or time are exhausted.
+///
+/// Delays are timed by a `Clock`, which is tokio's unless given by `with_clock`.
+#[derive(Debug)]
+pub struct Retry<T, K> {
+    inner: T,
+    attempts: u32,
+    initial: Duration,
+    limit: Duration,
+    clock: K,
+}
+
+#[cfg(feature = "tokio")]
+impl<T> Retry<T, TokioClock> {
+    /// Retry notifications to `inner` up to a number of attempts in total.
+    /// The initial delay is 10ms and the total delay is bounded to 1s.
+    pub fn new(inner: T, attempts: u32) -> Self {
+        Self::with_clock(inner, attempts, TokioClock)
+    }
+}
+
+impl<T, K> Retry<T, K> {
+    /// Retry notifications to `inner` up to a number of attempts in total,
+    /// timing delays with the given clock.
+    /// The initial delay is 10ms and the total delay is bounded to 1s.
+    pub fn with_clock(inner: T, attempts: u32, clock: K) -> Self {
+        Self {
+            inner,
+            attempts,
+            initial: Duration::from_millis(10),
+            limit: Duration::from_secs(1),
+            clock,
+        }
+    }
+
+    /// Set the initial delay and the bound on the total delay for a notification.
+    pub fn with_backoff(self, initial: Duration, limit: Duration) -> Self {
+        Self {
+            initial,
+            limit,
+            ..self
+        }
+    }
+
+    /// Recover the inner adapter.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<A, T, K> Adapter for Retry<T, K>
+where
+    T: Adapter<Item = A>,
+    K: Clock + Send + Sync,
+    A: Clone + Send + Sync,
+{
+    type Item = A;
+
+    async fn notify(&mut self, a: Self::Item) -> Result<()>
+    where
+        Self::Item: 'static,
+    {
+        let mut delay = self.initial;
+        let mut total = Duration::ZERO;
+        let mut attempt = 1;
+        loop {
+            match self.inner.notify(a.clone()).await {
+                Err(_) if attempt < self.attempts && total + delay <= self.limit => {
+                    self.clock.sleep(delay).await;
+                    total += delay;
+                    delay *= 2;
+                    attempt += 1;
+                }
+                r => return r,
+            }
+        }
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner.flush().await
+    }
+}
+
+/// An `Adapter` that pairs each item with the wall-clock time it is notified
+/// and passes the pair on. See `Adapter::with_timestamp`.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct Timestamped<T>(T);
+
+#[cfg(feature = "std")]
+impl<A, T> Adapter for Timestamped<T>
+where
+    T: Adapter<Item = (std::time::SystemTime, A)> + Send,
+    A: Send + 'static,
+{
+    type Item = A;
+
+    async fn notify(&mut self, a: Self::Item) -> Result<()>
+    where
+        Self::Item: 'static,
+    {
+        self.0.notify((std::time::SystemTime::now(), a)).await
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.0.flush().await
+    }
 }
 
 /// Implement `Adapter` for a vector
@@ -216,7 +603,7 @@ where
 #[cfg(feature = "tokio")]
 pub mod adapt_tokio {
     use crate::{adapter::Adapter, error::Result};
-    use tokio::sync::{broadcast, mpsc};
+    use tokio::sync::{broadcast, mpsc, watch};
 
     impl<A> Adapter for mpsc::Sender<A>
     where
@@ -241,6 +628,110 @@ pub mod adapt_tokio {
             Ok(())
         }
     }
+
+    /// A watch channel holds only the latest message, for outputs such as a gauge or status
+    /// where earlier values are of no interest. Each message replaces the last without
+    /// waiting, so a slow receiver cannot stall the machine, and messages that a receiver
+    /// does not observe in time are coalesced. Messages are accepted whether or not there
+    /// are receivers.
+    ///
+    /// A watch channel is created with an initial value. Where the message type has none,
+    /// create the channel with `watch::channel(None)` and connect `sender.with_map(Some)`.
+    impl<A> Adapter for watch::Sender<A>
+    where
+        A: Send + Sync,
+    {
+        type Item = A;
+
+        async fn notify(&mut self, a: Self::Item) -> Result<()> {
+            self.send_replace(a);
+            Ok(())
+        }
+    }
+
+    /// Forward each message from a broadcast subscription to an adapter until the channel is closed.
+    ///
+    /// If the receiver falls behind, messages are dropped by the channel and `Error::Lagged`
+    /// is returned with the number dropped. This is not recoverable where the messages are
+    /// events for a state machine, as its state would no longer reflect its history.
+    /// Instead, discard the state and rehydrate it in full from the durable event log
+    /// e.g. by restarting the machine with its `with_event_log`, then subscribe afresh.
+    pub async fn forward<A>(
+        mut receiver: broadcast::Receiver<A>,
+        output: &mut impl Adapter<Item = A>,
+    ) -> Result<()>
+    where
+        A: Clone + Send + 'static,
+    {
+        loop {
+            match receiver.recv().await {
+                Ok(a) => output.notify(a).await?,
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+/// Implementations of `Adapter` for the channels of the standard library, for synchronous consumers.
+#[cfg(feature = "std")]
+pub mod adapt_std {
+    use crate::{adapter::Adapter, error::Result};
+    use std::sync::mpsc::Sender;
+
+    /// A standard channel is unbounded, and so sending never blocks the machine.
+    /// This suits a consumer on a thread of its own, which receives with the blocking `recv`.
+    /// Sending fails with `Error::ChannelClosed` once the receiver is dropped.
+    impl<A> Adapter for Sender<A>
+    where
+        A: Send,
+    {
+        type Item = A;
+
+        async fn notify(&mut self, a: Self::Item) -> Result<()> {
+            self.send(a)?;
+            Ok(())
+        }
+    }
+}
+
+/// Implementations of `Adapter` for `async-channel`, as used by async-std and smol.
+#[cfg(feature = "async-channel")]
+pub mod adapt_async_channel {
+    use crate::{adapter::Adapter, error::Result};
+    use async_channel::Sender;
+
+    impl<A> Adapter for Sender<A>
+    where
+        A: Send,
+    {
+        type Item = A;
+
+        async fn notify(&mut self, a: Self::Item) -> Result<()> {
+            self.send(a).await?;
+            Ok(())
+        }
+    }
+}
+
+/// Implementations of `Adapter` for `futures-channel`, for interop without tokio.
+#[cfg(feature = "futures-channel")]
+pub mod adapt_futures_channel {
+    use crate::{adapter::Adapter, error::Result};
+    use futures_channel::mpsc::Sender;
+    use futures_util::SinkExt;
+
+    impl<A> Adapter for Sender<A>
+    where
+        A: Send,
+    {
+        type Item = A;
+
+        async fn notify(&mut self, a: Self::Item) -> Result<()> {
+            self.send(a).await?;
+            Ok(())
+        }
+    }
 }
 
 /// A source of messages that can `feed` an `Adapter`.
@@ -252,6 +743,59 @@ pub trait Feed {
         &self,
         output: &mut impl Adapter<Item = Self::Item>,
     ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Send a stream of messages into an adapter, stopping at the next message
+    /// once the token is cancelled. In that case `Error::Cancelled` is returned,
+    /// and the adapter will have received a prefix of the messages.
+    #[cfg(feature = "tokio")]
+    fn feed_cancellable(
+        &self,
+        output: &mut impl Adapter<Item = Self::Item>,
+        cancel: tokio_util::sync::CancellationToken,
+    ) -> impl Future<Output = Result<()>> + Send
+    where
+        Self: Sync,
+        Self::Item: Send,
+    {
+        async move {
+            let mut output = Cancellable {
+                inner: output,
+                cancel,
+            };
+            self.feed(&mut output).await
+        }
+    }
+}
+
+/// An `Adapter` that fails with `Error::Cancelled` once its token is cancelled.
+#[cfg(feature = "tokio")]
+struct Cancellable<'a, T> {
+    inner: &'a mut T,
+    cancel: tokio_util::sync::CancellationToken,
+}
+
+#[cfg(feature = "tokio")]
+impl<T> Adapter for Cancellable<'_, T>
+where
+    T: Adapter,
+    T::Item: Send,
+{
+    type Item = T::Item;
+
+    async fn notify(&mut self, a: Self::Item) -> Result<()>
+    where
+        Self::Item: 'static,
+    {
+        if self.cancel.is_cancelled() {
+            Err(crate::error::Error::Cancelled)
+        } else {
+            self.inner.notify(a).await
+        }
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner.flush().await
+    }
 }
 
 impl<A> Feed for Placeholder<A>
@@ -266,6 +810,9 @@ where
     }
 }
 
+#[cfg(feature = "streambed")]
+pub use adapt_streambed::{CompactedLog, Offset, PartialProduce, ProduceAll, TailingLog};
+
 /// Implementations of `Adapter` for streambed
 #[cfg(feature = "streambed")]
 mod adapt_streambed {
@@ -273,8 +820,70 @@ mod adapt_streambed {
         adapter::{Adapter, Feed},
         error::Result,
     };
-    use futures_util::StreamExt;
-    use streambed_codec::{Codec, CommitLog, LogAdapter};
+    use core::{future::Future, marker::PhantomData};
+    use edfsm::CompactionKey;
+    use futures_util::{future::ready, Stream, StreamExt};
+    use std::vec::Vec;
+    use streambed::commit_log::{ProducerRecord, Subscription, Topic};
+    use streambed_codec::{Codec, CommitLog, LogAdapter, ProducerError};
+
+    /// An offset in a commit log.
+    pub type Offset = u64;
+
+    /// The failure of `ProduceAll::produce_all` part way through a batch.
+    #[derive(Debug)]
+    pub struct PartialProduce {
+        /// The offsets of the items produced before the failure, in order.
+        pub offsets: Vec<Offset>,
+        /// The error producing the next item.
+        pub error: ProducerError,
+    }
+
+    /// Produce a batch of items to an event log.
+    pub trait ProduceAll {
+        type Item;
+
+        /// Encode and produce each item in order, returning their offsets.
+        ///
+        /// Items are produced one after another, as a `CommitLog` accepts one record at
+        /// a time, but without the overhead of a channel or task per item. Production
+        /// stops at the first failure. The items before it have been produced, and their
+        /// offsets are returned with the error, while the items after it have not.
+        fn produce_all<I>(
+            &self,
+            items: I,
+        ) -> impl Future<Output = core::result::Result<Vec<Offset>, PartialProduce>> + Send
+        where
+            I: IntoIterator<Item = Self::Item> + Send,
+            I::IntoIter: Send;
+    }
+
+    impl<L, C, A> ProduceAll for LogAdapter<L, C, A>
+    where
+        C: Codec<A> + Sync + Send,
+        L: CommitLog + Sync + Send,
+        A: Send + Sync + 'static,
+    {
+        type Item = A;
+
+        async fn produce_all<I>(
+            &self,
+            items: I,
+        ) -> core::result::Result<Vec<Offset>, PartialProduce>
+        where
+            I: IntoIterator<Item = Self::Item> + Send,
+            I::IntoIter: Send,
+        {
+            let mut offsets = Vec::new();
+            for item in items {
+                match self.produce(item).await {
+                    Ok(offset) => offsets.push(offset),
+                    Err(error) => return Err(PartialProduce { offsets, error }),
+                }
+            }
+            Ok(offsets)
+        }
+    }
 
     impl<L, C, A> Feed for LogAdapter<L, C, A>
     where
@@ -285,11 +894,7 @@ mod adapt_streambed {
         type Item = A;
 
         async fn feed(&self, output: &mut impl Adapter<Item = Self::Item>) -> Result<()> {
-            let mut s = self.history().await;
-            while let Some(a) = s.next().await {
-                output.notify(a).await?;
-            }
-            Ok(())
+            output.notify_all(self.history().await).await
         }
     }
 
@@ -309,4 +914,184 @@ mod adapt_streambed {
             Ok(())
         }
     }
+
+    /// An event log that keys each record by `CompactionKey`, for use with a commit log
+    /// that is compacted to retain the latest record for each key.
+    ///
+    /// This is used in the same way as the `LogAdapter` of a `CommitLog`, which
+    /// produces every record with the same key.
+    ///
+    /// Once compacted, only the latest event for each key is replayed. This is only
+    /// correct when that event alone recovers the state it applies to, such as a complete
+    /// snapshot of the state. Events that update part of a state are otherwise lost.
+    #[derive(Debug)]
+    pub struct CompactedLog<L, C, A> {
+        commit_log: L,
+        codec: C,
+        topic: Topic,
+        marker: PhantomData<A>,
+    }
+
+    impl<L, C, A> CompactedLog<L, C, A> {
+        /// Specialize a commit log for items of type `A`, given the topic
+        /// and a `Codec` for encoding and decoding the items.
+        pub fn new(commit_log: L, topic: impl Into<Topic>, codec: C) -> Self {
+            Self {
+                commit_log,
+                codec,
+                topic: topic.into(),
+                marker: PhantomData,
+            }
+        }
+    }
+
+    impl<L, C, A> CompactedLog<L, C, A>
+    where
+        C: Codec<A>,
+        L: CommitLog,
+        A: CompactionKey,
+    {
+        /// Send one item to the underlying commit log, keyed by its compaction key.
+        pub async fn produce(&self, item: A) -> core::result::Result<Offset, ProducerError> {
+            let key = item.compaction_key();
+            let value = self
+                .codec
+                .encode(item)
+                .ok_or(ProducerError::CannotProduce)?;
+            self.commit_log
+                .produce(ProducerRecord {
+                    topic: self.topic.clone(),
+                    headers: Vec::new(),
+                    timestamp: None,
+                    key,
+                    value,
+                    partition: 0,
+                })
+                .await
+                .map(|r| r.offset)
+        }
+    }
+
+    impl<L, C, A> Feed for CompactedLog<L, C, A>
+    where
+        C: Codec<A> + Sync + Send,
+        L: CommitLog + Sync + Send,
+        A: Send + Sync + 'static,
+    {
+        type Item = A;
+
+        async fn feed(&self, output: &mut impl Adapter<Item = Self::Item>) -> Result<()> {
+            let Some(last) = self
+                .commit_log
+                .offsets(self.topic.clone(), 0)
+                .await
+                .map(|o| o.end_offset)
+            else {
+                return Ok(());
+            };
+            let subscriptions = Vec::from([Subscription {
+                topic: self.topic.clone(),
+            }]);
+            let mut records =
+                self.commit_log
+                    .scoped_subscribe("EDFSM", Vec::new(), subscriptions, None);
+            while let Some(mut r) = records.next().await {
+                if r.offset > last {
+                    break;
+                }
+                if let Some(item) = self.codec.decode(&mut r.value) {
+                    output.notify(item).await?;
+                }
+                if r.offset == last {
+                    break;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl<L, C, A> Adapter for CompactedLog<L, C, A>
+    where
+        C: Codec<A> + Sync + Send,
+        L: CommitLog + Sync + Send,
+        A: CompactionKey + Sync + Send,
+    {
+        type Item = A;
+
+        async fn notify(&mut self, a: Self::Item) -> Result<()>
+        where
+            Self::Item: 'static,
+        {
+            self.produce(a).await?;
+            Ok(())
+        }
+    }
+
+    /// A source of the items of a commit log that follows the log as it grows,
+    /// for a read model that follows the event log of another machine.
+    ///
+    /// Unlike the `Feed` of a `LogAdapter`, which yields the history up to the time it
+    /// is called, this yields each item of the log from the beginning and then each item
+    /// as it is produced. It does not complete unless the subscription to the log ends.
+    /// So it is not for use as an event log, which must complete to initialise a machine.
+    /// Instead, feed the input of the following machine:
+    ///
+    /// ```rust,ignore
+    /// let log = TailingLog::new(commit_log, "events", Cbor);
+    /// let follower = spawn(async move { log.feed(&mut input.with_map(Input::Event)).await });
+    /// ```
+    ///
+    /// To stop following, abort the task, or race it with a cancellation such as
+    /// `CancellationToken::run_until_cancelled`. Note that `Feed::feed_cancellable` stops
+    /// only at the next item, which may not arrive.
+    #[derive(Debug)]
+    pub struct TailingLog<L, C, A> {
+        commit_log: L,
+        codec: C,
+        topic: Topic,
+        marker: PhantomData<A>,
+    }
+
+    impl<L, C, A> TailingLog<L, C, A> {
+        /// Specialize a commit log for items of type `A`, given the topic
+        /// and a `Codec` for decoding the items.
+        pub fn new(commit_log: L, topic: impl Into<Topic>, codec: C) -> Self {
+            Self {
+                commit_log,
+                codec,
+                topic: topic.into(),
+                marker: PhantomData,
+            }
+        }
+    }
+
+    impl<L, C, A> TailingLog<L, C, A>
+    where
+        C: Codec<A>,
+        L: CommitLog,
+    {
+        /// Return a stream of the items of the log, from the beginning and then as they
+        /// are produced. Records that cannot be decoded are skipped.
+        pub fn tail(&self) -> impl Stream<Item = A> + '_ {
+            let subscriptions = Vec::from([Subscription {
+                topic: self.topic.clone(),
+            }]);
+            self.commit_log
+                .scoped_subscribe("EDFSM", Vec::new(), subscriptions, None)
+                .filter_map(move |mut r| ready(self.codec.decode(&mut r.value)))
+        }
+    }
+
+    impl<L, C, A> Feed for TailingLog<L, C, A>
+    where
+        C: Codec<A> + Sync + Send,
+        L: CommitLog + Sync + Send,
+        A: Send + Sync + 'static,
+    {
+        type Item = A;
+
+        async fn feed(&self, output: &mut impl Adapter<Item = Self::Item>) -> Result<()> {
+            output.notify_all(self.tail()).await
+        }
+    }
 }
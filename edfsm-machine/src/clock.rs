@@ -0,0 +1,96 @@
+//! Implementations of `edfsm::Clock` for the supported runtimes, and timers using them.
+use crate::{adapter::Adapter, error::Result};
+#[cfg(any(feature = "tokio", feature = "embassy"))]
+use core::future::Future;
+use core::time::Duration;
+use edfsm::{Clock, HeartbeatCommand, Input, WatchdogCommand};
+
+/// Send a heartbeat command to a machine every interval, for a `Heartbeating` state machine.
+///
+/// Heartbeats are sent whether or not the machine is otherwise idle. This runs until the
+/// input is closed, when the machine has ended, returning the error. Note that a machine
+/// runs while any sender of its input exists, and so while this runs. To stop the machine
+/// by closing its input, abort this task first.
+pub async fn heartbeat<C, E>(
+    mut input: impl Adapter<Item = Input<HeartbeatCommand<C>, E>>,
+    clock: impl Clock,
+    interval: Duration,
+) -> Result<()>
+where
+    C: 'static,
+    E: 'static,
+{
+    loop {
+        clock.sleep(interval).await;
+        input
+            .notify(Input::Command(HeartbeatCommand::Heartbeat))
+            .await?;
+    }
+}
+
+/// Send a check command to a machine every interval, for a `Watchdog` state machine.
+///
+/// The interval bounds how late after its timeout a `Watchdog` fails the underlying
+/// state machine, so it is typically a fraction of the timeout. As for `heartbeat`, this
+/// runs until the input is closed, returning the error, and keeps the machine running.
+pub async fn watchdog<C, E>(
+    mut input: impl Adapter<Item = Input<WatchdogCommand<C>, E>>,
+    clock: impl Clock,
+    interval: Duration,
+) -> Result<()>
+where
+    C: 'static,
+    E: 'static,
+{
+    loop {
+        clock.sleep(interval).await;
+        input.notify(Input::Command(WatchdogCommand::Check)).await?;
+    }
+}
+
+/// A `Clock` driven by tokio's timer. This is the default clock on std targets.
+#[cfg(feature = "tokio")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TokioClock;
+
+#[cfg(feature = "tokio")]
+impl Clock for TokioClock {
+    type Instant = tokio::time::Instant;
+
+    fn now(&self) -> Self::Instant {
+        tokio::time::Instant::now()
+    }
+
+    fn deadline(&self, after: Duration) -> Self::Instant {
+        tokio::time::Instant::now() + after
+    }
+
+    fn sleep_until(&self, deadline: Self::Instant) -> impl Future<Output = ()> + Send {
+        tokio::time::sleep_until(deadline)
+    }
+}
+
+/// A `Clock` driven by the Embassy time driver, for embedded targets.
+#[cfg(feature = "embassy")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EmbassyClock;
+
+#[cfg(feature = "embassy")]
+impl Clock for EmbassyClock {
+    type Instant = embassy_time::Instant;
+
+    fn now(&self) -> Self::Instant {
+        embassy_time::Instant::now()
+    }
+
+    fn deadline(&self, after: Duration) -> Self::Instant {
+        let micros = after.as_micros().try_into().unwrap_or(u64::MAX);
+        let now = embassy_time::Instant::now();
+        now.checked_add(embassy_time::Duration::from_micros(micros))
+            .unwrap_or(embassy_time::Instant::MAX)
+    }
+
+    fn sleep_until(&self, deadline: Self::Instant) -> impl Future<Output = ()> + Send {
+        embassy_time::Timer::at(deadline)
+    }
+}
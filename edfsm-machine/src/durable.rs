@@ -0,0 +1,187 @@
+//! A durable queue of inputs for a machine, so that inputs queued when it stops are not lost.
+//!
+//! The input channel of a machine is held in memory, and so inputs that are queued when
+//! the machine crashes are lost. A `DurableInput` instead appends each input to a commit
+//! log as it is sent. Inputs are forwarded from the log to the machine and acknowledged,
+//! by recording their offset, once the machine has processed them. On restart, forwarding
+//! resumes after the last acknowledged input:
+//!
+//! ```rust,ignore
+//! let durable = DurableInput::new(commit_log, "commands", Cbor);
+//! let (machine, processed) = machine::<Counter>().with_event_log(log).processed();
+//! let input = machine.input().with_map(Input::Command);
+//! spawn(machine.task());
+//! durable.forward(input, processed).await?;
+//! ```
+//!
+//! Delivery is at least once. An input that is processed but not yet acknowledged when the
+//! machine stops is forwarded again on restart, so commands should be handled idempotently,
+//! for example with `edfsm::Idempotent`.
+use crate::{adapter::Adapter, error::Result};
+use core::marker::PhantomData;
+use futures_util::StreamExt;
+use std::{format, vec::Vec};
+use streambed::commit_log::{ConsumerOffset, ProducerRecord, Subscription, Topic};
+use streambed_codec::{Codec, CommitLog, ProducerError};
+use tokio::sync::watch;
+
+/// An offset in a commit log.
+pub type Offset = u64;
+
+/// A queue of inputs held in a commit log, which are forwarded to a machine
+/// and acknowledged once processed.
+///
+/// Inputs are produced to the given topic, encoded with the codec. Acknowledgements are
+/// produced to a second topic, named for the first with an `-ack` suffix, and carry
+/// the offset of the input acknowledged as their key.
+///
+/// As an `Adapter`, this appends each item to the queue. Clones of the commit log may be
+/// used to create further `DurableInput`s that append to the same queue.
+#[derive(Debug)]
+pub struct DurableInput<L, C, A> {
+    commit_log: L,
+    codec: C,
+    topic: Topic,
+    ack_topic: Topic,
+    marker: PhantomData<A>,
+}
+
+impl<L, C, A> DurableInput<L, C, A> {
+    /// Specialize a commit log as a queue of inputs of type `A`, given the topic
+    /// and a `Codec` for encoding and decoding the inputs.
+    pub fn new(commit_log: L, topic: impl Into<Topic>, codec: C) -> Self {
+        let topic = topic.into();
+        let ack_topic = format!("{topic}-ack").into();
+        Self {
+            commit_log,
+            codec,
+            topic,
+            ack_topic,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<L, C, A> DurableInput<L, C, A>
+where
+    C: Codec<A>,
+    L: CommitLog,
+{
+    /// Append an input to the queue, returning its offset.
+    pub async fn produce(&self, item: A) -> core::result::Result<Offset, ProducerError> {
+        let value = self
+            .codec
+            .encode(item)
+            .ok_or(ProducerError::CannotProduce)?;
+        self.commit_log
+            .produce(ProducerRecord {
+                topic: self.topic.clone(),
+                headers: Vec::new(),
+                timestamp: None,
+                key: 0,
+                value,
+                partition: 0,
+            })
+            .await
+            .map(|r| r.offset)
+    }
+
+    /// The offset of the last input acknowledged, or None if there is none.
+    pub async fn acknowledged(&self) -> Option<Offset> {
+        let last = self
+            .commit_log
+            .offsets(self.ack_topic.clone(), 0)
+            .await?
+            .end_offset;
+        let subscriptions = Vec::from([Subscription {
+            topic: self.ack_topic.clone(),
+        }]);
+        let mut records =
+            self.commit_log
+                .scoped_subscribe("EDFSM", Vec::new(), subscriptions, None);
+        let mut acknowledged = None;
+        while let Some(r) = records.next().await {
+            if r.offset > last {
+                break;
+            }
+            acknowledged = Some(r.key);
+            if r.offset == last {
+                break;
+            }
+        }
+        acknowledged
+    }
+
+    /// Forward the inputs in the queue to a machine, starting after the last one
+    /// acknowledged, and acknowledge each once the machine has processed it.
+    ///
+    /// The `input` is typically the machine's input mapped with `Input::Command`,
+    /// and `processed` is obtained from `Machine::processed`. Inputs are forwarded one
+    /// at a time, each once the previous one is processed, and so this should be the only
+    /// source of inputs to the machine. Otherwise an input may be acknowledged before
+    /// it is processed. Forwarding ends when the subscription to the queue ends, and
+    /// the input adapter is then dropped.
+    pub async fn forward(
+        &self,
+        mut input: impl Adapter<Item = A>,
+        mut processed: watch::Receiver<u64>,
+    ) -> Result<()>
+    where
+        A: 'static,
+    {
+        let offsets = match self.acknowledged().await {
+            Some(offset) => Vec::from([ConsumerOffset {
+                topic: self.topic.clone(),
+                partition: 0,
+                offset,
+            }]),
+            None => Vec::new(),
+        };
+        let subscriptions = Vec::from([Subscription {
+            topic: self.topic.clone(),
+        }]);
+        let mut records = self
+            .commit_log
+            .scoped_subscribe("EDFSM", offsets, subscriptions, None);
+        while let Some(mut r) = records.next().await {
+            if let Some(item) = self.codec.decode(&mut r.value) {
+                let target = *processed.borrow_and_update() + 1;
+                input.notify(item).await?;
+                processed.wait_for(|n| *n >= target).await?;
+            }
+            self.acknowledge(r.offset).await?;
+        }
+        Ok(())
+    }
+
+    async fn acknowledge(&self, offset: Offset) -> core::result::Result<Offset, ProducerError> {
+        self.commit_log
+            .produce(ProducerRecord {
+                topic: self.ack_topic.clone(),
+                headers: Vec::new(),
+                timestamp: None,
+                key: offset,
+                value: Vec::new(),
+                partition: 0,
+            })
+            .await
+            .map(|r| r.offset)
+    }
+}
+
+impl<L, C, A> Adapter for DurableInput<L, C, A>
+where
+    C: Codec<A> + Sync + Send,
+    L: CommitLog + Sync + Send,
+    A: Sync + Send,
+{
+    type Item = A;
+
+    async fn notify(&mut self, a: Self::Item) -> Result<()>
+    where
+        Self::Item: 'static,
+    {
+        self.produce(a).await?;
+        Ok(())
+    }
+}
@@ -7,12 +7,21 @@ pub type Result<A> = core::result::Result<A, Error>;
 #[derive(Debug, Clone, From)]
 pub enum Error {
     ChannelClosed,
+    /// A broadcast receiver fell behind and this many messages were dropped.
+    #[from(skip)]
+    Lagged(u64),
+    /// An operation was cancelled before it completed.
+    #[from(skip)]
+    Cancelled,
+    /// A strict machine was started without a destination for its events.
+    #[from(skip)]
+    Unwired,
 }
 
 #[cfg(feature = "tokio")]
 pub mod adapt_tokio {
     use super::Error;
-    use tokio::sync::{broadcast, mpsc, oneshot};
+    use tokio::sync::{broadcast, mpsc, oneshot, watch};
 
     impl<E> From<mpsc::error::SendError<E>> for Error {
         fn from(_: mpsc::error::SendError<E>) -> Self {
@@ -26,11 +35,68 @@ pub mod adapt_tokio {
         }
     }
 
+    impl From<broadcast::error::RecvError> for Error {
+        fn from(e: broadcast::error::RecvError) -> Self {
+            match e {
+                broadcast::error::RecvError::Closed => Error::ChannelClosed,
+                broadcast::error::RecvError::Lagged(n) => Error::Lagged(n),
+            }
+        }
+    }
+
     impl From<oneshot::error::RecvError> for Error {
         fn from(_: oneshot::error::RecvError) -> Self {
             Error::ChannelClosed
         }
     }
+
+    impl From<watch::error::RecvError> for Error {
+        fn from(_: watch::error::RecvError) -> Self {
+            Error::ChannelClosed
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+pub mod adapt_std {
+    use super::Error;
+    use std::sync::mpsc::SendError;
+
+    impl<E> From<SendError<E>> for Error {
+        fn from(_: SendError<E>) -> Self {
+            Error::ChannelClosed
+        }
+    }
+}
+
+#[cfg(feature = "async-channel")]
+pub mod adapt_async_channel {
+    use super::Error;
+    use async_channel::{RecvError, SendError};
+
+    impl<E> From<SendError<E>> for Error {
+        fn from(_: SendError<E>) -> Self {
+            Error::ChannelClosed
+        }
+    }
+
+    impl From<RecvError> for Error {
+        fn from(_: RecvError) -> Self {
+            Error::ChannelClosed
+        }
+    }
+}
+
+#[cfg(feature = "futures-channel")]
+pub mod adapt_futures_channel {
+    use super::Error;
+    use futures_channel::mpsc::SendError;
+
+    impl From<SendError> for Error {
+        fn from(_: SendError) -> Self {
+            Error::ChannelClosed
+        }
+    }
 }
 
 #[cfg(feature = "streambed")]
@@ -0,0 +1,75 @@
+//! Probing what a running machine is doing, to tell a stuck machine from an idle one.
+//!
+//! `Machine::health` returns a `HealthHandle` whose status is updated by the machine's
+//! task around each point at which it waits:
+//!
+//! ```rust,ignore
+//! let (machine, health) = machine::<Counter>().with_output(sink).health();
+//! spawn(machine.task());
+//! if health.status() == Status::BlockedOnOutput {
+//!     // the sink may be stalled if this persists
+//! }
+//! ```
+use core::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+/// What a machine is doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Status {
+    /// Waiting for input, or paused. This is also the status before the task
+    /// starts and after it ends.
+    Idle,
+    /// Stepping inputs.
+    Processing,
+    /// Waiting for the output adapter to take an output message.
+    BlockedOnOutput,
+    /// Waiting for the event log or event adapter, including while rehydrating.
+    BlockedOnLog,
+}
+
+impl Status {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Status::Processing,
+            2 => Status::BlockedOnOutput,
+            3 => Status::BlockedOnLog,
+            _ => Status::Idle,
+        }
+    }
+}
+
+/// A handle to observe the status of a running machine.
+///
+/// The status is set as the machine begins to wait, whether or not the wait turns out to
+/// be brief. A single observation of a blocked status is therefore normal, while one that
+/// persists across observations indicates an adapter that has stalled. Reading the
+/// status costs an atomic load, so it may be polled, for example by a liveness probe.
+#[derive(Debug, Clone)]
+pub struct HealthHandle {
+    status: Arc<AtomicU8>,
+}
+
+impl HealthHandle {
+    pub(crate) fn new() -> Self {
+        Self {
+            status: Arc::new(AtomicU8::new(Status::Idle as u8)),
+        }
+    }
+
+    pub(crate) fn set(&self, status: Status) {
+        self.status.store(status as u8, Ordering::Relaxed);
+    }
+
+    /// The current status of the machine.
+    pub fn status(&self) -> Status {
+        Status::from_u8(self.status.load(Ordering::Relaxed))
+    }
+}
+
+/// Report the status of a machine, if a health handle is held.
+pub(crate) fn report(health: &Option<HealthHandle>, status: Status) {
+    if let Some(health) = health {
+        health.set(status);
+    }
+}
@@ -4,20 +4,64 @@
 extern crate std;
 
 pub mod adapter;
+pub mod clock;
 pub mod error;
+pub mod metrics;
+
+#[cfg(all(feature = "streambed", feature = "tokio"))]
+pub mod durable;
+
+#[cfg(feature = "streambed")]
+pub mod memlog;
+
+#[cfg(feature = "tokio")]
+pub mod health;
 
 #[cfg(feature = "std")]
 pub mod output;
 
 #[cfg(feature = "tokio")]
-use tokio::sync::mpsc::{channel, Receiver, Sender};
+pub mod pause;
+
+#[cfg(feature = "tokio")]
+pub mod registry;
+
+#[cfg(feature = "std")]
+pub mod testing;
+
+#[cfg(feature = "streambed")]
+pub mod versioned;
+
+#[cfg(feature = "tokio")]
+use health::{report, HealthHandle, Status};
+#[cfg(feature = "tokio")]
+use pause::PauseHandle;
+#[cfg(feature = "tokio")]
+use registry::{OutputHandle, Registry};
+#[cfg(feature = "tokio")]
+use std::{
+    boxed::Box,
+    collections::VecDeque,
+    panic::{catch_unwind, AssertUnwindSafe},
+    string::String,
+    vec::Vec,
+};
+#[cfg(feature = "tokio")]
+use tokio::sync::{
+    mpsc::{channel, Receiver, Sender},
+    watch,
+};
 
 use crate::{
     adapter::{Adapter, Feed, Placeholder},
-    error::Result,
+    error::{Error, Result},
+};
+use core::{future::Future, pin::pin};
+use edfsm::{Cascade, Drain, Fsm, Init, Input, Summarize, Terminating};
+use futures_util::{
+    future::{select, Either},
+    stream, Stream,
 };
-use core::future::Future;
-use edfsm::{Drain, Fsm, Init, Input, Terminating};
 
 /// The event type of an Fsm
 pub type Event<M> = <M as Fsm>::E;
@@ -37,6 +81,41 @@ pub type Effects<M> = <M as Fsm>::SE;
 /// The state type of an Fsm
 pub type State<M> = <M as Fsm>::S;
 
+/// The summary type of the state of an Fsm
+pub type Summary<M> = <State<M> as Summarize>::Summary;
+
+/// A function giving an input for an output message that failed to be dispatched.
+#[cfg(feature = "tokio")]
+type Recover<M> = Box<dyn Fn(Out<M>, Error) -> Option<In<M>> + Send + Sync>;
+
+/// A `Recover` function and the means to retain a copy of each output message for it.
+/// See `Machine::with_output_failure`.
+#[cfg(feature = "tokio")]
+struct OutputFailure<M>
+where
+    M: Fsm,
+    Effects<M>: Drain,
+{
+    copy: fn(&Out<M>) -> Out<M>,
+    func: Recover<M>,
+}
+
+/// A function giving an output message to report a step that panicked.
+#[cfg(feature = "tokio")]
+type Report<M> = Box<dyn Fn(&str) -> Option<Out<M>> + Send + Sync>;
+
+/// A `Report` function and the means to snapshot the state before each step.
+/// See `Machine::with_rollback`.
+#[cfg(feature = "tokio")]
+struct Rollback<M>
+where
+    M: Fsm,
+    Effects<M>: Drain,
+{
+    snapshot: fn(&State<M>) -> State<M>,
+    func: Report<M>,
+}
+
 /// A `Machine` is a state machine (implementing `Fsm`) that will run in a rust `task`.
 ///
 /// Each `Machine` has an input channel, and adapters for output and event log.
@@ -48,23 +127,30 @@ pub type State<M> = <M as Fsm>::S;
 /// A `Machine` also has a data structure used to perform side effects, including generating output messages.
 /// The type of this is also part of the state machine specification (the `SE` associated type).  
 /// Note: side effects must be synchronous. If they may block they should be bracketed with
-/// tokio's `block_in_place` or equivalent.
+/// tokio's `block_in_place` or equivalent, or handed to the runtime's `spawn_blocking`
+/// with any result sent back as an input.
 ///
-/// A machine is created by functions `machine` or `machine_with_effects`.
-/// It is wired to other machines or channels by functions `input`, `with_output`, `merge_output` and
-/// `with_event_log`.
+/// A machine is created by functions `machine`, `machine_with_effects` or `machine_with_channel`.
+/// It is wired to other machines or channels by functions `input`, `with_output`, `merge_output`,
+/// `output_handle` and `with_event_log`.
 ///
-/// The machine is made runnable by function `task`.  This is a future intended to be spawned onto
-/// the tokio (or other) runtime.
+/// The machine is made runnable by function `task` or `task_with_state`.  This is a future
+/// intended to be spawned onto the tokio (or other) runtime. It does not depend on the tokio
+/// runtime and can equally be spawned with async-std or smol.
 ///
 /// Once running, a `Machine`
 /// - initialises state, which may involve replaying messages from the event log
-/// - performs initial effects
+/// - performs initial effects with `Init::init` and then `Fsm::on_init`
 /// - enters the main loop, which is dirven by messages received on the input channel
 /// - each message may cause the state to evolve and/or generate side effects
+/// - a transition may be followed up by further events (see `Fsm::step_all`)
 /// - an event is logged if the state changed
 /// - any output messages are dispatched
 ///
+/// When the input channel is closed, or a terminating event is seen, the machine
+/// performs final effects with `Fsm::on_stop` and dispatches their output messages.
+/// Finally, the event log, event and output adapters are flushed (see `Adapter::flush`).
+///
 pub trait Machine<M>
 where
     M: Fsm,
@@ -95,10 +181,55 @@ where
     where
         Out<M>: Clone + Send;
 
+    /// Connect an additional channel for output messages and return its receiver as a `Stream`.
+    ///
+    /// This is a convenience for `merge_output` with a new channel. The stream ends when
+    /// the machine task ends. As with any output, if the stream is not consumed this will
+    /// stall the state machine. For example:
+    ///
+    /// ```rust,ignore
+    /// let (machine, outputs) = machine::<Counter>().output_stream();
+    /// spawn(machine.task());
+    /// let tocks = outputs.count().await;
+    /// ```
+    fn output_stream(self) -> (impl Machine<M>, impl Stream<Item = Out<M>> + Send + 'static)
+    where
+        Out<M>: Clone + Send + 'static;
+
+    /// Connect a registry of output subscribers and return a handle to subscribe to it.
+    ///
+    /// Unlike `merge_output`, subscribers may come and go while the machine runs.
+    /// Output messages are dropped while there are no subscribers. See `registry::OutputHandle`.
+    fn output_handle(self) -> (impl Machine<M>, OutputHandle<Out<M>>)
+    where
+        Out<M>: Clone + Send + 'static;
+
+    /// Publish a copy of the state after each change and return a receiver to observe it.
+    ///
+    /// This lets external code display the state without the round trip of a query.
+    /// The state is published once it is rehydrated, and then after each batch of inputs
+    /// that changes it (see `with_batch`). Each publication clones the state, so this
+    /// suits states that are cheap to clone or change infrequently. The `watch` channel
+    /// retains only the latest state, so rapid changes are coalesced for slow observers.
+    fn publish_state(self) -> (impl Machine<M>, watch::Receiver<State<M>>)
+    where
+        State<M>: Clone + Default + Send + Sync + 'static;
+
+    /// Publish a summary of the state after each change and return a receiver to observe it.
+    ///
+    /// This is as `publish_state`, but publishes `Summarize::summary` of the state, such as
+    /// an `edfsm::StateSummary` for a health endpoint. This suits states that are costly
+    /// to clone or that should not be exposed in full.
+    fn publish_summary(self) -> (impl Machine<M>, watch::Receiver<Summary<M>>)
+    where
+        State<M>: Summarize + Default,
+        Summary<M>: Send + Sync + 'static;
+
     /// Connect an event log that provides intialisation from historical events and records live events.
     ///
     /// Each event received by the machine and each event produced by a command will be notified.
     /// This method replaces any existing event log.
+    /// Events are moved to the log, and cloned only if event adapters are also connected.
     fn with_event_log(
         self,
         log: impl Adapter<Item = Event<M>> + Feed<Item = Event<M>> + 'static,
@@ -111,6 +242,127 @@ where
     /// Each will receive all output messages, however if an adapter stalls this will stall the state machine.
     fn merge_event_log(self, output: impl Adapter<Item = Event<M>> + 'static) -> impl Machine<M>;
 
+    /// Process up to `size` queued inputs before flushing output messages.
+    ///
+    /// By default each input is stepped and its output messages are flushed
+    /// before the next input is received. With a batch size greater than one,
+    /// inputs that are already queued are stepped together and their output messages
+    /// are flushed once for the whole batch. Likewise, the events of the batch are logged
+    /// together with `Adapter::notify_all`, and then passed to any other event adapters.
+    /// Events and output messages retain their order, so this only changes when they are
+    /// delivered relative to subsequent inputs.
+    fn with_batch(self, size: usize) -> impl Machine<M>;
+
+    /// Require that events produced by the machine have a destination.
+    ///
+    /// A machine that is not wired to an event log or other event adapter silently
+    /// discards its events, which is legitimate for some machines. A strict machine
+    /// instead fails at the start of its task with `Error::Unwired`, guarding against
+    /// misconfiguration of machines whose events must be recorded.
+    fn strict(self) -> impl Machine<M>;
+
+    /// Choose whether events received as inputs are recorded in the event log.
+    ///
+    /// By default, events received directly as `Input::Event` are logged along with those
+    /// produced by commands. A machine that follows another, receiving the events of the
+    /// other's log, would then duplicate that log. With `false`, a received event and any
+    /// follow-up events that it causes update the state but are not notified to the event
+    /// log given by `with_event_log`. Events produced by commands are logged as usual, and
+    /// event adapters connected by `merge_event_log` still receive every event.
+    ///
+    /// The event log then no longer reconstructs the state by itself, as received events are
+    /// missing from it. Rehydrate such a machine from a log that includes them, such as one
+    /// that feeds the source's events followed by the machine's own.
+    fn log_injected_events(self, log: bool) -> impl Machine<M>;
+
+    /// Return a handle to pause and resume the processing of inputs.
+    ///
+    /// Unlike ending the machine, pausing retains its state and channels. See `pause::PauseHandle`.
+    fn pausable(self) -> (impl Machine<M>, PauseHandle);
+
+    /// Return a receiver of the number of inputs that the machine has processed.
+    ///
+    /// The count increases once an input has been stepped and the output messages of its
+    /// batch have been flushed (see `with_batch`). This lets a source of inputs acknowledge
+    /// them only once processed, as `durable::DurableInput` does.
+    fn processed(self) -> (impl Machine<M>, watch::Receiver<u64>);
+
+    /// Dispatch output messages from a separate task, so that a slow output adapter does not
+    /// hold up the stepping of inputs. Return the future of that task, to be spawned.
+    ///
+    /// The output adapter connected so far is moved to the returned future, and the machine
+    /// instead hands its output messages to the future through a channel with room for
+    /// `buffer` of them. The machine then waits for its output only when the channel is full.
+    /// The future ends, having flushed the adapter, once the machine has ended and every
+    /// output message has been dispatched. It fails with the first error of the adapter.
+    ///
+    /// Output messages remain in the order they were produced. However, they are no longer
+    /// dispatched before the next input is stepped, and so may be dispatched after the state
+    /// has moved on and later events are logged. Inputs are counted as processed (see
+    /// `processed`) when their output messages are handed over, not dispatched. Outputs
+    /// connected after this, with `merge_output` for example, are dispatched by the machine
+    /// as usual, and `with_output_failure` then sees failures only to hand over a message.
+    fn concurrent_output(
+        self,
+        buffer: usize,
+    ) -> (
+        impl Machine<M>,
+        impl Future<Output = Result<()>> + Send + 'static,
+    )
+    where
+        Out<M>: Send + 'static;
+
+    /// Return a handle reporting whether the machine is idle, processing, or blocked on
+    /// its output or event log.
+    ///
+    /// A machine that waits for input is idle, while one that persistently waits on an
+    /// adapter is stuck behind a stalled downstream. See `health::HealthHandle`.
+    fn health(self) -> (impl Machine<M>, HealthHandle);
+
+    /// Return output messages that fail to be dispatched to the machine as inputs.
+    ///
+    /// By default, an output adapter that fails ends the machine with its error. Instead, the
+    /// function is given a message that failed along with the error, and may return an input
+    /// such as an `OutputFailed` command. This is stepped before the next input received, so
+    /// that the state machine can react to the failure, for example by entering a state that
+    /// retries after a backoff. The message is cloned before it is dispatched so that it can
+    /// be returned. Returning None drops the failed message and the machine continues.
+    ///
+    /// Take care that a failure does not loop: if stepping the input produces the same output
+    /// and that fails again, the machine spins on the failure without receiving other input.
+    /// Bound this in the state machine, for example by counting attempts in the state and
+    /// giving up after a number of them, and by awaiting a timer between attempts rather than
+    /// producing the output again immediately. Inputs returned by the function are not counted
+    /// as processed (see `processed`). Failures of output messages produced by `Fsm::on_stop`
+    /// still end the machine with the error, as no further input is stepped.
+    fn with_output_failure(
+        self,
+        func: impl Fn(Out<M>, Error) -> Option<In<M>> + Send + Sync + 'static,
+    ) -> impl Machine<M>
+    where
+        Out<M>: Clone;
+
+    /// Roll back the state when stepping an input panics, and continue with the next input.
+    ///
+    /// By default, a panic in an effect handler ends the machine task, and the state may
+    /// have been partly changed. Instead, the state is copied before each input is stepped
+    /// and restored if the step panics, including any follow-up events. No events of the
+    /// input are logged, and output messages that the step produced are discarded. The
+    /// function is given the panic message and may return an output message to report the
+    /// failure, for example to be logged or counted as a metric. It is dispatched in place
+    /// of the output messages of the step.
+    ///
+    /// The effector is not rolled back, so an effect handler should not be left in a state
+    /// that is inconsistent when it panics. Copying the state for each input has a cost
+    /// proportional to its size. Panics are caught only where they unwind, and so not
+    /// when built with `panic = "abort"`.
+    fn with_rollback(
+        self,
+        func: impl Fn(&str) -> Option<Out<M>> + Send + Sync + 'static,
+    ) -> impl Machine<M>
+    where
+        State<M>: Clone;
+
     /// Convert this machine into a future that will run as a task
     fn task(self) -> impl Future<Output = Result<()>> + Send + 'static
     where
@@ -120,12 +372,30 @@ where
         Effects<M>: Init<State<M>> + Send,
         Command<M>: Send,
         State<M>: Default + Send;
+
+    /// Convert this machine into a future that will run as a task and yield
+    /// the final state and effector when it ends.
+    ///
+    /// This consumes the machine as for `task`, and behaves in the same way.
+    /// It lets a test, for example, inspect data accumulated by the effector
+    /// without passing it through output messages.
+    fn task_with_state(
+        self,
+    ) -> impl Future<Output = Result<(State<M>, Effects<M>)>> + Send + 'static
+    where
+        Self: Sized,
+        Out<M>: Send,
+        Event<M>: Send + Terminating,
+        Effects<M>: Init<State<M>> + Send,
+        Command<M>: Send,
+        State<M>: Default + Send;
 }
 
 /// A concrete `Machine`
-struct Template<M, N, O, P>
+struct Template<M, N, O, P, V>
 where
     M: Fsm,
+    Effects<M>: Drain,
 {
     sender: Option<Sender<In<M>>>,
     receiver: Receiver<In<M>>,
@@ -133,15 +403,25 @@ where
     log: N,
     output: O,
     events: P,
+    view: V,
+    batch: usize,
+    strict: bool,
+    log_injected: bool,
+    pause: Option<watch::Receiver<bool>>,
+    processed: Option<watch::Sender<u64>>,
+    output_failure: Option<OutputFailure<M>>,
+    rollback: Option<Rollback<M>>,
+    health: Option<HealthHandle>,
 }
 
-impl<M, N, O, P> Machine<M> for Template<M, N, O, P>
+impl<M, N, O, P, V> Machine<M> for Template<M, N, O, P, V>
 where
     M: Fsm + 'static,
     Effects<M>: Drain,
     N: Adapter<Item = Event<M>> + Feed<Item = Event<M>> + 'static,
     O: Adapter<Item = Out<M>> + 'static,
     P: Adapter<Item = Event<M>> + 'static,
+    V: StateView<State<M>> + 'static,
     Event<M>: Clone + Send,
 {
     fn input(&self) -> Sender<In<M>> {
@@ -156,6 +436,15 @@ where
             log: self.log,
             output,
             events: self.events,
+            view: self.view,
+            batch: self.batch,
+            strict: self.strict,
+            log_injected: self.log_injected,
+            pause: self.pause,
+            processed: self.processed,
+            output_failure: self.output_failure,
+            rollback: self.rollback,
+            health: self.health,
         }
     }
 
@@ -170,9 +459,89 @@ where
             log: self.log,
             output: self.output.merge(output),
             events: self.events,
+            view: self.view,
+            batch: self.batch,
+            strict: self.strict,
+            log_injected: self.log_injected,
+            pause: self.pause,
+            processed: self.processed,
+            output_failure: self.output_failure,
+            rollback: self.rollback,
+            health: self.health,
         }
     }
 
+    fn output_stream(self) -> (impl Machine<M>, impl Stream<Item = Out<M>> + Send + 'static)
+    where
+        Out<M>: Clone + Send + 'static,
+    {
+        let (sender, receiver) = channel(DEFAULT_BUFFER);
+        let outputs = stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|o| (o, receiver))
+        });
+        (self.merge_output(sender), outputs)
+    }
+
+    fn output_handle(self) -> (impl Machine<M>, OutputHandle<Out<M>>)
+    where
+        Out<M>: Clone + Send + 'static,
+    {
+        let registry = Registry::new();
+        let handle = registry.handle();
+        (self.merge_output(registry), handle)
+    }
+
+    fn publish_state(self) -> (impl Machine<M>, watch::Receiver<State<M>>)
+    where
+        State<M>: Clone + Default + Send + Sync + 'static,
+    {
+        let (view, receiver) = watch::channel(State::<M>::default());
+        let machine = Template {
+            sender: self.sender,
+            receiver: self.receiver,
+            effects: self.effects,
+            log: self.log,
+            output: self.output,
+            events: self.events,
+            view,
+            batch: self.batch,
+            strict: self.strict,
+            log_injected: self.log_injected,
+            pause: self.pause,
+            processed: self.processed,
+            output_failure: self.output_failure,
+            rollback: self.rollback,
+            health: self.health,
+        };
+        (machine, receiver)
+    }
+
+    fn publish_summary(self) -> (impl Machine<M>, watch::Receiver<Summary<M>>)
+    where
+        State<M>: Summarize + Default,
+        Summary<M>: Send + Sync + 'static,
+    {
+        let (view, receiver) = watch::channel(State::<M>::default().summary());
+        let machine = Template {
+            sender: self.sender,
+            receiver: self.receiver,
+            effects: self.effects,
+            log: self.log,
+            output: self.output,
+            events: self.events,
+            view: SummaryView(view),
+            batch: self.batch,
+            strict: self.strict,
+            log_injected: self.log_injected,
+            pause: self.pause,
+            processed: self.processed,
+            output_failure: self.output_failure,
+            rollback: self.rollback,
+            health: self.health,
+        };
+        (machine, receiver)
+    }
+
     fn with_event_log(
         self,
         log: impl Adapter<Item = Event<M>> + Feed<Item = Event<M>> + 'static,
@@ -184,6 +553,15 @@ where
             log,
             output: self.output,
             events: self.events,
+            view: self.view,
+            batch: self.batch,
+            strict: self.strict,
+            log_injected: self.log_injected,
+            pause: self.pause,
+            processed: self.processed,
+            output_failure: self.output_failure,
+            rollback: self.rollback,
+            health: self.health,
         }
     }
 
@@ -195,16 +573,162 @@ where
             log: self.log,
             output: self.output,
             events: self.events.merge(events),
+            view: self.view,
+            batch: self.batch,
+            strict: self.strict,
+            log_injected: self.log_injected,
+            pause: self.pause,
+            processed: self.processed,
+            output_failure: self.output_failure,
+            rollback: self.rollback,
+            health: self.health,
+        }
+    }
+
+    fn strict(self) -> impl Machine<M> {
+        Template {
+            strict: true,
+            ..self
+        }
+    }
+
+    fn log_injected_events(self, log: bool) -> impl Machine<M> {
+        Template {
+            log_injected: log,
+            ..self
+        }
+    }
+
+    fn with_batch(self, size: usize) -> impl Machine<M> {
+        Template {
+            batch: size.max(1),
+            ..self
         }
     }
 
-    async fn task(mut self) -> Result<()>
+    fn pausable(self) -> (impl Machine<M>, PauseHandle) {
+        let (handle, pause) = PauseHandle::new();
+        let machine = Template {
+            pause: Some(pause),
+            ..self
+        };
+        (machine, handle)
+    }
+
+    fn processed(self) -> (impl Machine<M>, watch::Receiver<u64>) {
+        let (processed, receiver) = watch::channel(0);
+        let machine = Template {
+            processed: Some(processed),
+            ..self
+        };
+        (machine, receiver)
+    }
+
+    fn concurrent_output(
+        self,
+        buffer: usize,
+    ) -> (
+        impl Machine<M>,
+        impl Future<Output = Result<()>> + Send + 'static,
+    )
+    where
+        Out<M>: Send + 'static,
+    {
+        let (sender, mut receiver) = channel(buffer.max(1));
+        let mut output = self.output;
+        let forward = async move {
+            while let Some(item) = receiver.recv().await {
+                output.notify(item).await?;
+            }
+            output.flush().await
+        };
+        let machine = Template {
+            sender: self.sender,
+            receiver: self.receiver,
+            effects: self.effects,
+            log: self.log,
+            output: sender,
+            events: self.events,
+            view: self.view,
+            batch: self.batch,
+            strict: self.strict,
+            log_injected: self.log_injected,
+            pause: self.pause,
+            processed: self.processed,
+            output_failure: self.output_failure,
+            rollback: self.rollback,
+            health: self.health,
+        };
+        (machine, forward)
+    }
+
+    fn health(self) -> (impl Machine<M>, HealthHandle) {
+        let health = HealthHandle::new();
+        let machine = Template {
+            health: Some(health.clone()),
+            ..self
+        };
+        (machine, health)
+    }
+
+    fn with_output_failure(
+        self,
+        func: impl Fn(Out<M>, Error) -> Option<In<M>> + Send + Sync + 'static,
+    ) -> impl Machine<M>
+    where
+        Out<M>: Clone,
+    {
+        let output_failure = OutputFailure {
+            copy: Out::<M>::clone,
+            func: Box::new(func),
+        };
+        Template {
+            output_failure: Some(output_failure),
+            ..self
+        }
+    }
+
+    fn with_rollback(
+        self,
+        func: impl Fn(&str) -> Option<Out<M>> + Send + Sync + 'static,
+    ) -> impl Machine<M>
+    where
+        State<M>: Clone,
+    {
+        let rollback = Rollback {
+            snapshot: State::<M>::clone,
+            func: Box::new(func),
+        };
+        Template {
+            rollback: Some(rollback),
+            ..self
+        }
+    }
+
+    async fn task(self) -> Result<()>
+    where
+        Out<M>: Send,
+        Effects<M>: Init<State<M>> + Send,
+        Command<M>: Send,
+        State<M>: Default,
+        Event<M>: Send + Terminating,
+        State<M>: Send,
+    {
+        self.task_with_state().await.map(drop)
+    }
+
+    async fn task_with_state(mut self) -> Result<(State<M>, Effects<M>)>
     where
         Effects<M>: Init<State<M>>,
         State<M>: Default,
         Event<M>: Send + Terminating,
         State<M>: Send,
     {
+        // Fail fast if events are required and have no destination.
+        if self.strict && self.log.is_placeholder() && self.events.is_placeholder() {
+            return Err(Error::Unwired);
+        }
+
         // close the local sender side of the input channel
         // this ensures the task will exit when all other senders are closed
         self.sender = None;
@@ -212,37 +736,208 @@ where
         // Construct the initial state and rehydrate it from the log.
         let mut state: State<M> = Default::default();
         let mut hydra = Hydrator::<M> { state: &mut state };
+        report(&self.health, Status::BlockedOnLog);
         self.log.feed(&mut hydra).await?;
+        report(&self.health, Status::Processing);
+
+        // Publish the rehydrated state.
+        self.view.publish(&state);
 
         // Initialise the effector with the rehydrated, state.
         self.effects.init(&state);
 
-        // Flush output messages generated in initialisation
-        for item in self.effects.drain_all() {
-            self.output.notify(item).await?
-        }
+        // Perform initial effects given the rehydrated state.
+        M::on_init(&state, &mut self.effects);
+
+        // Flush output messages generated in initialisation.
+        // Inputs returned for failed output messages are stepped before those received.
+        // Output messages are held as pending where steps may be rolled back.
+        let mut failed = VecDeque::new();
+        let mut pending = Vec::new();
+        self.dispatch(&mut pending, &mut failed).await?;
 
         // Read events and commands
-        while let Some(input) = self.receiver.recv().await {
+        loop {
+            let (input, received) = match failed.pop_front() {
+                Some(input) => {
+                    self.unpaused().await;
+                    (input, false)
+                }
+                None => {
+                    report(&self.health, Status::Idle);
+                    match self.recv().await {
+                        Some(input) => (input, true),
+                        None => break,
+                    }
+                }
+            };
+
+            report(&self.health, Status::Processing);
+
             // Indicates a terminating event is seen
             let mut terminating = false;
 
-            // Run Fsm and log any event
-            if let Some(e) = M::step(&mut state, input, &mut self.effects) {
-                terminating = e.terminating();
-                self.log.clone_notify(&e).await?;
-                self.events.notify(e).await?;
+            // Run Fsm and log any events, for each input in the batch.
+            // An event is produced if it is applied, not if it is ignored,
+            // and may be followed by events from entry to a new state.
+            let mut next = Some(input);
+            let mut stepped = 0;
+            let mut processed = u64::from(received);
+            let mut changed = false;
+            let mut logged = Vec::new();
+            let mut published = Vec::new();
+            while let Some(input) = next.take() {
+                let log = self.log_injected || matches!(input, Input::Command(_));
+                for e in self.step(&mut state, input, &mut pending) {
+                    changed = true;
+                    terminating |= e.terminating();
+                    // The event is moved to a single destination and cloned only to fan out.
+                    if !log {
+                        published.push(e);
+                    } else if self.events.is_placeholder() {
+                        logged.push(e);
+                    } else {
+                        logged.push(e.clone());
+                        published.push(e);
+                    }
+                }
+                stepped += 1;
+                let paused = self.pause.as_ref().is_some_and(|pause| *pause.borrow());
+                if !terminating && !paused && stepped < self.batch {
+                    next = self.receiver.try_recv().ok();
+                    processed += u64::from(next.is_some());
+                }
             }
 
-            // Flush output messages generated during the `step`, if any.
-            for item in self.effects.drain_all() {
-                self.output.notify(item).await?
+            // Log the events of the batch together, then publish them, in order.
+            if !logged.is_empty() || !published.is_empty() {
+                report(&self.health, Status::BlockedOnLog);
+                self.log.notify_all(stream::iter(logged)).await?;
+                self.events.notify_all(stream::iter(published)).await?;
+                report(&self.health, Status::Processing);
+            }
+
+            // Publish the state if it changed during the batch.
+            if changed {
+                self.view.publish(&state);
+            }
+
+            // Flush output messages generated during the batch, if any.
+            self.dispatch(&mut pending, &mut failed).await?;
+
+            // Count the inputs of the batch as processed.
+            if let Some(count) = &self.processed {
+                count.send_modify(|n| *n += processed);
             }
 
             if terminating {
                 break;
             }
         }
+
+        // Perform final effects and flush their output messages.
+        M::on_stop(&state, &mut self.effects);
+        report(&self.health, Status::BlockedOnOutput);
+        for item in self.effects.drain_all() {
+            self.output.notify(item).await?
+        }
+
+        // Forward anything held by buffering adapters.
+        report(&self.health, Status::BlockedOnLog);
+        self.log.flush().await?;
+        self.events.flush().await?;
+        report(&self.health, Status::BlockedOnOutput);
+        self.output.flush().await?;
+        report(&self.health, Status::Idle);
+        Ok((state, self.effects))
+    }
+}
+
+impl<M, N, O, P, V> Template<M, N, O, P, V>
+where
+    M: Fsm + 'static,
+    Effects<M>: Drain,
+    O: Adapter<Item = Out<M>> + 'static,
+{
+    /// Wait while paused. Every handle being dropped resumes the machine.
+    async fn unpaused(&mut self) {
+        if let Some(pause) = &mut self.pause {
+            if *pause.borrow() {
+                report(&self.health, Status::Idle);
+            }
+            if pause.wait_for(|paused| !paused).await.is_err() {
+                self.pause = None;
+            }
+        }
+    }
+
+    /// Receive an input, taking none while paused, including while waiting for one.
+    async fn recv(&mut self) -> Option<In<M>> {
+        loop {
+            self.unpaused().await;
+            let Some(pause) = &mut self.pause else {
+                return self.receiver.recv().await;
+            };
+            let paused = pause.wait_for(|paused| *paused);
+            // Pausing is checked first, so that it takes precedence over an input.
+            let dropped = match select(pin!(paused), pin!(self.receiver.recv())).await {
+                Either::Left((paused, _)) => paused.is_err(),
+                Either::Right((input, _)) => return input,
+            };
+            if dropped {
+                self.pause = None;
+            }
+        }
+    }
+
+    /// Step an input, rolling back the state if it panics and if so configured.
+    fn step(
+        &mut self,
+        state: &mut State<M>,
+        input: In<M>,
+        pending: &mut Vec<Out<M>>,
+    ) -> Cascade<Event<M>> {
+        let Some(rollback) = &self.rollback else {
+            return M::step_all(state, input, &mut self.effects);
+        };
+        // Set aside the output messages of earlier steps, which are not rolled back.
+        pending.extend(self.effects.drain_all());
+        let snapshot = (rollback.snapshot)(state);
+        let effects = &mut self.effects;
+        match catch_unwind(AssertUnwindSafe(|| M::step_all(state, input, effects))) {
+            Ok(events) => events,
+            Err(panic) => {
+                *state = snapshot;
+                self.effects.drain_all().for_each(drop);
+                let message = panic
+                    .downcast_ref::<&str>()
+                    .copied()
+                    .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+                    .unwrap_or("panic");
+                pending.extend((rollback.func)(message));
+                Cascade::default()
+            }
+        }
+    }
+
+    /// Dispatch output messages, collecting inputs for those that fail if so configured.
+    async fn dispatch(
+        &mut self,
+        pending: &mut Vec<Out<M>>,
+        failed: &mut VecDeque<In<M>>,
+    ) -> Result<()> {
+        for item in pending.drain(..).chain(self.effects.drain_all()) {
+            report(&self.health, Status::BlockedOnOutput);
+            if let Some(f) = &self.output_failure {
+                let copy = (f.copy)(&item);
+                if let Err(e) = self.output.notify(item).await {
+                    failed.extend((f.func)(copy, e));
+                }
+            } else {
+                self.output.notify(item).await?
+            }
+        }
+        report(&self.health, Status::Processing);
         Ok(())
     }
 }
@@ -270,6 +965,24 @@ where
     Event<M>: Send + Sync + Clone,
 {
     let (sender, receiver) = channel(buffer);
+    machine_with_channel(effects, sender, receiver)
+}
+
+/// Create a new machine for an `Fsm` of type `M` with explicit effects and input channel.
+///
+/// The `sender` is retained to provide for `input`. Other senders for the channel may be held
+/// elsewhere. Either way, the machine task ends when all of them are dropped.
+pub fn machine_with_channel<M>(
+    effects: Effects<M>,
+    sender: Sender<In<M>>,
+    receiver: Receiver<In<M>>,
+) -> impl Machine<M>
+where
+    M: Fsm + 'static,
+    Effects<M>: Drain,
+    Out<M>: Send + Clone,
+    Event<M>: Send + Sync + Clone,
+{
     Template {
         sender: Some(sender),
         receiver,
@@ -277,6 +990,113 @@ where
         log: Placeholder::default(),
         output: Placeholder::default(),
         events: Placeholder::default(),
+        view: (),
+        batch: 1,
+        strict: false,
+        log_injected: true,
+        pause: None,
+        processed: None,
+        output_failure: None,
+        rollback: None,
+        health: None,
+    }
+}
+
+/// Connect the output of machine `a` to the input of machine `b`, mapping each output
+/// message to an input with `map`. Messages mapped to None are dropped.
+///
+/// The messages are sent on the input channel of `b`, so they are buffered by that channel
+/// along with any other input of `b`. When the channel is full, `a` stalls until `b`
+/// catches up. The connection is in addition to any other outputs of `a`. As `a` holds
+/// a sender for the input of `b`, the task of `b` continues until that of `a` ends.
+/// Both machines are returned, ready for their tasks to be spawned. For example:
+///
+/// ```rust,ignore
+/// let (sensor, alarm) = connect(machine::<Sensor>(), machine::<Alarm>(), |o| match o {
+///     Reading::High(t) => Some(Input::Command(AlarmCommand::Raise(t))),
+///     _ => None,
+/// });
+/// spawn(sensor.task());
+/// spawn(alarm.task());
+/// ```
+pub fn connect<A, B, MB>(
+    a: impl Machine<A>,
+    b: MB,
+    map: impl Fn(Out<A>) -> Option<In<B>> + Send + 'static,
+) -> (impl Machine<A>, MB)
+where
+    A: Fsm,
+    B: Fsm,
+    MB: Machine<B>,
+    Effects<A>: Drain,
+    Effects<B>: Drain,
+    Out<A>: Clone + Send + 'static,
+    In<B>: Send + 'static,
+{
+    let input = b.input().with_filter_map(map);
+    (a.merge_output(input), b)
+}
+
+/// Replay events through the full `step` of a state machine, with effects, and return
+/// the output messages produced. This is a diagnostic for reproducing an issue from
+/// an event log, by observing the effects that its history produces.
+///
+/// Unlike rehydration, which applies events without effects, every event is stepped from
+/// the default state and so its effects are performed again. This is not to be used with
+/// a production effector, which would repeat real effects, but with a mock or recording one.
+/// Follow-up events are not applied (see `Fsm::step_all`), as they are in the log themselves.
+#[cfg(feature = "std")]
+pub fn replay_effects<M>(
+    events: impl IntoIterator<Item = Event<M>>,
+    mut effects: Effects<M>,
+) -> std::vec::Vec<Out<M>>
+where
+    M: Fsm,
+    Effects<M>: Drain,
+    State<M>: Default,
+{
+    let mut state = State::<M>::default();
+    let mut outputs = std::vec::Vec::new();
+    for e in events {
+        M::step(&mut state, Input::Event(e), &mut effects);
+        outputs.extend(effects.drain_all());
+    }
+    outputs
+}
+
+/// A destination for copies of the state of a machine. See `Machine::publish_state`.
+pub trait StateView<S>: Send {
+    /// Observe the state after it has changed.
+    fn publish(&mut self, s: &S);
+}
+
+/// The unit `StateView` discards the state without cloning it.
+impl<S> StateView<S> for () {
+    fn publish(&mut self, _s: &S) {}
+}
+
+#[cfg(feature = "tokio")]
+impl<S> StateView<S> for watch::Sender<S>
+where
+    S: Clone + Send + Sync,
+{
+    fn publish(&mut self, s: &S) {
+        self.send_replace(s.clone());
+    }
+}
+
+/// A `StateView` that publishes a summary of the state. See `Machine::publish_summary`.
+#[cfg(feature = "tokio")]
+struct SummaryView<T>(watch::Sender<T>);
+
+#[cfg(feature = "tokio")]
+impl<S> StateView<S> for SummaryView<S::Summary>
+where
+    S: Summarize,
+    S::Summary: Send + Sync,
+{
+    fn publish(&mut self, s: &S) {
+        self.0.send_replace(s.summary());
     }
 }
 
@@ -304,7 +1124,7 @@ where
     where
         Self::Item: Send + 'static,
     {
-        M::on_event(self.state, &a);
+        M::apply(self.state, &a);
         Ok(())
     }
 }
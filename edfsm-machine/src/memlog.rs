@@ -0,0 +1,105 @@
+//! An in-memory `CommitLog`, for testing machines with an event log without touching disk.
+//!
+//! A `MemLog` is adapted for an event type in the same way as any `CommitLog`:
+//!
+//! ```rust,ignore
+//! let log = MemLog::new().adapt::<Event>("events", Cbor);
+//! let machine = machine::<Counter>().with_event_log(log);
+//! ```
+use async_trait::async_trait;
+use core::{pin::Pin, time::Duration};
+use futures_util::{stream, Stream};
+use std::{
+    boxed::Box,
+    sync::{Arc, Mutex},
+    vec::Vec,
+};
+use streambed::commit_log::{
+    CommitLog, ConsumerOffset, ConsumerRecord, Offset, Partition, PartitionOffsets, ProducedOffset,
+    ProducerError, ProducerRecord, Subscription, Topic,
+};
+
+/// A `CommitLog` that holds its records in memory.
+///
+/// Records are assigned offsets from 0 in each topic and partition. Clones of a `MemLog`
+/// share the same records. A subscription yields the records present when it is made,
+/// after any given consumer offsets, and then ends rather than waiting for more.
+#[derive(Debug, Default, Clone)]
+pub struct MemLog {
+    records: Arc<Mutex<Vec<ConsumerRecord>>>,
+}
+
+impl MemLog {
+    /// Create an empty log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of records in all topics.
+    pub fn len(&self) -> usize {
+        self.records.lock().unwrap().len()
+    }
+
+    /// The log has no records.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait]
+impl CommitLog for MemLog {
+    async fn offsets(&self, topic: Topic, partition: Partition) -> Option<PartitionOffsets> {
+        let records = self.records.lock().unwrap();
+        let mut offsets = records
+            .iter()
+            .filter(|r| r.topic == topic && r.partition == partition)
+            .map(|r| r.offset);
+        let beginning_offset = offsets.next()?;
+        let end_offset = offsets.next_back().unwrap_or(beginning_offset);
+        Some(PartitionOffsets {
+            beginning_offset,
+            end_offset,
+        })
+    }
+
+    async fn produce(&self, record: ProducerRecord) -> Result<ProducedOffset, ProducerError> {
+        let mut records = self.records.lock().unwrap();
+        let offset = records
+            .iter()
+            .filter(|r| r.topic == record.topic && r.partition == record.partition)
+            .count() as Offset;
+        records.push(ConsumerRecord {
+            topic: record.topic,
+            headers: record.headers,
+            timestamp: record.timestamp,
+            key: record.key,
+            value: record.value,
+            partition: record.partition,
+            offset,
+        });
+        Ok(ProducedOffset { offset })
+    }
+
+    fn scoped_subscribe<'a>(
+        &'a self,
+        _consumer_group_name: &str,
+        offsets: Vec<ConsumerOffset>,
+        subscriptions: Vec<Subscription>,
+        _idle_timeout: Option<Duration>,
+    ) -> Pin<Box<dyn Stream<Item = ConsumerRecord> + Send + 'a>> {
+        let records: Vec<_> = self
+            .records
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| subscriptions.iter().any(|s| s.topic == r.topic))
+            .filter(|r| {
+                !offsets.iter().any(|o| {
+                    o.topic == r.topic && o.partition == r.partition && o.offset >= r.offset
+                })
+            })
+            .cloned()
+            .collect();
+        Box::pin(stream::iter(records))
+    }
+}
@@ -0,0 +1,100 @@
+//! Record metrics derived from the output messages of a machine.
+//!
+//! A `MetricsAdapter` is connected as an output of a machine and passes each output message
+//! to a function that may produce a `MetricEvent`. Events are recorded by a `MetricSink`,
+//! which can be implemented in terms of a metrics library or exporter of choice, e.g.:
+//!
+//! ```rust,ignore
+//! let metrics = MetricsAdapter::new(
+//!     |o: &Output| match o {
+//!         Output::Tock => Some(MetricEvent::Counter("tocks", 1)),
+//!     },
+//!     my_sink,
+//! );
+//! let machine = machine::<Counter>().with_output(send_o).merge_output(metrics);
+//! ```
+use crate::{adapter::Adapter, error::Result};
+use core::marker::PhantomData;
+
+/// A measurement derived from an output message.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetricEvent {
+    /// Increment the named counter by a value.
+    Counter(&'static str, u64),
+    /// Set the named gauge to a value.
+    Gauge(&'static str, f64),
+}
+
+/// A destination for metrics.
+pub trait MetricSink: Send {
+    /// Increment the named counter by a value.
+    fn incr(&mut self, name: &'static str, value: u64);
+
+    /// Set the named gauge to a value.
+    fn set(&mut self, name: &'static str, value: f64);
+
+    /// Record a metric event.
+    fn record(&mut self, event: MetricEvent) {
+        match event {
+            MetricEvent::Counter(name, value) => self.incr(name, value),
+            MetricEvent::Gauge(name, value) => self.set(name, value),
+        }
+    }
+}
+
+/// A `MetricSink` that discards all metrics.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopSink;
+
+impl MetricSink for NoopSink {
+    fn incr(&mut self, _name: &'static str, _value: u64) {}
+
+    fn set(&mut self, _name: &'static str, _value: f64) {}
+}
+
+/// An `Adapter` that records a metric for each item, where the given function provides one.
+#[derive(Debug)]
+pub struct MetricsAdapter<A, F, K> {
+    func: F,
+    sink: K,
+    marker: PhantomData<A>,
+}
+
+impl<A, F, K> MetricsAdapter<A, F, K>
+where
+    F: Fn(&A) -> Option<MetricEvent>,
+    K: MetricSink,
+{
+    /// Create an adapter that records metrics produced by `func` in `sink`.
+    pub fn new(func: F, sink: K) -> Self {
+        Self {
+            func,
+            sink,
+            marker: PhantomData,
+        }
+    }
+
+    /// The sink that metrics are recorded in.
+    pub fn sink(&self) -> &K {
+        &self.sink
+    }
+}
+
+impl<A, F, K> Adapter for MetricsAdapter<A, F, K>
+where
+    F: Fn(&A) -> Option<MetricEvent> + Send,
+    K: MetricSink,
+    A: Send,
+{
+    type Item = A;
+
+    async fn notify(&mut self, a: Self::Item) -> Result<()>
+    where
+        Self::Item: 'static,
+    {
+        if let Some(event) = (self.func)(&a) {
+            self.sink.record(event);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+//! Pausing and resuming the processing of a running machine.
+//!
+//! `Machine::pausable` returns a `PauseHandle` with which input processing can be
+//! suspended, for example during maintenance, without ending the machine:
+//!
+//! ```rust,ignore
+//! let (machine, pause) = machine::<Counter>().pausable();
+//! spawn(machine.task());
+//! pause.pause();
+//! // inputs are retained in the input channel
+//! pause.resume();
+//! ```
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// A handle to pause and resume a running machine.
+///
+/// While paused, a machine takes no inputs. Inputs remain in its input channel, so
+/// senders will wait once the channel is full. This includes inputs from timers such as
+/// `clock::heartbeat`, which are therefore suspended too. An input that is being stepped
+/// when the machine is paused is completed, along with its events and output messages,
+/// and a batch in progress takes no further inputs. As the input channel is not read
+/// while paused, a machine whose input channel is closed ends once resumed. Dropping
+/// every handle resumes the machine.
+#[derive(Debug, Clone)]
+pub struct PauseHandle {
+    paused: Arc<watch::Sender<bool>>,
+}
+
+impl PauseHandle {
+    pub(crate) fn new() -> (Self, watch::Receiver<bool>) {
+        let (sender, receiver) = watch::channel(false);
+        let handle = Self {
+            paused: Arc::new(sender),
+        };
+        (handle, receiver)
+    }
+
+    /// Stop stepping inputs until resumed.
+    pub fn pause(&self) {
+        self.paused.send_replace(true);
+    }
+
+    /// Continue stepping inputs.
+    pub fn resume(&self) {
+        self.paused.send_replace(false);
+    }
+
+    /// The machine is paused.
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+}
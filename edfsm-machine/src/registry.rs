@@ -0,0 +1,101 @@
+//! A registry of output subscribers that can change while a machine runs.
+//!
+//! `Machine::output_handle` connects a registry as an output of a machine and returns an
+//! `OutputHandle`. Clients may then subscribe to output messages at any time:
+//!
+//! ```rust,ignore
+//! let (machine, handle) = machine::<Counter>().output_handle();
+//! spawn(machine.task());
+//! let mut outputs = handle.subscribe();
+//! while let Some(o) = outputs.recv().await { ... }
+//! ```
+use crate::{adapter::Adapter, error::Result, DEFAULT_BUFFER};
+use std::{
+    sync::{Arc, Mutex},
+    vec::Vec,
+};
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+/// A handle to subscribe to the output messages of a running machine.
+///
+/// A subscriber receives each output message notified after it subscribes, in the order
+/// produced by the machine. Subscribers are removed once their `Receiver` is dropped.
+/// As with any output, a subscriber that does not consume its messages will stall the
+/// state machine. Output messages are dropped while there are no subscribers.
+#[derive(Debug)]
+pub struct OutputHandle<A> {
+    pending: Arc<Mutex<Vec<Sender<A>>>>,
+}
+
+impl<A> OutputHandle<A> {
+    /// Subscribe to output messages.
+    pub fn subscribe(&self) -> Receiver<A> {
+        let (sender, receiver) = channel(DEFAULT_BUFFER);
+        self.pending.lock().unwrap().push(sender);
+        receiver
+    }
+}
+
+impl<A> Clone for OutputHandle<A> {
+    fn clone(&self) -> Self {
+        Self {
+            pending: self.pending.clone(),
+        }
+    }
+}
+
+/// An `Adapter` that forwards each item to the subscribers of an `OutputHandle`.
+///
+/// New subscribers are admitted as each item is notified, and then served in the order
+/// they subscribed. A subscriber whose receiver has been dropped is removed.
+#[derive(Debug)]
+pub struct Registry<A> {
+    subscribers: Vec<Sender<A>>,
+    pending: Arc<Mutex<Vec<Sender<A>>>>,
+}
+
+impl<A> Registry<A> {
+    /// Create a registry with no subscribers.
+    pub fn new() -> Self {
+        Self {
+            subscribers: Vec::new(),
+            pending: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Create a handle to subscribe to this registry.
+    pub fn handle(&self) -> OutputHandle<A> {
+        OutputHandle {
+            pending: self.pending.clone(),
+        }
+    }
+}
+
+impl<A> Default for Registry<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A> Adapter for Registry<A>
+where
+    A: Clone + Send,
+{
+    type Item = A;
+
+    async fn notify(&mut self, a: Self::Item) -> Result<()>
+    where
+        Self::Item: 'static,
+    {
+        self.subscribers.append(&mut self.pending.lock().unwrap());
+        let mut i = 0;
+        while i < self.subscribers.len() {
+            if self.subscribers[i].send(a.clone()).await.is_ok() {
+                i += 1;
+            } else {
+                self.subscribers.remove(i);
+            }
+        }
+        Ok(())
+    }
+}
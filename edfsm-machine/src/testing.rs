@@ -0,0 +1,97 @@
+//! A machine that is stepped synchronously under the control of a test.
+//!
+//! A `TestMachine` has the semantics of a `Machine` without channels, a task or a runtime,
+//! so that it can be driven from a plain `#[test]` function:
+//!
+//! ```rust,ignore
+//! let mut machine = TestMachine::<Counter>::new(&[Event::Tick], Default::default());
+//! machine.step(Input::Event(Event::Tick));
+//! assert_eq!(machine.state().count, 2);
+//! assert_eq!(machine.take_events(), [Event::Tick]);
+//! ```
+use crate::{Effects, Event, In, Out, State};
+use edfsm::{Drain, Fsm, Init};
+use std::{mem, vec::Vec};
+
+/// A state machine and its effector, stepped synchronously.
+///
+/// As a `Machine` does, this rehydrates the state from a history of events, initialises
+/// the effector with `Init::init` and then `Fsm::on_init`, steps each input with
+/// `Fsm::step_all`, and drains output messages after initialisation and each step.
+/// The events that would be logged and the output messages that would be dispatched
+/// are instead accumulated, to be taken by the test.
+pub struct TestMachine<M>
+where
+    M: Fsm,
+    Effects<M>: Drain,
+{
+    state: State<M>,
+    effects: Effects<M>,
+    events: Vec<Event<M>>,
+    outputs: Vec<Out<M>>,
+}
+
+impl<M> TestMachine<M>
+where
+    M: Fsm,
+    Effects<M>: Drain + Init<State<M>>,
+    State<M>: Default,
+{
+    /// Create a machine rehydrated from a history of events, and initialise it.
+    pub fn new(history: &[Event<M>], mut effects: Effects<M>) -> Self {
+        let state = edfsm::replay::<M>(history);
+        effects.init(&state);
+        M::on_init(&state, &mut effects);
+        let outputs = effects.drain_all().collect();
+        Self {
+            state,
+            effects,
+            events: Vec::new(),
+            outputs,
+        }
+    }
+}
+
+impl<M> TestMachine<M>
+where
+    M: Fsm,
+    Effects<M>: Drain,
+{
+    /// Step an input, accumulating the events produced and then the output messages.
+    pub fn step(&mut self, input: In<M>) {
+        self.events
+            .extend(M::step_all(&mut self.state, input, &mut self.effects));
+        self.outputs.extend(self.effects.drain_all());
+    }
+
+    /// Perform final effects with `Fsm::on_stop`, accumulating their output messages.
+    pub fn stop(&mut self) {
+        M::on_stop(&self.state, &mut self.effects);
+        self.outputs.extend(self.effects.drain_all());
+    }
+
+    /// The current state.
+    pub fn state(&self) -> &State<M> {
+        &self.state
+    }
+
+    /// The effector.
+    pub fn effects(&self) -> &Effects<M> {
+        &self.effects
+    }
+
+    /// The effector, mutably.
+    pub fn effects_mut(&mut self) -> &mut Effects<M> {
+        &mut self.effects
+    }
+
+    /// Take the output messages accumulated since last taken.
+    pub fn drain_outputs(&mut self) -> Vec<Out<M>> {
+        mem::take(&mut self.outputs)
+    }
+
+    /// Take the events accumulated since last taken.
+    pub fn take_events(&mut self) -> Vec<Event<M>> {
+        mem::take(&mut self.events)
+    }
+}
@@ -0,0 +1,142 @@
+use std::{boxed::Box, collections::BTreeMap, vec::Vec};
+use streambed_codec::Codec;
+
+/// Type of a function that decodes a record of an earlier version.
+pub type Migration<A> = Box<dyn Fn(&mut [u8]) -> Option<A> + Send + Sync>;
+
+/// A `Codec` that tags each record with a version byte, so that an event log
+/// can outlive changes to its event type.
+///
+/// Records are encoded by the wrapped codec and prefixed with the current version.
+/// Records of the current version are decoded by the wrapped codec, whereas records
+/// of an earlier version are decoded by the migration registered for that version.
+///
+/// The current version is 1, or one more than the latest registered migration.
+/// For example, having evolved an event type, a migration for version 1 can be
+/// registered. New records will then be tagged as version 2.
+pub struct Versioned<C, A> {
+    current: C,
+    version: u8,
+    migrations: BTreeMap<u8, Migration<A>>,
+}
+
+impl<C, A> Versioned<C, A> {
+    /// Wrap the codec for the current version of the event type.
+    pub fn new(current: C) -> Self {
+        Self {
+            current,
+            version: 1,
+            migrations: BTreeMap::new(),
+        }
+    }
+
+    /// Register a function to decode records of an earlier version.
+    pub fn with_migration(
+        mut self,
+        version: u8,
+        migration: impl Fn(&mut [u8]) -> Option<A> + Send + Sync + 'static,
+    ) -> Self {
+        self.version = self.version.max(version.saturating_add(1));
+        self.migrations.insert(version, Box::new(migration));
+        self
+    }
+
+    /// The version that records are tagged with when encoded.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+}
+
+impl<C, A> Codec<A> for Versioned<C, A>
+where
+    C: Codec<A>,
+{
+    fn encode(&self, item: A) -> Option<Vec<u8>> {
+        let mut bytes = self.current.encode(item)?;
+        bytes.insert(0, self.version);
+        Some(bytes)
+    }
+
+    fn decode(&self, bytes: &mut [u8]) -> Option<A> {
+        let (version, bytes) = bytes.split_first_mut()?;
+        if *version == self.version {
+            self.current.decode(bytes)
+        } else {
+            self.migrations.get(version)?(bytes)
+        }
+    }
+}
+
+/// A `Codec` composed of a list of codecs, for a log holding records of more than one encoding.
+///
+/// Records are encoded by the first codec, and decoded by the first codec that succeeds.
+/// This supports a gradual migration between codecs within one log: new records are
+/// encoded with the new codec listed first, while existing records remain decodable
+/// by the old codec listed after it.
+///
+/// The list is an array of codecs of the same type, `FirstSuccessful([c1, c2])`, or a
+/// tuple of codecs of differing types, `FirstSuccessful((Versioned::new(Cbor), Cbor))`.
+/// Codecs should be listed so that each does not succeed in decoding the records of those
+/// after it, which is typically assured by a `Versioned` codec. Each codec decodes a
+/// copy of the record, as a codec may modify the bytes in place, for example when decrypting.
+pub struct FirstSuccessful<T>(pub T);
+
+/// A list of codecs, as composed by `FirstSuccessful`.
+pub trait Codecs<A> {
+    /// Encode a value with the first codec.
+    fn encode_first(&self, item: A) -> Option<Vec<u8>>;
+    /// Decode a value with the first codec that succeeds.
+    fn decode_any(&self, bytes: &mut [u8]) -> Option<A>;
+}
+
+impl<C, A, const N: usize> Codecs<A> for [C; N]
+where
+    C: Codec<A>,
+{
+    fn encode_first(&self, item: A) -> Option<Vec<u8>> {
+        self.first()?.encode(item)
+    }
+
+    fn decode_any(&self, bytes: &mut [u8]) -> Option<A> {
+        self.iter().find_map(|c| c.decode(&mut bytes.to_vec()))
+    }
+}
+
+macro_rules! codecs_tuple {
+    ($first:ident $(, $rest:ident)*) => {
+        impl<A, $first, $($rest),*> Codecs<A> for ($first, $($rest),*)
+        where
+            $first: Codec<A>,
+            $($rest: Codec<A>),*
+        {
+            fn encode_first(&self, item: A) -> Option<Vec<u8>> {
+                self.0.encode(item)
+            }
+
+            #[allow(non_snake_case)]
+            fn decode_any(&self, bytes: &mut [u8]) -> Option<A> {
+                let ($first, $($rest),*) = self;
+                $first
+                    .decode(&mut bytes.to_vec())
+                    $(.or_else(|| $rest.decode(&mut bytes.to_vec())))*
+            }
+        }
+    };
+}
+
+codecs_tuple!(C1, C2);
+codecs_tuple!(C1, C2, C3);
+codecs_tuple!(C1, C2, C3, C4);
+
+impl<T, A> Codec<A> for FirstSuccessful<T>
+where
+    T: Codecs<A>,
+{
+    fn encode(&self, item: A) -> Option<Vec<u8>> {
+        self.0.encode_first(item)
+    }
+
+    fn decode(&self, bytes: &mut [u8]) -> Option<A> {
+        self.0.decode_any(bytes)
+    }
+}
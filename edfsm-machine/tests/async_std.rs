@@ -0,0 +1,38 @@
+pub mod fixtures;
+use async_std::task;
+use edfsm::Input;
+use edfsm_machine::{adapter::Adapter, error::Result, machine, Machine};
+use fixtures::{Command, Counter, Event, Output};
+
+#[test]
+fn async_std_test() -> Result<()> {
+    task::block_on(async {
+        let (send_o, recv_o) = async_channel::bounded::<Output>(3);
+        let (send_e, recv_e) = async_channel::unbounded::<Event>();
+
+        let machine = machine::<Counter>()
+            .with_output(send_o)
+            .merge_event_log(send_e);
+        let mut input = machine.input();
+
+        let machine_task = task::spawn(machine.task());
+        let output_task = task::spawn(async move {
+            let mut count = 0;
+            while recv_o.recv().await.is_ok() {
+                count += 1;
+            }
+            count
+        });
+
+        for _ in 0..30 {
+            input.notify(Input::Event(Event::Tick)).await?;
+        }
+        input.notify(Input::Command(Command::Assert(30))).await?;
+        drop(input);
+
+        machine_task.await?;
+        assert_eq!(output_task.await, 3);
+        assert_eq!(recv_e.len(), 30);
+        Ok(())
+    })
+}
@@ -0,0 +1,59 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::{
+    adapter::{Adapter, Feed},
+    error::Result,
+    machine, Machine,
+};
+use fixtures::{Counter, Event};
+use futures_util::{Stream, StreamExt};
+use std::sync::{Arc, Mutex};
+
+/// An event log that records the number of events in each notification.
+#[derive(Clone, Default)]
+struct BatchLog(Arc<Mutex<Vec<usize>>>);
+
+impl Adapter for BatchLog {
+    type Item = Event;
+
+    async fn notify(&mut self, _e: Event) -> Result<()> {
+        self.0.lock().unwrap().push(1);
+        Ok(())
+    }
+
+    async fn notify_all<S>(&mut self, items: S) -> Result<()>
+    where
+        S: Stream<Item = Event> + Send,
+    {
+        let count = items.count().await;
+        self.0.lock().unwrap().push(count);
+        Ok(())
+    }
+}
+
+impl Feed for BatchLog {
+    type Item = Event;
+
+    async fn feed(&self, _output: &mut impl Adapter<Item = Event>) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn batch_logging_test() -> Result<()> {
+    let log = BatchLog::default();
+    let machine = machine::<Counter>()
+        .with_batch(4)
+        .log_injected_events(true)
+        .with_event_log(log.clone());
+    let input = machine.input();
+    for _ in 0..8 {
+        input.send(Input::Event(Event::Tick)).await?;
+    }
+    drop(input);
+    machine.task().await?;
+
+    // The events of each batch are logged together
+    assert_eq!(*log.0.lock().unwrap(), [4, 4]);
+    Ok(())
+}
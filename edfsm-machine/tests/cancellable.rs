@@ -0,0 +1,46 @@
+use edfsm_machine::{
+    adapter::{Adapter, Feed},
+    error::{Error, Result},
+};
+use tokio_util::sync::CancellationToken;
+
+/// Collects items, cancelling the token once a number have been seen.
+struct CancelAfter {
+    seen: Vec<u32>,
+    at: usize,
+    cancel: CancellationToken,
+}
+
+impl Adapter for CancelAfter {
+    type Item = u32;
+
+    async fn notify(&mut self, a: Self::Item) -> Result<()> {
+        self.seen.push(a);
+        if self.seen.len() == self.at {
+            self.cancel.cancel();
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn feed_cancellable_test() {
+    let log: Vec<u32> = (0..10).collect();
+
+    let cancel = CancellationToken::new();
+    let mut output = CancelAfter {
+        seen: Vec::new(),
+        at: 3,
+        cancel: cancel.clone(),
+    };
+    let r = log.feed_cancellable(&mut output, cancel).await;
+    assert!(matches!(r, Err(Error::Cancelled)));
+    assert_eq!(output.seen, [0, 1, 2]);
+
+    let mut output = Vec::new();
+    let r = log
+        .feed_cancellable(&mut output, CancellationToken::new())
+        .await;
+    assert!(r.is_ok());
+    assert_eq!(output, log);
+}
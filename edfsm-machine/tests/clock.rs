@@ -0,0 +1,67 @@
+use core::future::{ready, Future};
+use edfsm::Clock;
+use edfsm_machine::{
+    adapter::{Adapter, Retry},
+    clock::TokioClock,
+    error::{Error, Result},
+};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// A virtual clock whose time advances only when it is slept on.
+#[derive(Clone, Default)]
+struct ManualClock(Arc<Mutex<Duration>>);
+
+impl Clock for ManualClock {
+    type Instant = Duration;
+
+    fn now(&self) -> Self::Instant {
+        *self.0.lock().unwrap()
+    }
+
+    fn deadline(&self, after: Duration) -> Self::Instant {
+        self.now() + after
+    }
+
+    fn sleep_until(&self, deadline: Self::Instant) -> impl Future<Output = ()> + Send {
+        let mut now = self.0.lock().unwrap();
+        *now = (*now).max(deadline);
+        ready(())
+    }
+}
+
+/// An adapter that always fails.
+struct Failing;
+
+impl Adapter for Failing {
+    type Item = u32;
+
+    async fn notify(&mut self, _a: Self::Item) -> Result<()> {
+        Err(Error::ChannelClosed)
+    }
+}
+
+#[test]
+fn retry_with_clock_test() {
+    let clock = ManualClock::default();
+    let mut retry = Retry::with_clock(Failing, 10, clock.clone())
+        .with_backoff(Duration::from_secs(1), Duration::from_secs(60));
+
+    // Delays of 1, 2, 4, 8 and 16s fit within the bound, but not 32s more
+    let result = async_std::task::block_on(retry.notify(42));
+    assert!(result.is_err());
+    assert_eq!(clock.now(), Duration::from_secs(31));
+}
+
+#[tokio::test]
+async fn tokio_clock_test() {
+    let clock = TokioClock;
+    let start = clock.now();
+    clock.sleep(Duration::from_millis(5)).await;
+    assert!(clock.now() >= start + Duration::from_millis(5));
+
+    // A deadline in the past completes immediately
+    clock.sleep_until(start).await;
+}
@@ -0,0 +1,53 @@
+pub mod fixtures;
+use edfsm::{CompactionKey, Input};
+use edfsm_machine::{adapter::CompactedLog, error::Result, machine, memlog::MemLog, Machine};
+use fixtures::{Command, Counter, Event};
+use futures_util::StreamExt;
+use streambed::commit_log::{CommitLog, Subscription};
+use streambed_codec::Cbor;
+
+const TOPIC: &str = "event_series";
+
+impl CompactionKey for Event {
+    fn compaction_key(&self) -> u64 {
+        match self {
+            Event::Tick => 1,
+            Event::Reset => 2,
+            Event::Stop => 3,
+        }
+    }
+}
+
+#[tokio::test]
+async fn compacted_log_test() -> Result<()> {
+    let mem = MemLog::new();
+
+    // Record events with one machine
+    let recorder = machine::<Counter>().with_event_log(CompactedLog::new(mem.clone(), TOPIC, Cbor));
+    let input = recorder.input();
+    let task = tokio::spawn(recorder.task());
+    input.send(Input::Event(Event::Tick)).await?;
+    input.send(Input::Event(Event::Reset)).await?;
+    input.send(Input::Event(Event::Tick)).await?;
+    drop(input);
+    task.await.unwrap()?;
+
+    // Records are keyed by the events
+    let subscriptions = vec![Subscription {
+        topic: TOPIC.into(),
+    }];
+    let keys: Vec<_> = mem
+        .scoped_subscribe("test", Vec::new(), subscriptions, None)
+        .map(|r| r.key)
+        .collect()
+        .await;
+    assert_eq!(keys, [1, 2, 1]);
+
+    // Rehydrate another from them
+    let machine = machine::<Counter>().with_event_log(CompactedLog::new(mem, TOPIC, Cbor));
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+    input.send(Input::Command(Command::Assert(1))).await?;
+    drop(input);
+    task.await.unwrap()
+}
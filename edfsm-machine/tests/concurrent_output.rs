@@ -0,0 +1,109 @@
+use edfsm::{Change, Fsm, Input, Terminating};
+use edfsm_machine::{adapter::Adapter, error::Result, machine, output::OutputBuffer, Machine};
+use std::time::Duration;
+use tokio::{
+    sync::mpsc::{channel, Receiver},
+    time::{sleep, Instant},
+};
+
+/// Outputs each item that it receives.
+struct Echo;
+
+#[derive(Debug, Clone)]
+struct Item(u32);
+
+impl Terminating for Item {
+    fn terminating(&self) -> bool {
+        false
+    }
+}
+
+impl Fsm for Echo {
+    type S = ();
+    type C = ();
+    type E = Item;
+    type SE = OutputBuffer<u32>;
+
+    fn for_command(_s: &Self::S, _c: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
+        None
+    }
+
+    fn on_event(_s: &mut Self::S, _e: &Self::E) -> Option<Change> {
+        Some(Change::Updated)
+    }
+
+    fn on_change(_s: &Self::S, e: &Self::E, se: &mut Self::SE, _change: Change) {
+        se.push(e.0);
+    }
+}
+
+/// An adapter that takes a while to accept each item.
+struct Slow<T>(T);
+
+const DELAY: Duration = Duration::from_millis(10);
+
+impl<T> Adapter for Slow<T>
+where
+    T: Adapter,
+    T::Item: Send,
+{
+    type Item = T::Item;
+
+    async fn notify(&mut self, a: Self::Item) -> Result<()>
+    where
+        Self::Item: 'static,
+    {
+        sleep(DELAY).await;
+        self.0.notify(a).await
+    }
+}
+
+const ITEMS: u32 = 20;
+
+/// Run the machine with a slow event log and a slow output,
+/// returning the time taken and the output messages.
+async fn run(concurrent: bool) -> Result<(Duration, Receiver<u32>)> {
+    let (sink, outputs) = channel(ITEMS as usize);
+    let machine = machine::<Echo>()
+        .merge_event_log(Slow(Vec::new()))
+        .with_output(Slow(sink));
+    let start = Instant::now();
+    let input = machine.input();
+    if concurrent {
+        let (machine, forward) = machine.concurrent_output(ITEMS as usize);
+        let task = tokio::spawn(machine.task());
+        let forward = tokio::spawn(forward);
+        for n in 0..ITEMS {
+            input.send(Input::Event(Item(n))).await?;
+        }
+        drop(input);
+        task.await.unwrap()?;
+        forward.await.unwrap()?;
+    } else {
+        let task = tokio::spawn(machine.task());
+        for n in 0..ITEMS {
+            input.send(Input::Event(Item(n))).await?;
+        }
+        drop(input);
+        task.await.unwrap()?;
+    }
+    Ok((start.elapsed(), outputs))
+}
+
+#[tokio::test(start_paused = true)]
+async fn concurrent_output_test() -> Result<()> {
+    let (serial, _) = run(false).await?;
+    let (concurrent, mut outputs) = run(true).await?;
+
+    // Logging and output overlap, rather than each input waiting for both
+    println!("{ITEMS} items: serial {serial:?}, concurrent {concurrent:?}");
+    assert_eq!(serial, DELAY * ITEMS * 2);
+    assert!(concurrent <= DELAY * (ITEMS + 1));
+
+    // Output messages remain in order
+    for n in 0..ITEMS {
+        assert_eq!(outputs.recv().await, Some(n));
+    }
+    assert_eq!(outputs.recv().await, None);
+    Ok(())
+}
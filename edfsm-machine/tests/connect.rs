@@ -0,0 +1,79 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::{
+    connect,
+    error::Result,
+    health::{HealthHandle, Status},
+    machine, machine_with_effects, Machine,
+};
+use fixtures::{Counter, Event, Output};
+use std::time::Duration;
+use tokio::{sync::mpsc::channel, time::sleep};
+
+/// Each tock of the first counter ticks the second.
+fn tick(o: Output) -> Option<Input<fixtures::Command, Event>> {
+    match o {
+        Output::Tock => Some(Input::Event(Event::Tick)),
+    }
+}
+
+#[tokio::test]
+async fn connect_test() -> Result<()> {
+    // The second counter also outputs its own tocks
+    let (sink, mut outputs) = channel(10);
+    let seconds = machine::<Counter>().with_output(sink);
+    let (minutes, hours) = connect(seconds, machine::<Counter>(), tick);
+
+    let input = minutes.input();
+    let minutes = tokio::spawn(minutes.task());
+    let hours = tokio::spawn(hours.task_with_state());
+    for _ in 0..100 {
+        input.send(Input::Event(Event::Tick)).await?;
+    }
+    drop(input);
+    minutes.await.unwrap()?;
+
+    // The second task ends once the first has ended
+    let (state, _) = hours.await.unwrap()?;
+    assert_eq!(state.count, 10);
+
+    // Other outputs of the first machine are retained
+    let mut tocks = 0;
+    while outputs.recv().await.is_some() {
+        tocks += 1;
+    }
+    assert_eq!(tocks, 10);
+    Ok(())
+}
+
+async fn wait_for(health: &HealthHandle, status: Status) {
+    while health.status() != status {
+        sleep(Duration::from_millis(1)).await;
+    }
+}
+
+#[tokio::test]
+async fn connect_backpressure() -> Result<()> {
+    // The second machine has room for one input and is not yet running
+    let slow = machine_with_effects::<Counter>(Default::default(), 1);
+    let (fast, slow) = connect(machine::<Counter>(), slow, tick);
+    let (fast, health) = fast.health();
+    let input = fast.input();
+    let fast = tokio::spawn(fast.task());
+
+    // The second tock does not fit, so the first machine stalls
+    for _ in 0..20 {
+        input.send(Input::Event(Event::Tick)).await?;
+    }
+    drop(input);
+    wait_for(&health, Status::BlockedOnOutput).await;
+    sleep(Duration::from_millis(50)).await;
+    assert!(!fast.is_finished());
+
+    // It continues once the second machine takes its input
+    let slow = tokio::spawn(slow.task_with_state());
+    fast.await.unwrap()?;
+    let (state, _) = slow.await.unwrap()?;
+    assert_eq!(state.count, 2);
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use edfsm::{Change, Fsm, Input, Terminating};
+use edfsm_machine::{
+    adapter::Adapter, durable::DurableInput, error::Result, machine, memlog::MemLog,
+    output::OutputBuffer, Machine,
+};
+use serde::{Deserialize, Serialize};
+use streambed_codec::{Cbor, CommitLogExt};
+use tokio::sync::mpsc::channel;
+
+/// A state machine that sums the numbers it is given and outputs each total.
+struct Accumulator;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Add(i32);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Added(i32);
+
+impl Terminating for Added {
+    fn terminating(&self) -> bool {
+        false
+    }
+}
+
+impl Fsm for Accumulator {
+    type S = i32;
+    type C = Add;
+    type E = Added;
+    type SE = OutputBuffer<i32>;
+
+    fn for_command(_s: &Self::S, c: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
+        Some(Added(c.0))
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        *s += e.0;
+        Some(Change::Updated)
+    }
+
+    fn on_change(s: &Self::S, _e: &Self::E, se: &mut Self::SE, _change: Change) {
+        se.push(*s);
+    }
+}
+
+/// Run a machine that forwards the inputs queued in the log, returning its outputs.
+async fn run(mem: &MemLog) -> Result<Vec<i32>> {
+    let durable = DurableInput::new(mem.clone(), "commands", Cbor);
+    let (sender, mut outputs) = channel(10);
+    let (machine, processed) = machine::<Accumulator>()
+        .with_event_log(mem.clone().adapt::<Added>("events", Cbor))
+        .with_output(sender)
+        .processed();
+    let input = machine.input().with_map(Input::Command);
+    let task = tokio::spawn(machine.task());
+
+    durable.forward(input, processed).await?;
+    task.await.unwrap()?;
+
+    let mut totals = Vec::new();
+    while let Some(total) = outputs.recv().await {
+        totals.push(total);
+    }
+    Ok(totals)
+}
+
+#[tokio::test]
+async fn durable_input_test() -> Result<()> {
+    let mem = MemLog::new();
+    let mut durable = DurableInput::new(mem.clone(), "commands", Cbor);
+    assert_eq!(durable.acknowledged().await, None);
+
+    // Inputs queued before the machine runs are processed and acknowledged
+    durable.notify(Add(1)).await?;
+    durable.notify(Add(2)).await?;
+    assert_eq!(run(&mem).await?, [1, 3]);
+    assert_eq!(durable.acknowledged().await, Some(1));
+
+    // Inputs queued while the machine is down are processed on restart, and only those
+    durable.notify(Add(3)).await?;
+    durable.notify(Add(4)).await?;
+    assert_eq!(run(&mem).await?, [6, 10]);
+    assert_eq!(durable.acknowledged().await, Some(3));
+
+    // Nothing is forwarded again once all are acknowledged
+    assert_eq!(run(&mem).await?, []);
+    Ok(())
+}
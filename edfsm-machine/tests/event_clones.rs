@@ -0,0 +1,66 @@
+use edfsm::{Change, Fsm, Input, Terminating};
+use edfsm_machine::{error::Result, machine, output::OutputBuffer, Machine};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static CLONES: AtomicUsize = AtomicUsize::new(0);
+
+/// An event that counts its clones.
+#[derive(Debug)]
+struct Large;
+
+impl Clone for Large {
+    fn clone(&self) -> Self {
+        CLONES.fetch_add(1, Ordering::SeqCst);
+        Large
+    }
+}
+
+impl Terminating for Large {
+    fn terminating(&self) -> bool {
+        false
+    }
+}
+
+struct Sink;
+
+impl Fsm for Sink {
+    type S = ();
+    type C = ();
+    type E = Large;
+    type SE = OutputBuffer<()>;
+
+    fn for_command(_s: &Self::S, _c: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
+        None
+    }
+
+    fn on_event(_s: &mut Self::S, _e: &Self::E) -> Option<Change> {
+        Some(Change::Updated)
+    }
+
+    fn on_change(_s: &Self::S, _e: &Self::E, _se: &mut Self::SE, _change: Change) {}
+}
+
+async fn run(machine: impl Machine<Sink>) -> Result<usize> {
+    CLONES.store(0, Ordering::SeqCst);
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+    for _ in 0..3 {
+        input.send(Input::Event(Large)).await?;
+    }
+    drop(input);
+    task.await.unwrap()?;
+    Ok(CLONES.load(Ordering::SeqCst))
+}
+
+#[tokio::test]
+async fn event_clones_test() -> Result<()> {
+    // A single destination receives each event without a clone
+    assert_eq!(run(machine::<Sink>().with_event_log(Vec::new())).await?, 0);
+
+    // Fanning out clones each event once
+    let fan_out = machine::<Sink>()
+        .with_event_log(Vec::new())
+        .merge_event_log(Vec::new());
+    assert_eq!(run(fan_out).await?, 3);
+    Ok(())
+}
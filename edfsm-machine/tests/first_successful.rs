@@ -0,0 +1,67 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::{
+    error::Result,
+    machine,
+    memlog::MemLog,
+    versioned::{FirstSuccessful, Versioned},
+    Machine,
+};
+use fixtures::{Command, Counter, Event};
+use streambed_codec::{Cbor, Codec, CommitLogExt};
+
+const TOPIC: &str = "event_series";
+
+#[test]
+fn first_successful_codec() {
+    let codec = FirstSuccessful((Versioned::<_, Event>::new(Cbor), Cbor));
+
+    // Encoded by the first codec
+    let mut record = codec.encode(Event::Tick).unwrap();
+    assert_eq!(record[0], 1);
+    assert!(matches!(codec.decode(&mut record), Some(Event::Tick)));
+
+    // Decoded by the second codec
+    let mut record = Codec::<Event>::encode(&Cbor, Event::Reset).unwrap();
+    assert!(matches!(codec.decode(&mut record), Some(Event::Reset)));
+
+    // Decoded by neither
+    assert!(codec.decode(&mut [0xff]).is_none());
+
+    // An array of codecs of the same type
+    let codec = FirstSuccessful([Versioned::<_, Event>::new(Cbor)]);
+    let mut record = codec.encode(Event::Stop).unwrap();
+    assert!(matches!(codec.decode(&mut record), Some(Event::Stop)));
+}
+
+#[tokio::test]
+async fn first_successful_mixed_topic() -> Result<()> {
+    let mem = MemLog::new();
+    let codec = || FirstSuccessful((Versioned::new(Cbor), Cbor));
+
+    // Records in the old encoding
+    let old = mem.clone().adapt::<Event>(TOPIC, Cbor);
+    for _ in 0..3 {
+        old.produce(Event::Tick).await.unwrap();
+    }
+
+    // Further records in the new encoding
+    let recorder = machine::<Counter>().with_event_log(mem.clone().adapt(TOPIC, codec()));
+    let input = recorder.input();
+    let task = tokio::spawn(recorder.task());
+    input.send(Input::Command(Command::Assert(3))).await?;
+    for _ in 0..2 {
+        input.send(Input::Event(Event::Tick)).await?;
+    }
+    drop(input);
+    task.await.unwrap()?;
+    assert_eq!(mem.len(), 5);
+
+    // The whole topic replays
+    let machine = machine::<Counter>().with_event_log(mem.adapt(TOPIC, codec()));
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+    input.send(Input::Command(Command::Assert(5))).await?;
+    drop(input);
+    task.await.unwrap()
+}
@@ -22,9 +22,9 @@ pub enum Output {
     Tock,
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct State {
-    count: i32,
+    pub count: i32,
 }
 
 impl Fsm for Counter {
@@ -0,0 +1,60 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::{adapter::Adapter, error::Result, machine, Machine};
+use fixtures::{Counter, Event};
+use tokio::sync::mpsc::{channel, Sender};
+
+/// An adapter that holds items until flushed.
+struct Buffer<A> {
+    pending: Vec<A>,
+    inner: Sender<A>,
+}
+
+impl<A> Adapter for Buffer<A>
+where
+    A: Send,
+{
+    type Item = A;
+
+    async fn notify(&mut self, a: Self::Item) -> Result<()>
+    where
+        Self::Item: 'static,
+    {
+        self.pending.push(a);
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        for a in self.pending.drain(..) {
+            self.inner.send(a).await?;
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn flush_test() -> Result<()> {
+    let (send_e, mut recv_e) = channel(10);
+    let events = Buffer {
+        pending: Vec::new(),
+        inner: send_e,
+    }
+    .with_map(|e: Event| format!("{e:?}"));
+
+    let machine = machine::<Counter>().merge_event_log(events);
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+
+    input.send(Input::Event(Event::Tick)).await?;
+    input.send(Input::Event(Event::Tick)).await?;
+    tokio::task::yield_now().await;
+    assert!(recv_e.try_recv().is_err());
+
+    // Buffered events are forwarded when the machine stops
+    drop(input);
+    task.await.unwrap()?;
+    assert_eq!(recv_e.recv().await.as_deref(), Some("Tick"));
+    assert_eq!(recv_e.recv().await.as_deref(), Some("Tick"));
+    assert_eq!(recv_e.recv().await, None);
+    Ok(())
+}
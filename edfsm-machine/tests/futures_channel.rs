@@ -0,0 +1,35 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::{adapter::Adapter, error::Result, machine, Machine};
+use fixtures::{Command, Counter, Event, Output};
+use futures_channel::mpsc;
+use futures_util::StreamExt;
+
+#[tokio::test]
+async fn futures_channel_test() -> Result<()> {
+    let (send_o, recv_o) = mpsc::channel::<Output>(3);
+    let machine = machine::<Counter>().with_output(send_o);
+    let mut input = machine.input();
+    let task = tokio::spawn(machine.task());
+    let consumer = tokio::spawn(recv_o.count());
+
+    for _ in 0..30 {
+        input.notify(Input::Event(Event::Tick)).await?;
+    }
+    input.notify(Input::Command(Command::Assert(30))).await?;
+    drop(input);
+
+    task.await.unwrap()?;
+    assert_eq!(consumer.await.unwrap(), 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn futures_channel_closed_test() {
+    let (mut sender, receiver) = mpsc::channel::<u32>(1);
+    drop(receiver);
+    assert!(matches!(
+        sender.notify(1).await,
+        Err(edfsm_machine::error::Error::ChannelClosed)
+    ));
+}
@@ -0,0 +1,48 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::{
+    error::Result,
+    health::{HealthHandle, Status},
+    machine, Machine,
+};
+use fixtures::{Counter, Event};
+use std::time::Duration;
+use tokio::{sync::mpsc::channel, time::sleep};
+
+async fn wait_for(health: &HealthHandle, status: Status) {
+    while health.status() != status {
+        sleep(Duration::from_millis(1)).await;
+    }
+}
+
+#[tokio::test]
+async fn health_test() -> Result<()> {
+    // An output channel with room for one message, which is not received
+    let (sink, mut outputs) = channel(1);
+    let (machine, health) = machine::<Counter>().with_output(sink).health();
+    assert_eq!(health.status(), Status::Idle);
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+
+    // Waiting for input is idle
+    input.send(Input::Event(Event::Tick)).await?;
+    wait_for(&health, Status::Idle).await;
+
+    // The second output message cannot be dispatched
+    for _ in 0..19 {
+        input.send(Input::Event(Event::Tick)).await?;
+    }
+    wait_for(&health, Status::BlockedOnOutput).await;
+    sleep(Duration::from_millis(50)).await;
+    assert_eq!(health.status(), Status::BlockedOnOutput);
+
+    // It is dispatched once the sink takes messages again
+    outputs.recv().await.unwrap();
+    wait_for(&health, Status::Idle).await;
+    outputs.recv().await.unwrap();
+
+    drop(input);
+    task.await.unwrap()?;
+    assert_eq!(health.status(), Status::Idle);
+    Ok(())
+}
@@ -0,0 +1,63 @@
+pub mod fixtures;
+use edfsm::{HeartbeatCommand, HeartbeatEvent, Heartbeating, Input, Summarize};
+use edfsm_machine::{
+    clock::{heartbeat, TokioClock},
+    error::Result,
+    machine, Machine,
+};
+use fixtures::{Command, Counter, Event, State};
+use std::time::Duration;
+use tokio::sync::mpsc::channel;
+
+impl Summarize for State {
+    type Summary = i32;
+
+    fn summary(&self) -> Self::Summary {
+        self.count
+    }
+}
+
+#[tokio::test]
+async fn heartbeat_test() -> Result<()> {
+    let (send_e, mut events) = channel(10);
+    let machine = machine::<Heartbeating<Counter>>().merge_event_log(send_e);
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+    let beats = tokio::spawn(heartbeat(
+        input.clone(),
+        TokioClock,
+        Duration::from_millis(5),
+    ));
+
+    input
+        .send(Input::Event(HeartbeatEvent::Event(Event::Tick)))
+        .await?;
+    assert!(matches!(
+        events.recv().await,
+        Some(HeartbeatEvent::Event(Event::Tick))
+    ));
+
+    // Heartbeats are logged when idle and carry the state summary
+    assert!(matches!(
+        events.recv().await,
+        Some(HeartbeatEvent::Heartbeat(1))
+    ));
+    assert!(matches!(
+        events.recv().await,
+        Some(HeartbeatEvent::Heartbeat(1))
+    ));
+
+    // A heartbeat leaves the state unchanged
+    input
+        .send(Input::Command(HeartbeatCommand::Command(Command::Assert(
+            1,
+        ))))
+        .await?;
+
+    input
+        .send(Input::Event(HeartbeatEvent::Event(Event::Stop)))
+        .await?;
+    task.await.unwrap()?;
+    assert!(beats.await.unwrap().is_err());
+    Ok(())
+}
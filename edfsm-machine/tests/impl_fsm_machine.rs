@@ -0,0 +1,96 @@
+use edfsm::{impl_fsm, Fsm, Input, Terminating};
+use edfsm_machine::{error::Result, output::OutputBuffer, Machine};
+use tokio::sync::mpsc::channel;
+
+#[derive(Debug)]
+struct Off;
+#[derive(Debug)]
+struct On;
+#[derive(Debug)]
+enum State {
+    Off(Off),
+    On(On),
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State::Off(Off)
+    }
+}
+
+struct Toggle;
+enum Command {
+    Toggle(Toggle),
+}
+
+#[derive(Clone, Debug)]
+struct Toggled;
+#[derive(Clone, Debug)]
+enum Event {
+    Toggled(Toggled),
+}
+
+impl Terminating for Event {
+    fn terminating(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Lit;
+
+struct Switch;
+
+#[impl_fsm(machine)]
+impl Fsm for Switch {
+    type S = State;
+    type C = Command;
+    type E = Event;
+    type SE = OutputBuffer<Lit>;
+
+    state!(On / entry);
+
+    command!(Off => Toggle => Toggled => On);
+    command!(On => Toggle => Toggled => Off);
+}
+
+impl Switch {
+    fn for_off_toggle(_s: &Off, _c: Toggle, _se: &mut OutputBuffer<Lit>) -> Option<Toggled> {
+        Some(Toggled)
+    }
+
+    fn for_on_toggle(_s: &On, _c: Toggle, _se: &mut OutputBuffer<Lit>) -> Option<Toggled> {
+        Some(Toggled)
+    }
+
+    fn on_off_toggled(_s: &Off, _e: &Toggled) -> Option<On> {
+        Some(On)
+    }
+
+    fn on_on_toggled(_s: &On, _e: &Toggled) -> Option<Off> {
+        Some(Off)
+    }
+
+    fn on_entry_on(_s: &On, se: &mut OutputBuffer<Lit>) {
+        se.push(Lit);
+    }
+}
+
+#[tokio::test]
+async fn impl_fsm_machine_test() -> Result<()> {
+    let (sender, mut outputs) = channel(3);
+    let machine = Switch::machine().with_output(sender);
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+
+    for _ in 0..3 {
+        input.send(Input::Command(Command::Toggle(Toggle))).await?;
+    }
+    drop(input);
+    task.await.unwrap()?;
+
+    assert_eq!(outputs.recv().await, Some(Lit));
+    assert_eq!(outputs.recv().await, Some(Lit));
+    assert_eq!(outputs.recv().await, None);
+    Ok(())
+}
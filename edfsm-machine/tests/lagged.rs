@@ -0,0 +1,32 @@
+use edfsm_machine::{
+    adapter::adapt_tokio::forward,
+    error::{Error, Result},
+};
+use tokio::sync::broadcast;
+
+#[tokio::test]
+async fn forward_test() -> Result<()> {
+    let (sender, receiver) = broadcast::channel(2);
+    sender.send(1)?;
+    sender.send(2)?;
+    drop(sender);
+
+    let mut output = Vec::new();
+    forward(receiver, &mut output).await?;
+    assert_eq!(output, [1, 2]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn lagged_test() -> Result<()> {
+    let (sender, receiver) = broadcast::channel(2);
+    for i in 0..5 {
+        sender.send(i)?;
+    }
+
+    let mut output = Vec::new();
+    let r = forward(receiver, &mut output).await;
+    assert!(matches!(r, Err(Error::Lagged(3))));
+    assert!(output.is_empty());
+    Ok(())
+}
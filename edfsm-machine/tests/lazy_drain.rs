@@ -0,0 +1,99 @@
+use edfsm::{Change, Drain, Fsm, Init, Input, Terminating};
+use edfsm_machine::{adapter::Adapter, error::Result, machine_with_effects, Machine};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+/// A state machine that requests a number of output messages with each event.
+struct Generator;
+
+/// A request for a number of output messages.
+#[derive(Clone, Debug)]
+struct Request(usize);
+
+impl Terminating for Request {
+    fn terminating(&self) -> bool {
+        false
+    }
+}
+
+/// An effector that generates the requested output messages as they are drained.
+#[derive(Default)]
+struct Lazy {
+    pending: usize,
+    generated: Arc<AtomicUsize>,
+}
+
+impl Init<()> for Lazy {
+    fn init(&mut self, _state: &()) {}
+}
+
+impl Drain for Lazy {
+    type Item = usize;
+
+    fn drain_all(&mut self) -> impl Iterator<Item = Self::Item> + Send {
+        let generated = self.generated.clone();
+        (0..core::mem::take(&mut self.pending))
+            .map(move |_| generated.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+}
+
+impl Fsm for Generator {
+    type S = ();
+    type C = ();
+    type E = Request;
+    type SE = Lazy;
+
+    fn for_command(_s: &Self::S, _c: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
+        None
+    }
+
+    fn on_event(_s: &mut Self::S, _e: &Self::E) -> Option<Change> {
+        Some(Change::Updated)
+    }
+
+    fn on_change(_s: &Self::S, e: &Self::E, se: &mut Self::SE, _change: Change) {
+        se.pending += e.0;
+    }
+}
+
+/// An adapter that checks each message is generated just before it is notified.
+struct Check {
+    generated: Arc<AtomicUsize>,
+    notified: usize,
+}
+
+impl Adapter for Check {
+    type Item = usize;
+
+    async fn notify(&mut self, a: Self::Item) -> Result<()> {
+        self.notified += 1;
+        assert_eq!(a, self.notified);
+        assert_eq!(self.generated.load(Ordering::SeqCst), self.notified);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn lazy_drain_test() -> Result<()> {
+    let generated = Arc::new(AtomicUsize::new(0));
+    let effects = Lazy {
+        pending: 0,
+        generated: generated.clone(),
+    };
+    let machine = machine_with_effects::<Generator>(effects, 10).with_output(Check {
+        generated: generated.clone(),
+        notified: 0,
+    });
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+
+    input.send(Input::Event(Request(1000))).await?;
+    input.send(Input::Event(Request(24))).await?;
+    drop(input);
+    task.await.unwrap()?;
+
+    assert_eq!(generated.load(Ordering::SeqCst), 1024);
+    Ok(())
+}
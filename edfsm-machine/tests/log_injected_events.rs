@@ -0,0 +1,81 @@
+use edfsm::{Change, Fsm, Input, Terminating};
+use edfsm_machine::{error::Result, machine, memlog::MemLog, output::OutputBuffer, Machine};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use streambed_codec::{Cbor, CommitLogExt};
+use tokio::sync::mpsc::channel;
+
+/// Totals amounts added locally or by a leader whose events it follows.
+struct Follower;
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    total: u32,
+}
+
+#[derive(Debug)]
+enum Command {
+    Add(u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Event {
+    Added(u32),
+}
+
+impl Terminating for Event {
+    fn terminating(&self) -> bool {
+        false
+    }
+}
+
+impl Fsm for Follower {
+    type S = State;
+    type C = Command;
+    type E = Event;
+    type SE = OutputBuffer<()>;
+
+    fn for_command(_s: &Self::S, c: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
+        let Command::Add(n) = c;
+        Some(Event::Added(n))
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        let Event::Added(n) = e;
+        s.total += n;
+        Some(Change::Updated)
+    }
+
+    fn on_change(_s: &Self::S, _e: &Self::E, _se: &mut Self::SE, _change: Change) {}
+}
+
+#[tokio::test]
+async fn log_injected_events_test() -> Result<()> {
+    let mem = MemLog::new();
+    let (send_e, mut events) = channel(10);
+    let follower = machine::<Follower>()
+        .with_event_log(mem.clone().adapt::<Event>("follower", Cbor))
+        .merge_event_log(send_e)
+        .log_injected_events(false);
+    let input = follower.input();
+    let task = tokio::spawn(follower.task_with_state());
+
+    // Events of the leader, received directly, and a local command
+    input.send(Input::Event(Event::Added(1))).await?;
+    input.send(Input::Command(Command::Add(10))).await?;
+    input.send(Input::Event(Event::Added(100))).await?;
+    drop(input);
+    let (state, _) = task.await.unwrap()?;
+
+    // Every event updates the state and reaches the event adapter
+    assert_eq!(state.total, 111);
+    for n in [1, 10, 100] {
+        assert_eq!(events.recv().await, Some(Event::Added(n)));
+    }
+
+    // Only the event produced by the command is logged
+    let log = mem.adapt::<Event>("follower", Cbor);
+    let logged: Vec<_> = log.history().await.collect().await;
+    assert_eq!(logged, [Event::Added(10)]);
+    Ok(())
+}
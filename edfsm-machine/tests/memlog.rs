@@ -0,0 +1,66 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::{error::Result, machine, memlog::MemLog, Machine};
+use fixtures::{Command, Counter, Event};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use streambed_codec::{Cbor, CommitLogExt};
+
+const TOPIC: &str = "event_series";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+enum Num {
+    Num(u32),
+}
+
+#[tokio::test]
+async fn memlog_history() {
+    let log = MemLog::new().adapt::<Num>(TOPIC, Cbor);
+    assert_eq!(log.history().await.count().await, 0);
+
+    for i in 1..100 {
+        let offset = log.produce(Num::Num(i)).await.unwrap();
+        assert_eq!(offset, u64::from(i) - 1);
+    }
+
+    let history: Vec<_> = log.history().await.collect().await;
+    assert_eq!(history, (1..100).map(Num::Num).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn memlog_topics() {
+    let mem = MemLog::new();
+    let first = mem.clone().adapt::<Num>("first", Cbor);
+    let second = mem.clone().adapt::<Num>("second", Cbor);
+
+    first.produce(Num::Num(1)).await.unwrap();
+    assert_eq!(second.produce(Num::Num(2)).await.unwrap(), 0);
+    first.produce(Num::Num(3)).await.unwrap();
+
+    let history: Vec<_> = first.history().await.collect().await;
+    assert_eq!(history, [Num::Num(1), Num::Num(3)]);
+    assert_eq!(mem.len(), 3);
+}
+
+#[tokio::test]
+async fn memlog_machine() -> Result<()> {
+    let mem = MemLog::new();
+
+    // Record events with one machine
+    let recorder = machine::<Counter>().with_event_log(mem.clone().adapt::<Event>(TOPIC, Cbor));
+    let input = recorder.input();
+    let task = tokio::spawn(recorder.task());
+    for _ in 0..5 {
+        input.send(Input::Event(Event::Tick)).await?;
+    }
+    drop(input);
+    task.await.unwrap()?;
+
+    // Rehydrate another from them
+    let machine = machine::<Counter>().with_event_log(mem.adapt::<Event>(TOPIC, Cbor));
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+    input.send(Input::Command(Command::Assert(5))).await?;
+    drop(input);
+    task.await.unwrap()
+}
@@ -0,0 +1,51 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::{
+    adapter::Adapter,
+    error::Result,
+    machine,
+    metrics::{MetricEvent, MetricSink, MetricsAdapter, NoopSink},
+    Machine,
+};
+use fixtures::{Command, Counter, Event, Output};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// A sink that totals a single counter.
+struct Total(Arc<AtomicU64>);
+
+impl MetricSink for Total {
+    fn incr(&mut self, _name: &'static str, value: u64) {
+        self.0.fetch_add(value, Ordering::Relaxed);
+    }
+
+    fn set(&mut self, _name: &'static str, _value: f64) {}
+}
+
+fn tocks(o: &Output) -> Option<MetricEvent> {
+    match o {
+        Output::Tock => Some(MetricEvent::Counter("tocks", 1)),
+    }
+}
+
+#[tokio::test]
+async fn metrics_test() -> Result<()> {
+    let total = Arc::new(AtomicU64::new(0));
+    let machine = machine::<Counter>()
+        .with_output(MetricsAdapter::new(tocks, Total(total.clone())))
+        .merge_output(MetricsAdapter::new(tocks, NoopSink));
+    let mut input = machine.input();
+    let task = tokio::spawn(machine.task());
+
+    for _ in 0..30 {
+        input.notify(Input::Event(Event::Tick)).await?;
+    }
+    input.notify(Input::Command(Command::Assert(30))).await?;
+    drop(input);
+    task.await.unwrap()?;
+
+    assert_eq!(total.load(Ordering::Relaxed), 3);
+    Ok(())
+}
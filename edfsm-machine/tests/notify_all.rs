@@ -0,0 +1,31 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::{adapter::Adapter, error::Result, machine, Machine};
+use fixtures::{Command, Counter, Event};
+use futures_util::stream;
+
+#[tokio::test]
+async fn notify_all_test() -> Result<()> {
+    let mut log = Vec::<Event>::default();
+    log.notify_all(stream::iter([Event::Tick, Event::Tick, Event::Reset]))
+        .await?;
+    assert!(matches!(
+        log.as_slice(),
+        [Event::Tick, Event::Tick, Event::Reset]
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn notify_all_machine_test() -> Result<()> {
+    let machine = machine::<Counter>();
+    let mut input = machine.input();
+    let task = tokio::spawn(machine.task());
+
+    let ticks = stream::iter((0..100).map(|_| Input::Event(Event::Tick)));
+    input.notify_all(ticks).await?;
+    input.notify(Input::Command(Command::Assert(100))).await?;
+    input.notify(Input::Event(Event::Stop)).await?;
+
+    task.await.unwrap()
+}
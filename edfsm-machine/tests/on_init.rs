@@ -0,0 +1,80 @@
+use edfsm::{Change, Fsm, Terminating};
+use edfsm_machine::{error::Result, machine, output::OutputBuffer, Machine};
+use tokio::sync::mpsc::channel;
+
+/// A state machine that starts its hardware on boot if it was running.
+struct Motor;
+
+#[derive(Debug, Default, PartialEq)]
+enum State {
+    #[default]
+    Stopped,
+    Running,
+}
+
+#[derive(Clone, Debug)]
+enum Event {
+    Started,
+    Stopped,
+}
+
+impl Terminating for Event {
+    fn terminating(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Output {
+    StartHardware,
+}
+
+impl Fsm for Motor {
+    type S = State;
+    type C = ();
+    type E = Event;
+    type SE = OutputBuffer<Output>;
+
+    fn for_command(_s: &Self::S, _c: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
+        None
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        *s = match e {
+            Event::Started => State::Running,
+            Event::Stopped => State::Stopped,
+        };
+        Some(Change::Transitioned)
+    }
+
+    fn on_change(_s: &Self::S, _e: &Self::E, _se: &mut Self::SE, _change: Change) {}
+
+    fn on_init(s: &Self::S, se: &mut Self::SE) {
+        if *s == State::Running {
+            se.push(Output::StartHardware);
+        }
+    }
+}
+
+async fn boot(history: Vec<Event>) -> Result<Vec<Output>> {
+    let (send_o, mut recv_o) = channel(3);
+    let machine = machine::<Motor>()
+        .with_event_log(history)
+        .with_output(send_o);
+    drop(machine.input());
+    machine.task().await?;
+
+    let mut outputs = Vec::new();
+    while let Some(o) = recv_o.recv().await {
+        outputs.push(o);
+    }
+    Ok(outputs)
+}
+
+#[tokio::test]
+async fn on_init_test() -> Result<()> {
+    assert_eq!(boot(vec![Event::Started]).await?, [Output::StartHardware]);
+    assert_eq!(boot(vec![Event::Started, Event::Stopped]).await?, []);
+    assert_eq!(boot(vec![]).await?, []);
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use edfsm::{Change, Fsm, Input, Terminating};
+use edfsm_machine::{error::Result, machine, output::OutputBuffer, Machine};
+use tokio::sync::mpsc::channel;
+
+/// A state machine that only reports its count when stopped.
+struct Tally;
+
+#[derive(Clone, Debug)]
+enum Event {
+    Tick,
+    Stop,
+}
+
+impl Terminating for Event {
+    fn terminating(&self) -> bool {
+        matches!(self, Event::Stop)
+    }
+}
+
+impl Fsm for Tally {
+    type S = i32;
+    type C = ();
+    type E = Event;
+    type SE = OutputBuffer<i32>;
+
+    fn for_command(_s: &Self::S, _c: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
+        None
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        match e {
+            Event::Tick => {
+                *s += 1;
+                Some(Change::Updated)
+            }
+            Event::Stop => Some(Change::Transitioned),
+        }
+    }
+
+    fn on_change(_s: &Self::S, _e: &Self::E, _se: &mut Self::SE, _change: Change) {}
+
+    fn on_stop(s: &Self::S, se: &mut Self::SE) {
+        se.push(*s);
+    }
+}
+
+async fn run(inputs: &[Event]) -> Result<Vec<i32>> {
+    let (send_o, mut recv_o) = channel(3);
+    let machine = machine::<Tally>().with_output(send_o);
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+
+    for e in inputs {
+        input.send(Input::Event(e.clone())).await?;
+    }
+    drop(input);
+    task.await.unwrap()?;
+
+    let mut outputs = Vec::new();
+    while let Some(o) = recv_o.recv().await {
+        outputs.push(o);
+    }
+    Ok(outputs)
+}
+
+#[tokio::test]
+async fn on_stop_closed_test() -> Result<()> {
+    assert_eq!(run(&[Event::Tick, Event::Tick]).await?, [2]);
+    assert_eq!(run(&[]).await?, [0]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn on_stop_terminating_test() -> Result<()> {
+    assert_eq!(run(&[Event::Tick, Event::Stop]).await?, [1]);
+    Ok(())
+}
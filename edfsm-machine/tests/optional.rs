@@ -0,0 +1,44 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::{adapter::Adapter, error::Result, machine, Machine};
+use fixtures::{Counter, Event, Output};
+use tokio::sync::mpsc::{channel, Sender};
+
+#[tokio::test]
+async fn optional_adapter_test() -> Result<()> {
+    // None discards items
+    let mut output: Option<Sender<u32>> = None;
+    assert!(output.is_placeholder());
+    output.notify(1).await?;
+    output.clone_notify(&2).await?;
+    output.flush().await?;
+
+    // Some forwards them
+    let (sender, mut received) = channel(3);
+    let mut output = Some(sender);
+    assert!(!output.is_placeholder());
+    output.notify(1).await?;
+    output.clone_notify(&2).await?;
+    drop(output);
+    assert_eq!(received.recv().await, Some(1));
+    assert_eq!(received.recv().await, Some(2));
+    assert_eq!(received.recv().await, None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn optional_output_test() -> Result<()> {
+    for wired in [false, true] {
+        let (sender, mut received) = channel::<Output>(3);
+        let machine = machine::<Counter>().with_output(wired.then_some(sender));
+        let input = machine.input();
+        let task = tokio::spawn(machine.task());
+        for _ in 0..10 {
+            input.send(Input::Event(Event::Tick)).await?;
+        }
+        drop(input);
+        task.await.unwrap()?;
+        assert_eq!(received.recv().await.is_some(), wired);
+    }
+    Ok(())
+}
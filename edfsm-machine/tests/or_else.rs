@@ -0,0 +1,22 @@
+use edfsm_machine::{adapter::Adapter, error::Result};
+use tokio::sync::mpsc::channel;
+
+#[tokio::test]
+async fn or_else_test() -> Result<()> {
+    // The primary always fails, as its receiver is dropped
+    let (primary, _) = channel::<u32>(1);
+    let (fallback, mut received) = channel(3);
+    let mut output = primary.or_else(fallback);
+    output.notify(1).await?;
+    output.notify(2).await?;
+    assert_eq!(received.recv().await, Some(1));
+    assert_eq!(received.recv().await, Some(2));
+
+    // The fallback is not notified when the primary succeeds
+    let (fallback, mut received) = channel(3);
+    let mut output = Vec::new().or_else(fallback);
+    output.notify(3).await?;
+    drop(output);
+    assert_eq!(received.recv().await, None);
+    Ok(())
+}
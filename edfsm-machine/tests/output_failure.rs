@@ -0,0 +1,152 @@
+use edfsm::{Change, Fsm, Input, Terminating};
+use edfsm_machine::{
+    adapter::Adapter,
+    error::{Error, Result},
+    machine,
+    output::OutputBuffer,
+    Machine,
+};
+use tokio::sync::mpsc::{channel, Sender};
+
+// A sender that retries a message whose delivery fails, up to a number of attempts
+
+struct Delivery;
+
+const ATTEMPTS: u32 = 3;
+
+#[derive(Debug, Default)]
+struct State {
+    retries: u32,
+    gave_up: bool,
+}
+
+enum Command {
+    Send(u32),
+    OutputFailed(u32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Event {
+    Sent(u32),
+    Retried(u32),
+    GaveUp(u32),
+}
+
+impl Terminating for Event {
+    fn terminating(&self) -> bool {
+        false
+    }
+}
+
+impl Fsm for Delivery {
+    type S = State;
+    type C = Command;
+    type E = Event;
+    type SE = OutputBuffer<u32>;
+
+    fn for_command(s: &Self::S, c: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
+        match c {
+            Command::Send(n) => Some(Event::Sent(n)),
+            Command::OutputFailed(n) if s.retries < ATTEMPTS => Some(Event::Retried(n)),
+            Command::OutputFailed(n) => Some(Event::GaveUp(n)),
+        }
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        match e {
+            Event::Sent(_) => s.retries = 0,
+            Event::Retried(_) => s.retries += 1,
+            Event::GaveUp(_) => s.gave_up = true,
+        }
+        Some(Change::Updated)
+    }
+
+    fn on_change(_s: &Self::S, e: &Self::E, se: &mut Self::SE, _change: Change) {
+        if let Event::Sent(n) | Event::Retried(n) = e {
+            se.push(*n);
+        }
+    }
+}
+
+/// An output adapter that fails a number of times before delivering.
+struct Flaky {
+    failures: u32,
+    delivered: Sender<u32>,
+}
+
+impl Adapter for Flaky {
+    type Item = u32;
+
+    async fn notify(&mut self, a: Self::Item) -> Result<()> {
+        if self.failures > 0 {
+            self.failures -= 1;
+            Err(Error::ChannelClosed)
+        } else {
+            self.delivered.notify(a).await
+        }
+    }
+}
+
+#[tokio::test]
+async fn output_failure_retried() -> Result<()> {
+    let (send_d, mut delivered) = channel(10);
+    let (send_e, mut events) = channel(10);
+    let (machine, processed) = machine::<Delivery>()
+        .with_output(Flaky {
+            failures: 2,
+            delivered: send_d,
+        })
+        .merge_event_log(send_e)
+        .with_output_failure(|n, _e| Some(Input::Command(Command::OutputFailed(n))))
+        .processed();
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+
+    // Failures are returned to the machine, which retries
+    input.send(Input::Command(Command::Send(7))).await?;
+    assert_eq!(delivered.recv().await, Some(7));
+    drop(input);
+    task.await.unwrap()?;
+
+    let mut logged = Vec::new();
+    while let Some(e) = events.recv().await {
+        logged.push(e);
+    }
+    assert_eq!(
+        logged,
+        [Event::Sent(7), Event::Retried(7), Event::Retried(7)]
+    );
+
+    // Only inputs received are counted as processed
+    assert_eq!(*processed.borrow(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn output_failure_bounded() -> Result<()> {
+    let (send_d, mut delivered) = channel(10);
+    let (send_e, mut events) = channel(10);
+    let machine = machine::<Delivery>()
+        .with_output(Flaky {
+            failures: u32::MAX,
+            delivered: send_d,
+        })
+        .merge_event_log(send_e)
+        .with_output_failure(|n, _e| Some(Input::Command(Command::OutputFailed(n))));
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+
+    // The state machine gives up after its attempts, and the machine continues
+    input.send(Input::Command(Command::Send(7))).await?;
+    drop(input);
+    task.await.unwrap()?;
+    assert_eq!(delivered.recv().await, None);
+
+    let mut logged = Vec::new();
+    while let Some(e) = events.recv().await {
+        logged.push(e);
+    }
+    assert_eq!(logged.len(), ATTEMPTS as usize + 2);
+    assert_eq!(logged.last(), Some(&Event::GaveUp(7)));
+    Ok(())
+}
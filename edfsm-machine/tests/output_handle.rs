@@ -0,0 +1,50 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::{error::Result, machine, Machine};
+use fixtures::{Counter, Event};
+use tokio::sync::mpsc::{channel, Sender};
+
+async fn ticks(input: &Sender<Input<fixtures::Command, Event>>, n: usize) -> Result<()> {
+    for _ in 0..n {
+        input.send(Input::Event(Event::Tick)).await?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn output_handle_test() -> Result<()> {
+    // A fixed output, notified after the registry, to know when each output is dispatched
+    let (send_sync, mut sync) = channel(1);
+    let (machine, handle) = machine::<Counter>().output_handle();
+    let machine = machine.merge_output(send_sync);
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+
+    // With no subscribers, outputs are dropped
+    ticks(&input, 10).await?;
+    sync.recv().await;
+
+    let mut first = handle.subscribe();
+    ticks(&input, 10).await?;
+    sync.recv().await;
+    assert!(first.try_recv().is_ok());
+    assert!(first.try_recv().is_err());
+
+    // A later subscriber only receives later outputs
+    let mut second = handle.clone().subscribe();
+    ticks(&input, 10).await?;
+    sync.recv().await;
+    assert!(first.try_recv().is_ok());
+    assert!(second.try_recv().is_ok());
+
+    // A dropped subscriber is removed without affecting others
+    drop(first);
+    ticks(&input, 10).await?;
+    sync.recv().await;
+    assert!(second.try_recv().is_ok());
+
+    drop(input);
+    task.await.unwrap()?;
+    assert!(second.recv().await.is_none());
+    Ok(())
+}
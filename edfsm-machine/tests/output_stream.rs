@@ -0,0 +1,23 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::{error::Result, machine, Machine};
+use fixtures::{Command, Counter, Event};
+use futures_util::StreamExt;
+
+#[tokio::test]
+async fn output_stream_test() -> Result<()> {
+    let (machine, outputs) = machine::<Counter>().output_stream();
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+    let consumer = tokio::spawn(outputs.count());
+
+    for _ in 0..30 {
+        input.send(Input::Event(Event::Tick)).await?;
+    }
+    input.send(Input::Command(Command::Assert(30))).await?;
+    drop(input);
+
+    task.await.unwrap()?;
+    assert_eq!(consumer.await.unwrap(), 3);
+    Ok(())
+}
@@ -0,0 +1,36 @@
+pub mod fixtures;
+use edfsm_machine::{
+    adapter::{Adapter, Either},
+    error::Result,
+};
+use fixtures::Event;
+use tokio::sync::mpsc::channel;
+
+/// An archival record of an uninteresting event.
+#[derive(Debug, PartialEq)]
+struct Archived(&'static str);
+
+#[tokio::test]
+async fn partition_test() -> Result<()> {
+    let (send_i, mut interesting) = channel::<Event>(4);
+    let (send_a, mut archival) = channel::<Archived>(4);
+    let mut events = send_i.partition(
+        |e: Event| match e {
+            Event::Tick => Either::Right(Archived("tick")),
+            e => Either::Left(e),
+        },
+        send_a,
+    );
+    for e in [Event::Tick, Event::Reset, Event::Tick, Event::Stop] {
+        events.notify(e).await?;
+    }
+    drop(events);
+
+    assert!(matches!(interesting.recv().await, Some(Event::Reset)));
+    assert!(matches!(interesting.recv().await, Some(Event::Stop)));
+    assert!(interesting.recv().await.is_none());
+    assert_eq!(archival.recv().await, Some(Archived("tick")));
+    assert_eq!(archival.recv().await, Some(Archived("tick")));
+    assert!(archival.recv().await.is_none());
+    Ok(())
+}
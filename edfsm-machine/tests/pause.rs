@@ -0,0 +1,42 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::{error::Result, machine, Machine};
+use fixtures::{Counter, Event};
+use std::time::Duration;
+use tokio::time::sleep;
+
+#[tokio::test]
+async fn pause_test() -> Result<()> {
+    let (machine, pause) = machine::<Counter>().pausable();
+    let (machine, mut view) = machine.publish_state();
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+
+    input.send(Input::Event(Event::Tick)).await?;
+    view.wait_for(|s| s.count == 1).await.unwrap();
+
+    // Inputs are not stepped while paused
+    pause.pause();
+    assert!(pause.is_paused());
+    for _ in 0..3 {
+        input.send(Input::Event(Event::Tick)).await?;
+    }
+    sleep(Duration::from_millis(50)).await;
+    assert_eq!(view.borrow().count, 1);
+
+    // Nor are they taken from the input channel
+    assert_eq!(input.capacity(), input.max_capacity() - 3);
+
+    // They are stepped once resumed
+    pause.resume();
+    view.wait_for(|s| s.count == 4).await.unwrap();
+
+    // Dropping the handle resumes the machine
+    pause.pause();
+    input.send(Input::Event(Event::Tick)).await?;
+    drop(pause);
+    view.wait_for(|s| s.count == 5).await.unwrap();
+
+    drop(input);
+    task.await.unwrap()
+}
@@ -0,0 +1,25 @@
+pub mod fixtures;
+use edfsm_machine::{adapter::ProduceAll, memlog::MemLog};
+use fixtures::Event;
+use streambed_codec::{Cbor, CommitLogExt};
+
+const TOPIC: &str = "event_batch";
+
+#[tokio::test]
+async fn produce_all_test() {
+    let log = MemLog::new().adapt::<Event>(TOPIC, Cbor);
+
+    let first = log
+        .produce_all(std::iter::repeat_n(Event::Tick, 5))
+        .await
+        .unwrap();
+    assert_eq!(first.len(), 5);
+    assert!(first.windows(2).all(|w| w[1] == w[0] + 1));
+
+    // A following batch continues from the first
+    let next = log.produce_all([Event::Tick, Event::Tick]).await.unwrap();
+    assert_eq!(next, [first[4] + 1, first[4] + 2]);
+
+    // An empty batch produces nothing
+    assert!(log.produce_all([]).await.unwrap().is_empty());
+}
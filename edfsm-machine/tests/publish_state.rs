@@ -0,0 +1,28 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::{error::Result, machine, Machine};
+use fixtures::{Counter, Event};
+
+#[tokio::test]
+async fn publish_state_test() -> Result<()> {
+    let (machine, mut view) = machine::<Counter>()
+        .with_event_log(vec![Event::Tick, Event::Tick])
+        .publish_state();
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+
+    // The rehydrated state is published
+    view.wait_for(|s| s.count == 2).await.unwrap();
+
+    input.send(Input::Event(Event::Tick)).await?;
+    view.changed().await.unwrap();
+    assert_eq!(view.borrow_and_update().count, 3);
+
+    // The last state remains observable once the machine ends
+    input.send(Input::Event(Event::Reset)).await?;
+    drop(input);
+    task.await.unwrap()?;
+    assert_eq!(view.borrow_and_update().count, 0);
+    assert!(view.changed().await.is_err());
+    Ok(())
+}
@@ -0,0 +1,41 @@
+pub mod fixtures;
+use edfsm::{Input, StateSummary, Summarize};
+use edfsm_machine::{error::Result, machine, Machine};
+use fixtures::{Counter, Event, State};
+
+// An example summary of the counter, naming its state and selecting its count
+impl Summarize for State {
+    type Summary = StateSummary<i32>;
+
+    fn summary(&self) -> Self::Summary {
+        let state = if self.count == 0 { "Idle" } else { "Counting" };
+        StateSummary::new(state, self.count)
+    }
+}
+
+#[tokio::test]
+async fn publish_summary_test() -> Result<()> {
+    let (machine, mut view) = machine::<Counter>()
+        .with_event_log(vec![Event::Tick, Event::Tick])
+        .publish_summary();
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+
+    // The summary of the rehydrated state is published
+    view.wait_for(|s| s.fields == 2).await.unwrap();
+    assert_eq!(*view.borrow_and_update(), StateSummary::new("Counting", 2));
+
+    input.send(Input::Event(Event::Reset)).await?;
+    view.changed().await.unwrap();
+    let summary = *view.borrow_and_update();
+    assert_eq!(summary, StateSummary::new("Idle", 0));
+
+    // The summary is serializable
+    assert_eq!(
+        serde_json::to_string(&summary).unwrap(),
+        r#"{"state":"Idle","fields":0}"#
+    );
+
+    drop(input);
+    task.await.unwrap()
+}
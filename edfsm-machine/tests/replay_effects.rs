@@ -0,0 +1,20 @@
+pub mod fixtures;
+use edfsm_machine::{output::OutputBuffer, replay_effects};
+use fixtures::{Counter, Event, Output};
+
+#[test]
+fn replay_effects_test() {
+    // A tock is output whenever the count becomes a multiple of ten, including by a reset
+    let mut events = vec![Event::Tick; 15];
+    events.push(Event::Reset);
+    events.extend(vec![Event::Tick; 10]);
+    let outputs = replay_effects::<Counter>(events, OutputBuffer::default());
+    assert!(matches!(
+        outputs.as_slice(),
+        [Output::Tock, Output::Tock, Output::Tock]
+    ));
+
+    // Ignored events produce no effects
+    let outputs = replay_effects::<Counter>([Event::Reset], OutputBuffer::default());
+    assert!(outputs.is_empty());
+}
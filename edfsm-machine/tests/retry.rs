@@ -0,0 +1,60 @@
+use edfsm_machine::{
+    adapter::{Adapter, Retry},
+    error::{Error, Result},
+};
+use std::time::Duration;
+
+/// An adapter that fails a number of times before succeeding.
+struct Flaky {
+    failures: u32,
+    attempts: u32,
+    received: Vec<u32>,
+}
+
+impl Flaky {
+    fn new(failures: u32) -> Self {
+        Self {
+            failures,
+            attempts: 0,
+            received: Vec::new(),
+        }
+    }
+}
+
+impl Adapter for Flaky {
+    type Item = u32;
+
+    async fn notify(&mut self, a: Self::Item) -> Result<()> {
+        self.attempts += 1;
+        if self.attempts <= self.failures {
+            Err(Error::ChannelClosed)
+        } else {
+            self.received.push(a);
+            Ok(())
+        }
+    }
+}
+
+#[tokio::test]
+async fn retry_test() -> Result<()> {
+    let backoff = (Duration::from_millis(1), Duration::from_millis(100));
+
+    // Fails twice then succeeds
+    let mut retry = Retry::new(Flaky::new(2), 3).with_backoff(backoff.0, backoff.1);
+    retry.notify(42).await?;
+    let flaky = retry.into_inner();
+    assert_eq!(flaky.attempts, 3);
+    assert_eq!(flaky.received, [42]);
+
+    // Gives up after the number of attempts
+    let mut retry = Retry::new(Flaky::new(3), 3).with_backoff(backoff.0, backoff.1);
+    assert!(matches!(retry.notify(42).await, Err(Error::ChannelClosed)));
+    assert_eq!(retry.into_inner().attempts, 3);
+
+    // Gives up once the total delay would exceed the bound: 1ms + 2ms, but not 4ms more
+    let mut retry = Retry::new(Flaky::new(5), 10)
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(5));
+    assert!(retry.notify(42).await.is_err());
+    assert_eq!(retry.into_inner().attempts, 3);
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use edfsm::{Change, Fsm, Input, Terminating};
+use edfsm_machine::{error::Result, machine, output::OutputBuffer, Machine};
+use std::string::{String, ToString};
+use tokio::sync::mpsc::channel;
+
+// An account whose effect handler panics on a particular deposit
+
+struct Account;
+
+#[derive(Debug, Clone, Default)]
+struct State {
+    balance: u32,
+}
+
+enum Command {
+    Deposit(u32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Event {
+    Deposited(u32),
+}
+
+impl Terminating for Event {
+    fn terminating(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Output {
+    Balance(u32),
+    Failed(String),
+}
+
+impl Fsm for Account {
+    type S = State;
+    type C = Command;
+    type E = Event;
+    type SE = OutputBuffer<Output>;
+
+    fn for_command(_s: &Self::S, c: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
+        match c {
+            Command::Deposit(n) => Some(Event::Deposited(n)),
+        }
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        match e {
+            Event::Deposited(n) => s.balance += n,
+        }
+        Some(Change::Updated)
+    }
+
+    fn on_change(s: &Self::S, e: &Self::E, se: &mut Self::SE, _change: Change) {
+        se.push(Output::Balance(s.balance));
+        if let Event::Deposited(13) = e {
+            panic!("unlucky");
+        }
+    }
+}
+
+#[tokio::test]
+async fn rollback_test() -> Result<()> {
+    let (send_o, mut outputs) = channel(10);
+    let (send_e, mut events) = channel(10);
+    let (machine, view) = machine::<Account>()
+        .with_output(send_o)
+        .merge_event_log(send_e)
+        .with_rollback(|message| Some(Output::Failed(message.to_string())))
+        .with_batch(3)
+        .publish_state();
+    let input = machine.input();
+
+    // Queued so that the panic occurs within a batch
+    for n in [5, 13, 2] {
+        input.send(Input::Command(Command::Deposit(n))).await?;
+    }
+    drop(input);
+    tokio::spawn(machine.task()).await.unwrap()?;
+
+    // The step that panicked is rolled back and reported, and the machine continues
+    assert_eq!(view.borrow().balance, 7);
+    let mut dispatched = Vec::new();
+    while let Some(o) = outputs.recv().await {
+        dispatched.push(o);
+    }
+    assert_eq!(
+        dispatched,
+        [
+            Output::Balance(5),
+            Output::Failed("unlucky".to_string()),
+            Output::Balance(7)
+        ]
+    );
+    let mut logged = Vec::new();
+    while let Some(e) = events.recv().await {
+        logged.push(e);
+    }
+    assert_eq!(logged, [Event::Deposited(5), Event::Deposited(2)]);
+    Ok(())
+}
@@ -0,0 +1,37 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::{
+    adapter::Adapter,
+    error::{Error, Result},
+    machine, Machine,
+};
+use fixtures::{Counter, Event, Output};
+use std::{sync::mpsc, thread};
+
+#[tokio::test]
+async fn std_channel_test() -> Result<()> {
+    // A synchronous consumer on a thread of its own
+    let (sender, receiver) = mpsc::channel();
+    let consumer = thread::spawn(move || receiver.iter().count());
+
+    let machine = machine::<Counter>().with_output(sender);
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+    for _ in 0..30 {
+        input.send(Input::Event(Event::Tick)).await?;
+    }
+    drop(input);
+    task.await.unwrap()?;
+
+    // The machine is dropped with its sender, ending the consumer
+    assert_eq!(consumer.join().unwrap(), 3);
+
+    // A disconnected channel is closed
+    let (mut sender, receiver) = mpsc::channel();
+    drop(receiver);
+    assert!(matches!(
+        sender.notify(Output::Tock).await,
+        Err(Error::ChannelClosed)
+    ));
+    Ok(())
+}
@@ -0,0 +1,32 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::{
+    error::{Error, Result},
+    machine, Machine,
+};
+use fixtures::{Counter, Event};
+use tokio::sync::mpsc::channel;
+
+#[tokio::test]
+async fn strict_unwired_test() {
+    let machine = machine::<Counter>().strict();
+    assert!(matches!(machine.task().await, Err(Error::Unwired)));
+}
+
+#[tokio::test]
+async fn strict_wired_test() -> Result<()> {
+    let (send_e, mut events) = channel(1);
+    let wired = machine::<Counter>().merge_event_log(send_e).strict();
+    let input = wired.input();
+    let task = tokio::spawn(wired.task());
+
+    input.send(Input::Event(Event::Tick)).await?;
+    assert!(matches!(events.recv().await, Some(Event::Tick)));
+    drop(input);
+    task.await.unwrap()?;
+
+    // Unwired machines are allowed unless strict
+    let machine = machine::<Counter>();
+    drop(machine.input());
+    machine.task().await
+}
@@ -0,0 +1,62 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::{
+    adapter::{Adapter, Feed, TailingLog},
+    error::Result,
+    machine, Machine,
+};
+use fixtures::{Counter, Event};
+use streambed_codec::{Cbor, CommitLogExt};
+use streambed_logged::FileLog;
+use tokio_util::sync::CancellationToken;
+
+const TEST_DATA: &str = "test_data";
+const TOPIC: &str = "tailed_series";
+
+#[tokio::test]
+async fn tailing_log_test() -> Result<()> {
+    let topic_file = [TEST_DATA, TOPIC].join("/");
+    let _ = std::fs::remove_file(&topic_file);
+    let _ = std::fs::create_dir(TEST_DATA);
+    let commit_log = FileLog::new(TEST_DATA);
+
+    // A writer records events before the follower starts
+    let writer = machine::<Counter>().with_event_log(commit_log.clone().adapt(TOPIC, Cbor));
+    let input = writer.input();
+    let writer = tokio::spawn(writer.task());
+    for _ in 0..3 {
+        input.send(Input::Event(Event::Tick)).await?;
+    }
+
+    // A follower is fed the events of the writer as they are logged
+    let (follower, mut view) = machine::<Counter>().publish_state();
+    let mut follower_input = follower.input().with_map(Input::Event);
+    let follower = tokio::spawn(follower.task());
+    let cancel = CancellationToken::new();
+    let tailing = tokio::spawn({
+        let cancel = cancel.clone();
+        let log = TailingLog::<_, _, Event>::new(commit_log, TOPIC, Cbor);
+        async move {
+            cancel
+                .run_until_cancelled(log.feed(&mut follower_input))
+                .await
+        }
+    });
+    view.wait_for(|s| s.count == 3).await.unwrap();
+
+    for _ in 0..2 {
+        input.send(Input::Event(Event::Tick)).await?;
+    }
+    view.wait_for(|s| s.count == 5).await.unwrap();
+
+    // Following does not complete until cancelled
+    assert!(!tailing.is_finished());
+    cancel.cancel();
+    assert!(tailing.await.unwrap().is_none());
+    follower.await.unwrap()?;
+
+    drop(input);
+    writer.await.unwrap()?;
+    let _ = std::fs::remove_file(&topic_file);
+    Ok(())
+}
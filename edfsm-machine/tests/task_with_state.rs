@@ -0,0 +1,80 @@
+use edfsm::{Change, Drain, Fsm, Init, Input, Terminating};
+use edfsm_machine::{error::Result, machine, Machine};
+
+/// A state machine whose effector records each count it sees.
+struct Recording;
+
+#[derive(Clone, Debug)]
+enum Event {
+    Tick,
+    Stop,
+}
+
+impl Terminating for Event {
+    fn terminating(&self) -> bool {
+        matches!(self, Event::Stop)
+    }
+}
+
+/// An effector that accumulates data rather than producing output messages.
+#[derive(Default)]
+struct Recorder {
+    seen: Vec<i32>,
+    initialised: bool,
+}
+
+impl Init<i32> for Recorder {
+    fn init(&mut self, _state: &i32) {
+        self.initialised = true;
+    }
+}
+
+impl Drain for Recorder {
+    type Item = ();
+
+    fn drain_all(&mut self) -> impl Iterator<Item = Self::Item> + Send {
+        core::iter::empty()
+    }
+}
+
+impl Fsm for Recording {
+    type S = i32;
+    type C = ();
+    type E = Event;
+    type SE = Recorder;
+
+    fn for_command(_s: &Self::S, _c: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
+        None
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        match e {
+            Event::Tick => {
+                *s += 1;
+                Some(Change::Updated)
+            }
+            Event::Stop => Some(Change::Transitioned),
+        }
+    }
+
+    fn on_change(s: &Self::S, _e: &Self::E, se: &mut Self::SE, _change: Change) {
+        se.seen.push(*s);
+    }
+}
+
+#[tokio::test]
+async fn task_with_state_test() -> Result<()> {
+    let machine = machine::<Recording>();
+    let input = machine.input();
+    let task = tokio::spawn(machine.task_with_state());
+
+    for e in [Event::Tick, Event::Tick, Event::Stop, Event::Tick] {
+        input.send(Input::Event(e)).await?;
+    }
+
+    let (state, effects) = task.await.unwrap()?;
+    assert_eq!(state, 2);
+    assert!(effects.initialised);
+    assert_eq!(effects.seen, [1, 2, 2]);
+    Ok(())
+}
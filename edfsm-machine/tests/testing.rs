@@ -0,0 +1,30 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::testing::TestMachine;
+use fixtures::{Command, Counter, Event, Output};
+
+#[test]
+fn test_machine() {
+    // Rehydrate from a history, without effects
+    let history = vec![Event::Tick; 9];
+    let mut machine = TestMachine::<Counter>::new(&history, Default::default());
+    assert_eq!(machine.state().count, 9);
+    assert!(machine.drain_outputs().is_empty());
+
+    // Step inputs, observing events and output messages
+    machine.step(Input::Event(Event::Tick));
+    machine.step(Input::Command(Command::Assert(10)));
+    assert!(matches!(machine.take_events()[..], [Event::Tick]));
+    assert!(matches!(machine.drain_outputs()[..], [Output::Tock]));
+
+    // Both are taken once
+    machine.step(Input::Event(Event::Tick));
+    assert_eq!(machine.take_events().len(), 1);
+    assert!(machine.drain_outputs().is_empty());
+
+    machine.step(Input::Event(Event::Reset));
+    machine.stop();
+    assert_eq!(machine.state().count, 0);
+    assert!(matches!(machine.take_events()[..], [Event::Reset]));
+    assert!(matches!(machine.drain_outputs()[..], [Output::Tock]));
+}
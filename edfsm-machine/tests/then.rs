@@ -0,0 +1,20 @@
+use edfsm_machine::{adapter::Adapter, error::Result};
+use tokio::{sync::mpsc::channel, task::yield_now};
+
+#[tokio::test]
+async fn with_then_test() -> Result<()> {
+    // Odd numbers are dropped and even numbers are enriched after awaiting
+    let (sender, mut received) = channel(3);
+    let mut output = sender.with_then(|n: u32| async move {
+        yield_now().await;
+        n.is_multiple_of(2).then(|| format!("item {n}"))
+    });
+    for n in 1..=4 {
+        output.notify(n).await?;
+    }
+    drop(output);
+    assert_eq!(received.recv().await.as_deref(), Some("item 2"));
+    assert_eq!(received.recv().await.as_deref(), Some("item 4"));
+    assert_eq!(received.recv().await, None);
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use edfsm_machine::{adapter::Adapter, error::Result};
+use tokio::{sync::mpsc::channel, task::yield_now};
+
+#[tokio::test]
+async fn with_timestamp_test() -> Result<()> {
+    let (sender, mut received) = channel(10);
+    let mut output = sender.with_timestamp();
+    for n in 0..10u32 {
+        output.notify(n).await?;
+        yield_now().await;
+    }
+    drop(output);
+
+    let mut items = Vec::new();
+    while let Some(item) = received.recv().await {
+        items.push(item);
+    }
+    let numbers: Vec<u32> = items.iter().map(|(_, n)| *n).collect();
+    assert_eq!(numbers, (0..10).collect::<Vec<_>>());
+    assert!(items.windows(2).all(|w| w[0].0 <= w[1].0));
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use edfsm_machine::versioned::Versioned;
+use serde::{Deserialize, Serialize};
+use streambed_codec::{Cbor, Codec};
+
+#[derive(Debug, Serialize, Deserialize)]
+enum EventV1 {
+    Tick,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum EventV2 {
+    Tick(u32),
+}
+
+impl From<EventV1> for EventV2 {
+    fn from(value: EventV1) -> Self {
+        match value {
+            EventV1::Tick => EventV2::Tick(1),
+        }
+    }
+}
+
+#[test]
+fn versioned_test() {
+    let v1 = Versioned::<_, EventV1>::new(Cbor);
+    let mut record = v1.encode(EventV1::Tick).unwrap();
+    assert_eq!(record[0], 1);
+
+    let v2 = Versioned::<_, EventV2>::new(Cbor).with_migration(1, |bytes| {
+        Codec::<EventV1>::decode(&Cbor, bytes).map(EventV2::from)
+    });
+    assert_eq!(v2.version(), 2);
+
+    // An earlier record is migrated
+    assert_eq!(v2.decode(&mut record), Some(EventV2::Tick(1)));
+
+    // A current record round trips
+    let mut record = v2.encode(EventV2::Tick(5)).unwrap();
+    assert_eq!(record[0], 2);
+    assert_eq!(v2.decode(&mut record), Some(EventV2::Tick(5)));
+
+    // A record of an unknown version is not decoded
+    record[0] = 3;
+    assert_eq!(v2.decode(&mut record), None);
+    assert_eq!(v2.decode(&mut []), None);
+}
@@ -0,0 +1,41 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::{adapter::Adapter, error::Result, machine, Machine};
+use fixtures::{Counter, Event, Output};
+use tokio::sync::watch;
+
+#[tokio::test]
+async fn watch_output_coalesces() -> Result<()> {
+    let (mut sender, mut receiver) = watch::channel(0);
+
+    // Rapid notifies do not wait for the receiver, which observes only the latest
+    for n in 1..=100 {
+        sender.notify(n).await?;
+    }
+    assert!(receiver.has_changed().unwrap());
+    assert_eq!(*receiver.borrow_and_update(), 100);
+    assert!(!receiver.has_changed().unwrap());
+
+    // Messages are accepted with no receivers
+    drop(receiver);
+    sender.notify(101).await
+}
+
+#[tokio::test]
+async fn watch_output_machine() -> Result<()> {
+    // Outputs have no initial value, so the channel holds an Option
+    let (sender, mut receiver) = watch::channel(None);
+    let machine = machine::<Counter>().with_output(sender.with_map(Some));
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+
+    // Several outputs are produced without the receiver keeping up
+    for _ in 0..30 {
+        input.send(Input::Event(Event::Tick)).await?;
+    }
+    drop(input);
+    task.await.unwrap()?;
+
+    assert!(matches!(*receiver.borrow_and_update(), Some(Output::Tock)));
+    Ok(())
+}
@@ -0,0 +1,70 @@
+pub mod fixtures;
+use edfsm::{Fsm, Input, Watch, Watchdog, WatchdogCommand, Watched};
+use edfsm_machine::{
+    clock::{watchdog, TokioClock},
+    error::Result,
+    machine_with_effects, Machine,
+};
+use fixtures::{Command, Counter, Event};
+use std::time::Duration;
+use tokio::{sync::mpsc::channel, time::Instant};
+
+impl Watched for Counter {
+    fn on_timeout(s: &Self::S) -> Option<Self::E> {
+        (s.count != 0).then_some(Event::Reset)
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn watchdog_test() -> Result<()> {
+    let (send_e, mut events) = channel(10);
+    let timeout = Duration::from_millis(100);
+    let effects = Watch::new(Default::default(), TokioClock, timeout);
+    let machine =
+        machine_with_effects::<Watchdog<Counter, TokioClock>>(effects, 10).merge_event_log(send_e);
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+    let checks = tokio::spawn(watchdog(
+        input.clone(),
+        TokioClock,
+        Duration::from_millis(10),
+    ));
+
+    // Input within the timeout restarts it
+    input.send(Input::Event(Event::Tick)).await?;
+    assert!(matches!(events.recv().await, Some(Event::Tick)));
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    input.send(Input::Event(Event::Tick)).await?;
+    assert!(matches!(events.recv().await, Some(Event::Tick)));
+    let last_input = Instant::now();
+
+    // Without input, the timeout event is stepped and logged
+    assert!(matches!(events.recv().await, Some(Event::Reset)));
+    let elapsed = last_input.elapsed();
+    assert!(elapsed >= timeout && elapsed <= timeout + Duration::from_millis(10));
+
+    // Further timeouts produce no event for the failed state
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    input
+        .send(Input::Command(WatchdogCommand::Command(Command::Assert(0))))
+        .await?;
+    input.send(Input::Event(Event::Stop)).await?;
+    assert!(matches!(events.recv().await, Some(Event::Stop)));
+
+    task.await.unwrap()?;
+    assert!(checks.await.unwrap().is_err());
+    Ok(())
+}
+
+#[test]
+fn watchdog_check_within_timeout() {
+    let mut s = Default::default();
+    let mut se = Watch::new(Default::default(), TokioClock, Duration::from_secs(60));
+    let check = || Input::Command(WatchdogCommand::Check);
+
+    // Checks arm the timeout and then produce nothing within it
+    Watchdog::<Counter, TokioClock>::step(&mut s, Input::Event(Event::Tick), &mut se);
+    assert!(!se.is_expired());
+    assert!(Watchdog::<Counter, TokioClock>::step(&mut s, check(), &mut se).is_none());
+    assert_eq!(s.count, 1);
+}
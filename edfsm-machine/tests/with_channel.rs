@@ -0,0 +1,30 @@
+pub mod fixtures;
+use edfsm::Input;
+use edfsm_machine::{error::Result, machine_with_channel, Machine};
+use fixtures::{Command, Counter, Event};
+use tokio::sync::mpsc::channel;
+
+#[tokio::test]
+async fn with_channel_test() -> Result<()> {
+    let (sender, receiver) = channel(1);
+
+    // A producer created before the machine.
+    let producer = sender.clone();
+    let prod_task = tokio::spawn(async move {
+        for _ in 0..5 {
+            producer.send(Input::Event(Event::Tick)).await?;
+        }
+        Result::Ok(())
+    });
+
+    let machine = machine_with_channel::<Counter>(Default::default(), sender, receiver);
+    let input = machine.input();
+    let task = tokio::spawn(machine.task());
+
+    prod_task.await.unwrap()?;
+    input.send(Input::Command(Command::Assert(5))).await?;
+    drop(input);
+
+    // The task ends once all senders are dropped.
+    task.await.unwrap()
+}
@@ -0,0 +1,137 @@
+use std::fmt::Write;
+
+use syn::{Result, Type};
+
+use crate::expand::{declared_states, ident_from_type};
+use crate::parse::Fsm;
+
+/// Render the transitions of the state machine as a Mermaid `stateDiagram-v2`.
+///
+/// Each declared transition is an edge labelled by its command and event, or by its
+/// event alone. A transition from any state is drawn from each declared state, including
+/// the target state, as remaining in the same state is also a transition.
+pub fn mermaid(fsm: &Fsm) -> Result<String> {
+    let states = declared_states(fsm)?;
+    let mut diagram = String::from("stateDiagram-v2\n");
+    for s in &fsm.steps {
+        let (Some(to_states), Some(event)) = (s.to_state(), s.event()) else {
+            continue;
+        };
+        let event = ident_from_type(event)?;
+        let label = if let Some(command) = s.command() {
+            format!("{}/{}", ident_from_type(command)?, event)
+        } else {
+            event.to_string()
+        };
+        let from_states = if let Type::Infer(_) = s.from_state() {
+            states.clone()
+        } else {
+            vec![ident_from_type(s.from_state())?]
+        };
+        for to_state in &to_states.states {
+            if let Type::Infer(_) = to_state {
+                continue;
+            }
+            let to_state = ident_from_type(to_state)?;
+            for from_state in &from_states {
+                writeln!(diagram, "    {from_state} --> {to_state} : {label}").unwrap();
+            }
+        }
+    }
+    Ok(diagram)
+}
+
+/// Describe the declarations of the state machine as a human-readable table.
+///
+/// There is a section for each of the transitions, entry states and ignored commands and
+/// events that are declared, in the order declared. Types are named as in the declarations,
+/// with `_` for any state, and `-` where a transition declares no command, event or target.
+pub fn describe(fsm: &Fsm) -> Result<String> {
+    let mut transitions = vec![row(["state", "command", "event", "target"])];
+    for s in &fsm.steps {
+        let target = match s.to_state() {
+            Some(to_states) => {
+                let names = to_states
+                    .states
+                    .iter()
+                    .map(name)
+                    .collect::<Result<Vec<_>>>()?;
+                let mut target = names.join(" | ");
+                if s.reentry() {
+                    target.push_str(" / reentry");
+                }
+                if s.on_change() {
+                    target.push_str(" / action");
+                }
+                target
+            }
+            None => "-".to_string(),
+        };
+        transitions.push(vec![
+            name(s.from_state())?,
+            optional_name(s.command())?,
+            optional_name(s.event())?,
+            target,
+        ]);
+    }
+
+    let mut entries = vec![row(["state", "event"])];
+    for e in &fsm.entry_handlers {
+        entries.push(vec![name(&e.state)?, optional_name(&e.event)?]);
+    }
+
+    let mut ignored_commands = vec![row(["state", "command"])];
+    for i in &fsm.ignore_commands {
+        ignored_commands.push(vec![name(&i.from_state)?, name(&i.command)?]);
+    }
+
+    let mut ignored_events = vec![row(["state", "event"])];
+    for i in &fsm.ignore_events {
+        ignored_events.push(vec![name(&i.from_state)?, name(&i.event)?]);
+    }
+
+    let mut description = String::new();
+    for (title, rows) in [
+        ("Transitions", transitions),
+        ("Entry states", entries),
+        ("Ignored commands", ignored_commands),
+        ("Ignored events", ignored_events),
+    ] {
+        if rows.len() > 1 {
+            writeln!(description, "{title}:").unwrap();
+            write_table(&mut description, &rows);
+        }
+    }
+    Ok(description)
+}
+
+fn row<const N: usize>(cells: [&str; N]) -> Vec<String> {
+    cells.map(str::to_string).to_vec()
+}
+
+fn name(ty: &Type) -> Result<String> {
+    if let Type::Infer(_) = ty {
+        Ok("_".to_string())
+    } else {
+        Ok(ident_from_type(ty)?.to_string())
+    }
+}
+
+fn optional_name(ty: &Option<Type>) -> Result<String> {
+    ty.as_ref().map_or(Ok("-".to_string()), name)
+}
+
+/// Write the rows indented, with each column but the last padded to its widest cell.
+fn write_table(out: &mut String, rows: &[Vec<String>]) {
+    let columns = rows.first().map_or(0, Vec::len);
+    let widths: Vec<usize> = (0..columns)
+        .map(|c| rows.iter().map(|r| r[c].len()).max().unwrap_or(0))
+        .collect();
+    for r in rows {
+        let mut line = String::from(" ");
+        for (cell, width) in r.iter().zip(&widths) {
+            write!(line, " {cell:<width$}").unwrap();
+        }
+        writeln!(out, "{}", line.trim_end()).unwrap();
+    }
+}
@@ -1,24 +1,27 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, iter};
 
 use proc_macro2::TokenStream;
 use quote::__private::ext::RepToTokensExt;
 use quote::format_ident;
 use quote::quote;
+use quote::quote_spanned;
 use quote::ToTokens;
 use syn::Ident;
 use syn::Type;
 use syn::{parse2, Error, ImplItem, Result};
 
-use crate::parse::Fsm;
+use crate::diagram;
+use crate::parse::{Args, Fsm};
 
-pub fn expand(fsm: &mut Fsm) -> Result<TokenStream> {
+pub fn expand(fsm: &mut Fsm, args: &Args) -> Result<TokenStream> {
+    let expected_trait = if args.is_async { "AsyncFsm" } else { "Fsm" };
     if let Some(trait_) = &fsm.item_impl.trait_ {
         let trait_path = &trait_.1;
         if let Some(last_trait_segment) = trait_path.segments.last() {
-            if last_trait_segment.ident != "Fsm" {
+            if last_trait_segment.ident != expected_trait {
                 return Err(Error::new_spanned(
                     &last_trait_segment.ident,
-                    "Expected the Fsm trait to be implemented.",
+                    format!("Expected the {expected_trait} trait to be implemented."),
                 ));
             }
         } else {
@@ -39,18 +42,41 @@ pub fn expand(fsm: &mut Fsm) -> Result<TokenStream> {
     let event_enum = &fsm.event_enum;
     let effect_handlers = &fsm.effect_handlers;
 
+    // In async mode, effect handlers are awaited within async trait methods.
+    let (asyncness, await_) = if args.is_async {
+        (quote!(async), quote!(.await))
+    } else {
+        (quote!(), quote!())
+    };
+
     let mut entry_matches = Vec::with_capacity(fsm.entry_handlers.len());
+    let mut follow_up_matches = Vec::new();
     for ee in &fsm.entry_handlers {
         let state = ident_from_type(&ee.state)?;
-        let handler = format_ident!("on_entry_{}", state);
+        let handler = format_ident!("on_entry_{}", state, span = state.span());
         let handler = Ident::new(&handler.to_string().to_lowercase(), handler.span());
-        entry_matches.push(quote!(
-            #state_enum::#state(s) => Self::#handler(s, se),
-        ));
+        if let Some(event) = &ee.event {
+            // An entry handler yielding an event is called by `follow_up` instead of `on_change`.
+            if args.is_async {
+                return Err(Error::new_spanned(
+                    event,
+                    "Entry handlers cannot yield events for an AsyncFsm.",
+                ));
+            }
+            let event = ident_from_type(event)?;
+            follow_up_matches.push(quote!(
+                #state_enum::#state(s) => Self::#handler(s, se).map(#event_enum::#event),
+            ));
+        } else {
+            entry_matches.push(quote!(
+                #state_enum::#state(s) => Self::#handler(s, se)#await_,
+            ));
+        }
     }
 
     let steps_len = fsm.steps.len();
     let mut command_matches = Vec::with_capacity(steps_len);
+    let mut accept_matches = Vec::with_capacity(steps_len);
     let mut event_matches = Vec::with_capacity(steps_len);
     let mut change_matches = Vec::with_capacity(steps_len);
 
@@ -82,34 +108,45 @@ pub fn expand(fsm: &mut Fsm) -> Result<TokenStream> {
 
         if let Some(command) = command {
             if let Some(from_state) = from_state {
-                let command_handler =
-                    lowercase_ident(&format_ident!("for_{}_{}", from_state, command));
+                accept_matches.push(quote!(
+                    (#state_enum::#from_state(_), #command_enum::#command(_)) => true,
+                ));
+                let command_handler = lowercase_ident(&format_ident!(
+                    "for_{}_{}",
+                    from_state,
+                    command,
+                    span = command.span()
+                ));
                 if let Some(event) = event {
                     command_matches.push(quote!(
                         (#state_enum::#from_state(s), #command_enum::#command(c)) => {
-                            Self::#command_handler(s, c, se).map(#event_enum::#event)
+                            Self::#command_handler(s, c, se)#await_.map(#event_enum::#event)
                         }
                     ));
                 } else {
                     command_matches.push(quote!(
                         (#state_enum::#from_state(s), #command_enum::#command(c)) => {
-                            Self::#command_handler(s, c, se);
+                            Self::#command_handler(s, c, se)#await_;
                             None
                         }
                     ));
                 }
             } else {
-                let command_handler = lowercase_ident(&format_ident!("for_any_{}", command));
+                accept_matches.push(quote!(
+                    (_, #command_enum::#command(_)) => true,
+                ));
+                let command_handler =
+                    lowercase_ident(&format_ident!("for_any_{}", command, span = command.span()));
                 if let Some(event) = event {
                     command_matches.push(quote!(
                         (_, #command_enum::#command(c)) => {
-                            Self::#command_handler(s, c, se).map(#event_enum::#event)
+                            Self::#command_handler(s, c, se)#await_.map(#event_enum::#event)
                         }
                     ));
                 } else {
                     command_matches.push(quote!(
                         (_, #command_enum::#command(c)) => {
-                            Self::#command_handler(s, c, se);
+                            Self::#command_handler(s, c, se)#await_;
                             None
                         }
                     ));
@@ -117,16 +154,25 @@ pub fn expand(fsm: &mut Fsm) -> Result<TokenStream> {
             }
         }
 
-        let mut push_change_matches_conditionally = |to_state, event| {
+        let mut push_change_matches_conditionally = |to_state, event: &Ident| {
             if s.on_change() {
                 let change_handler = if let Some(to_state) = to_state {
-                    lowercase_ident(&format_ident!("on_change_{}_{}", to_state, event))
+                    lowercase_ident(&format_ident!(
+                        "on_change_{}_{}",
+                        to_state,
+                        event,
+                        span = event.span()
+                    ))
                 } else {
-                    lowercase_ident(&format_ident!("on_change_any_{}", event))
+                    lowercase_ident(&format_ident!(
+                        "on_change_any_{}",
+                        event,
+                        span = event.span()
+                    ))
                 };
                 change_matches.push(quote!(
                     (#state_enum::#to_state(s), #event_enum::#event(e)) => {
-                        Self::#change_handler(s, e, se)
+                        Self::#change_handler(s, e, se)#await_
                     }
                 ));
             }
@@ -135,8 +181,12 @@ pub fn expand(fsm: &mut Fsm) -> Result<TokenStream> {
         if let Some(to_state) = to_state {
             if let Some(from_state) = from_state {
                 if let Some(event) = event {
-                    let event_handler =
-                        lowercase_ident(&format_ident!("on_{}_{}", from_state, event));
+                    let event_handler = lowercase_ident(&format_ident!(
+                        "on_{}_{}",
+                        from_state,
+                        event,
+                        span = event.span()
+                    ));
                     match to_state_num.cmp(&1) {
                         Ordering::Less => event_matches.push(quote!(
                             (#state_enum::#from_state(s), #event_enum::#event(e)) => {
@@ -158,7 +208,8 @@ pub fn expand(fsm: &mut Fsm) -> Result<TokenStream> {
                 }
             } else {
                 let event = event.unwrap(); // Logic error if no event given a to_state.
-                let event_handler = lowercase_ident(&format_ident!("on_any_{}", event));
+                let event_handler =
+                    lowercase_ident(&format_ident!("on_any_{}", event, span = event.span()));
                 match to_state_num.cmp(&1) {
                     Ordering::Less => event_matches.push(quote!(
                         (s, #event_enum::#event(e)) => {
@@ -180,7 +231,12 @@ pub fn expand(fsm: &mut Fsm) -> Result<TokenStream> {
             };
         } else if let Some(from_state) = from_state {
             if let Some(event) = event {
-                let event_handler = lowercase_ident(&format_ident!("on_{}_{}", from_state, event));
+                let event_handler = lowercase_ident(&format_ident!(
+                    "on_{}_{}",
+                    from_state,
+                    event,
+                    span = event.span()
+                ));
                 event_matches.push(quote!(
                     (#state_enum::#from_state(s), #event_enum::#event(e)) => {
                         Self::#event_handler(s, e);
@@ -192,7 +248,8 @@ pub fn expand(fsm: &mut Fsm) -> Result<TokenStream> {
         } else {
             // from and to states are None
             if let Some(event) = event {
-                let event_handler = lowercase_ident(&format_ident!("on_any_{}", event));
+                let event_handler =
+                    lowercase_ident(&format_ident!("on_any_{}", event, span = event.span()));
                 event_matches.push(quote!(
                     (s, #event_enum::#event(e)) => {
                         Self::#event_handler(s, e);
@@ -254,7 +311,7 @@ pub fn expand(fsm: &mut Fsm) -> Result<TokenStream> {
         }
     }
 
-    fsm.item_impl.items = vec![
+    let mut items = vec![
         parse2::<ImplItem>(quote!(
             type S = #state_enum;
         ))
@@ -272,7 +329,7 @@ pub fn expand(fsm: &mut Fsm) -> Result<TokenStream> {
         ))
         .unwrap(),
         parse2::<ImplItem>(quote!(
-            fn for_command(
+            #asyncness fn for_command(
                 s: &#state_enum,
                 c: #command_enum,
                 se: &mut #effect_handlers,
@@ -303,7 +360,7 @@ pub fn expand(fsm: &mut Fsm) -> Result<TokenStream> {
         ))
         .unwrap(),
         parse2::<ImplItem>(quote!(
-            fn on_change(new_s: &#state_enum, e: &#event_enum, se: &mut #effect_handlers, change: edfsm::Change) {
+            #asyncness fn on_change(new_s: &#state_enum, e: &#event_enum, se: &mut #effect_handlers, change: edfsm::Change) {
                 if let edfsm::Change::Transitioned = change {
                     match new_s {
                         #( #entry_matches )*
@@ -318,14 +375,223 @@ pub fn expand(fsm: &mut Fsm) -> Result<TokenStream> {
         ))
         .unwrap(),
     ];
-    Ok(fsm.item_impl.to_token_stream())
+    if !follow_up_matches.is_empty() {
+        items.push(
+            parse2::<ImplItem>(quote!(
+                fn follow_up(s: &#state_enum, se: &mut #effect_handlers) -> Option<#event_enum> {
+                    match s {
+                        #( #follow_up_matches )*
+                        _ => None,
+                    }
+                }
+            ))
+            .unwrap(),
+        );
+    }
+    fsm.item_impl.items = items;
+
+    let (impl_generics, _, where_clause) = fsm.item_impl.generics.split_for_impl();
+    let self_ty = &fsm.item_impl.self_ty;
+    let state_eq = if args.state_eq {
+        quote!(
+            /// Returns true if the states are the same variant, regardless of their data.
+            pub fn state_eq(a: &#state_enum, b: &#state_enum) -> bool {
+                core::mem::discriminant(a) == core::mem::discriminant(b)
+            }
+        )
+    } else {
+        quote!()
+    };
+
+    let state_name = if args.state_name {
+        let states = declared_states(fsm)?;
+        let names = states.iter().map(|state| state.to_string());
+        quote!(
+            /// Returns the name of the state's variant, regardless of its data.
+            #[allow(unreachable_patterns)]
+            pub fn state_name(s: &#state_enum) -> &'static str {
+                match s {
+                    #( #state_enum::#states(_) => #names, )*
+                    _ => "",
+                }
+            }
+        )
+    } else {
+        quote!()
+    };
+
+    let machine = if args.machine {
+        // A machine runs an `Fsm`, and so there is none for an `AsyncFsm`.
+        if args.is_async {
+            return Err(Error::new_spanned(
+                self_ty,
+                "A machine cannot be generated for an AsyncFsm.",
+            ));
+        }
+        quote!(
+            /// Create a machine for this state machine, to be wired and run as a task.
+            /// This is `edfsm_machine::machine` for this type.
+            pub fn machine() -> impl edfsm_machine::Machine<Self> {
+                edfsm_machine::machine::<Self>()
+            }
+        )
+    } else {
+        quote!()
+    };
+
+    let describe = if args.describe {
+        let description = diagram::describe(fsm)?;
+        quote!(
+            /// Returns a human-readable table of the declared transitions, entry states
+            /// and ignored commands and events.
+            pub fn describe() -> ::std::string::String {
+                ::std::string::String::from(#description)
+            }
+        )
+    } else {
+        quote!()
+    };
+
+    let mermaid = if args.mermaid {
+        let diagram = diagram::mermaid(fsm)?;
+        quote!(
+            /// A Mermaid `stateDiagram-v2` of the declared transitions.
+            pub const MERMAID: &'static str = #diagram;
+        )
+    } else {
+        quote!()
+    };
+
+    let accepts = if args.accepts {
+        quote!(
+            /// Returns true if a step is declared for the command given the state.
+            /// The command is not performed and so there are no side effects.
+            #[allow(unreachable_patterns)]
+            pub fn accepts(s: &#state_enum, c: &#command_enum) -> bool {
+                match (s, c) {
+                    #( #accept_matches )*
+                    _ => false,
+                }
+            }
+        )
+    } else {
+        quote!()
+    };
+
+    let names = dsl_names(fsm)?;
+
+    let inherent = quote!(
+        impl #impl_generics #self_ty #where_clause {
+            #state_eq
+
+            #state_name
+
+            #machine
+
+            #describe
+
+            #mermaid
+
+            #accepts
+        }
+    );
+
+    let mut expanded = fsm.item_impl.to_token_stream();
+    expanded.extend(inherent);
+    expanded.extend(names);
+    Ok(expanded)
 }
 
 fn lowercase_ident(ident: &Ident) -> Ident {
     Ident::new(&ident.to_string().to_lowercase(), ident.span())
 }
 
-fn ident_from_type(from_type: &Type) -> Result<&Ident> {
+/// A function that refers to the enum variant of the same name as each struct named by a
+/// declaration, as a pattern. A misspelt or missing variant is then reported at the
+/// declaration. The variant's payload is not checked, and so may be, say, boxed. The
+/// function is within an anonymous const, so that it adds no name to the state machine.
+fn dsl_names(fsm: &Fsm) -> Result<TokenStream> {
+    let commands = fsm
+        .steps
+        .iter()
+        .filter_map(|s| s.command().as_ref())
+        .chain(fsm.ignore_commands.iter().map(|ic| &ic.command));
+    let events = fsm
+        .steps
+        .iter()
+        .filter_map(|s| s.event().as_ref())
+        .chain(fsm.entry_handlers.iter().filter_map(|ee| ee.event.as_ref()))
+        .chain(fsm.ignore_events.iter().map(|ie| &ie.event));
+
+    let mut checks = Vec::new();
+    let mut check = |enum_: &Type, names: Vec<&Ident>| {
+        for name in names {
+            // The enum is also given the span of the declaration, so that errors point there.
+            let enum_: TokenStream = enum_
+                .to_token_stream()
+                .into_iter()
+                .map(|mut t| {
+                    t.set_span(name.span());
+                    t
+                })
+                .collect();
+            checks.push(quote_spanned!(name.span()=>
+                let _ = |v: &#enum_| matches!(v, #enum_::#name(..));
+            ));
+        }
+    };
+    check(&fsm.state_enum, declared_states(fsm)?);
+    check(&fsm.command_enum, unique_idents(commands)?);
+    check(&fsm.event_enum, unique_idents(events)?);
+
+    let (impl_generics, _, where_clause) = fsm.item_impl.generics.split_for_impl();
+    Ok(quote!(
+        const _: () = {
+            #[allow(dead_code)]
+            fn check_dsl_names #impl_generics () #where_clause {
+                #( #checks )*
+            }
+        };
+    ))
+}
+
+fn unique_idents<'a>(types: impl Iterator<Item = &'a Type>) -> Result<Vec<&'a Ident>> {
+    let mut idents = Vec::new();
+    for t in types {
+        let ident = ident_from_type(t)?;
+        if !idents.contains(&ident) {
+            idents.push(ident);
+        }
+    }
+    Ok(idents)
+}
+
+/// The variants of the state enum named by declarations, in order of declaration.
+/// The variants are only known where they are declared.
+pub fn declared_states(fsm: &Fsm) -> Result<Vec<&Ident>> {
+    let declared = fsm
+        .entry_handlers
+        .iter()
+        .map(|ee| &ee.state)
+        .chain(fsm.steps.iter().flat_map(|s| {
+            iter::once(s.from_state()).chain(s.to_state().iter().flat_map(|t| t.states.iter()))
+        }))
+        .chain(fsm.ignore_commands.iter().map(|ic| &ic.from_state))
+        .chain(fsm.ignore_events.iter().map(|ie| &ie.from_state));
+    let mut states = Vec::new();
+    for state in declared {
+        if let Type::Infer(_) = state {
+            continue;
+        }
+        let state = ident_from_type(state)?;
+        if !states.contains(&state) {
+            states.push(state);
+        }
+    }
+    Ok(states)
+}
+
+pub fn ident_from_type(from_type: &Type) -> Result<&Ident> {
     if let Type::Path(path) = from_type {
         if path.path.segments.len() == 1 {
             let segment = path.path.segments.next().unwrap().first().unwrap();
@@ -1,8 +1,9 @@
 use proc_macro::TokenStream;
 
+mod diagram;
 mod expand;
 mod parse;
-use proc_macro_error::{abort_call_site, proc_macro_error};
+use proc_macro_error::proc_macro_error;
 use syn::parse2;
 
 /// Provides a DSL that conveniently implements the FSM trait.
@@ -35,12 +36,22 @@ use syn::parse2;
 /// }
 /// ```
 ///
+/// An entry handler may instead yield an event, declared as e.g. `state!(Connecting / entry => Connected)`.
+/// The handler then returns `Option<Connected>` and is called from a generated `follow_up` method,
+/// so that `Fsm::step_all` applies the event immediately. Follow-up events are applied to a depth of
+/// at most `edfsm::MAX_CASCADE`, which guards against states that follow up with each other endlessly.
+/// This is not available with `async`.
+///
 /// The `command!` macro declares an entire transition using the form:
 ///
 /// ```compile_fail
-/// <from-state> => <given-command> [=> <yields-event> []=> <to-state>]]
+/// <from-state> => <given-command> [=> <yields-event> []=> <to-state> [ / reentry]]]
 /// ```
 ///
+/// Where `<to-state>` is the same as `<from-state>`, the step is a transition, and so the
+/// entry handler of the state is called again. Qualifying the step with `/ reentry` declares
+/// this self-transition explicitly, documenting that the entry handler is intended to run.
+///
 /// In our example, for the first transition, multiple methods will be called that the developer must provide e.g.:
 ///
 /// ```compile_fail
@@ -66,19 +77,53 @@ use syn::parse2;
 /// becomes:
 ///
 /// ```compile_fail
-/// <from-state> => <given-event> [=> <to-state> [ / action]]
+/// <from-state> => <given-event> [=> <to-state> [ / action] [ / reentry]]
 /// ```
 ///
 /// The `/ action` is optional and is used to declare that a side-effect is to be performed.
+/// The `/ reentry` is also optional and declares an explicit self-transition as above.
+///
+/// Should a required method not be implemented, the compiler reports the missing method by
+/// name at the command, event or state of the declaration that requires it. Likewise, each
+/// struct named by a declaration must have an enum variant of the same name, such as `Start(Start)`
+/// or `Start(Box<Start>)`. A variant that is missing or misspelt is reported at the declaration.
+///
+/// The attribute accepts the following optional arguments e.g. `#[impl_fsm(state_eq, mermaid)]`:
+///
+/// - `accepts` generates an inherent `accepts(s, c) -> bool` function that returns `true` if a
+///   `command!` step is declared for the state and command, without performing anything.
+/// - `state_eq` generates an inherent `state_eq(a, b) -> bool` function that returns `true` if
+///   both states are the same variant, regardless of their data. This is useful for asserting
+///   the state in tests where the state enum does not implement `PartialEq`.
+/// - `state_name` generates an inherent `state_name(s) -> &'static str` function that returns the
+///   name of the state's variant, such as for a summary of the state (see `edfsm::StateSummary`).
+///   Variants are known by their declarations, and so a variant that is not named by any
+///   declaration is named by an empty string.
+/// - `async` implements `AsyncFsm` instead of `Fsm`, for effect handlers that must be awaited.
+///   The annotated impl must then be of `AsyncFsm`. Command, entry and change handlers are
+///   declared as `async fn` and awaited, while event handlers remain synchronous.
+/// - `machine` generates an inherent `machine()` function that returns `edfsm_machine::machine::<Self>()`,
+///   ready to be wired and run as a task. The crate must then depend on `edfsm-machine`, which is
+///   why this is opted into. It is not available with `async`.
+/// - `describe` generates an inherent `describe() -> String` function that returns a human-readable
+///   table of the declared transitions, entry states and ignored commands and events, such as
+///   for printing at startup. The table is formed when the code is compiled. The generated code
+///   refers to `std`, which is why this is opted into.
+/// - `mermaid` generates an inherent `MERMAID` constant holding a Mermaid `stateDiagram-v2` of the
+///   declared transitions. Each edge is labelled by its command and event, e.g.
+///   `Idle --> Running : Start/Started`, or by its event alone, and a transition from `_` is drawn
+///   from each declared state, including its target, as remaining in the same state is a transition. The diagram can be written out, say by a test, and embedded in
+///   documentation, so that it stays in sync with the code.
 #[proc_macro_attribute]
 #[proc_macro_error]
 pub fn impl_fsm(input: TokenStream, annotated_item: TokenStream) -> TokenStream {
-    if !input.is_empty() {
-        abort_call_site!("this attribute takes no arguments"; help = "use `#[impl-fsm]`")
-    }
+    let args = match parse2::<parse::Args>(input.into()) {
+        Ok(args) => args,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
     match parse2::<parse::Fsm>(annotated_item.into()) {
-        Ok(mut fsm) => match expand::expand(&mut fsm) {
+        Ok(mut fsm) => match expand::expand(&mut fsm, &args) {
             Ok(expanded) => expanded.into(),
             Err(e) => e.to_compile_error().into(),
         },
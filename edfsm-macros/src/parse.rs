@@ -2,12 +2,57 @@ use std::mem;
 
 use quote::quote;
 use syn::{
+    ext::IdentExt,
     parse::{Parse, ParseStream},
     parse2, token, Error, Ident, ImplItem, ImplItemMacro, ImplItemType, ItemImpl, Result, Type,
 };
 
+/// The arguments of the attribute e.g. `#[impl_fsm(state_eq)]`
+#[derive(Default)]
+pub struct Args {
+    pub state_eq: bool,
+    pub state_name: bool,
+    pub is_async: bool,
+    pub machine: bool,
+    pub describe: bool,
+    pub mermaid: bool,
+    pub accepts: bool,
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut args = Args::default();
+        while !input.is_empty() {
+            let ident = Ident::parse_any(input)?;
+            match ident.to_string().as_str() {
+                "state_eq" => args.state_eq = true,
+                "state_name" => args.state_name = true,
+                "async" => args.is_async = true,
+                "machine" => args.machine = true,
+                "describe" => args.describe = true,
+                "mermaid" => args.mermaid = true,
+                "accepts" => args.accepts = true,
+                ident_str => {
+                    return Err(Error::new_spanned(
+                        ident,
+                        format!(
+                            "Unknown argument: `{ident_str}`. Use only `state_eq`, `state_name`, `async`, `machine`, `describe`, `mermaid` or `accepts` here."
+                        ),
+                    ));
+                }
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<token::Comma>()?;
+        }
+        Ok(args)
+    }
+}
+
 pub struct Entry {
     pub state: Type,
+    pub event: Option<Type>,
 }
 
 impl Parse for Entry {
@@ -19,7 +64,12 @@ impl Parse for Entry {
         if ident_str != "entry" {
             return Err(Error::new_spanned(ident, format!("Unknown state qualifer: `/ {ident_str}`. Use only `/ entry` to indicate entry points here.")));
         };
-        Ok(Self { state })
+        let event = if input.parse::<token::FatArrow>().is_ok() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self { state, event })
     }
 }
 
@@ -51,6 +101,7 @@ pub trait Step {
     fn event(&self) -> &Option<Type>;
     fn to_state(&self) -> &Option<TargetStates>;
     fn on_change(&self) -> bool;
+    fn reentry(&self) -> bool;
 }
 
 pub struct CommandStep {
@@ -58,6 +109,7 @@ pub struct CommandStep {
     pub command: Option<Type>,
     pub event: Option<Type>,
     pub to_state: Option<TargetStates>,
+    pub reentry: bool,
 }
 
 impl Parse for CommandStep {
@@ -76,11 +128,23 @@ impl Parse for CommandStep {
         } else {
             (None, None)
         };
+        let reentry = if to_state.is_some() && input.peek(token::Div) {
+            input.parse::<token::Div>()?;
+            let ident = input.parse::<Ident>()?;
+            let ident_str = ident.to_string();
+            if ident_str != "reentry" {
+                return Err(Error::new_spanned(ident, format!("Unknown state qualifer: `/ {ident_str}`. Use only `/ reentry` to indicate an explicit self-transition here.")));
+            };
+            true
+        } else {
+            false
+        };
         Ok(Self {
             from_state,
             command,
             event,
             to_state,
+            reentry,
         })
     }
 }
@@ -105,6 +169,10 @@ impl Step for CommandStep {
     fn on_change(&self) -> bool {
         false
     }
+
+    fn reentry(&self) -> bool {
+        self.reentry
+    }
 }
 
 pub struct EventStep {
@@ -113,6 +181,7 @@ pub struct EventStep {
     pub event: Option<Type>,
     pub to_state: Option<TargetStates>,
     pub on_change: bool,
+    pub reentry: bool,
 }
 
 impl Parse for EventStep {
@@ -126,17 +195,19 @@ impl Parse for EventStep {
         } else {
             None
         };
-        let on_change = if input.peek(token::Div) {
+        let mut on_change = false;
+        let mut reentry = false;
+        while input.peek(token::Div) {
             input.parse::<token::Div>()?;
             let ident = input.parse::<Ident>()?;
-            let ident_str = ident.to_string();
-            if ident_str != "action" {
-                return Err(Error::new_spanned(ident, format!("Unknown state qualifer: `/ {ident_str}`. Use only `/ action` to indicate there is going to be an action here.")));
-            };
-            true
-        } else {
-            false
-        };
+            match ident.to_string().as_str() {
+                "action" => on_change = true,
+                "reentry" if to_state.is_some() => reentry = true,
+                ident_str => {
+                    return Err(Error::new_spanned(ident, format!("Unknown state qualifer: `/ {ident_str}`. Use only `/ action` to indicate there is going to be an action here, or `/ reentry` to indicate an explicit self-transition.")));
+                }
+            }
+        }
 
         Ok(Self {
             from_state,
@@ -144,6 +215,7 @@ impl Parse for EventStep {
             event,
             to_state,
             on_change,
+            reentry,
         })
     }
 }
@@ -168,6 +240,10 @@ impl Step for EventStep {
     fn on_change(&self) -> bool {
         self.on_change
     }
+
+    fn reentry(&self) -> bool {
+        self.reentry
+    }
 }
 
 pub struct IgnoreCommand {
@@ -0,0 +1,65 @@
+use crate::{Change, Input};
+use core::future::Future;
+
+/// A state machine whose effects are performed asynchronously.
+///
+/// This is the counterpart of `Fsm` for effect handlers that must await, for example
+/// to write to a bus before proceeding. Only the effects are asynchronous. Applying an event
+/// to the state with `on_event` remains synchronous and free of effects, so events replay
+/// the same as they do for an `Fsm`. Effects are awaited within `step`, and so a slow
+/// effect delays the next input.
+///
+/// `#[impl_fsm(async)]` implements this trait from the same declarations as `#[impl_fsm]`
+/// implements `Fsm`, generating async `for_command` and `on_change` methods that await
+/// each command, entry and change handler. `Fsm` remains the trait that `edfsm_machine`
+/// runs, and `Fsm` effects remain synchronous. An `AsyncFsm` is stepped by the application.
+pub trait AsyncFsm {
+    /// The state managed by the FSM
+    type S;
+    /// The command(s) that are able to be processed by the FSM
+    type C;
+    /// The event emitted having performed a command
+    type E;
+    /// The side effect handler
+    type SE;
+
+    /// Given a state and command, optionally emit an event if it applies, awaiting
+    /// any effects performed.
+    fn for_command(
+        s: &Self::S,
+        c: Self::C,
+        se: &mut Self::SE,
+    ) -> impl Future<Output = Option<Self::E>>;
+
+    /// Given a state and event, modify state, which could indicate transition to
+    /// the next state. No side effects are to be performed.
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change>;
+
+    /// Given a state and event having been applied then perform any side effects,
+    /// awaiting them.
+    fn on_change(
+        s: &Self::S,
+        e: &Self::E,
+        se: &mut Self::SE,
+        change: Change,
+    ) -> impl Future<Output = ()>;
+
+    /// This is the main entry point to the event driven FSM.
+    /// Runs the state machine for a command or event, optionally performing effects,
+    /// possibly producing an event and possibly transitioning to a new state.
+    fn step(
+        s: &mut Self::S,
+        i: Input<Self::C, Self::E>,
+        se: &mut Self::SE,
+    ) -> impl Future<Output = Option<Self::E>> {
+        async move {
+            let e = match i {
+                Input::Command(c) => Self::for_command(s, c, se).await,
+                Input::Event(e) => Some(e),
+            }?;
+            let change = Self::on_event(s, &e)?;
+            Self::on_change(s, &e, se, change).await;
+            Some(e)
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use core::{future::Future, time::Duration};
+
+/// A source of time for timers, independent of any particular runtime.
+///
+/// Time-based behaviour, such as a timeout or a retry delay, can be written generically
+/// over `Clock` and then run with tokio on std targets or Embassy on embedded ones.
+/// Implementations are provided by `edfsm-machine` for each of its runtime features.
+pub trait Clock {
+    /// A point in time, as measured by this clock.
+    type Instant: Copy + Ord;
+
+    /// The current time.
+    fn now(&self) -> Self::Instant;
+
+    /// The time that is the given duration after now.
+    fn deadline(&self, after: Duration) -> Self::Instant;
+
+    /// Wait until the given time. This completes immediately if the time has passed.
+    fn sleep_until(&self, deadline: Self::Instant) -> impl Future<Output = ()> + Send;
+
+    /// Wait for the given duration.
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send {
+        self.sleep_until(self.deadline(duration))
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{Cascade, Change, Fsm, Input, MAX_CASCADE};
+use core::marker::PhantomData;
+
+/// A state machine whose commands may each produce several events, as a compound command.
+pub trait Compound: Fsm {
+    /// Given a state, a command and the events produced for the command so far, optionally
+    /// produce the next event for the command, or None when the command is complete.
+    ///
+    /// This is consulted again after each event is applied, along with any of its follow-up
+    /// events, so the state is that resulting from the prior events. Those events are also
+    /// available to inspect, allowing the command to branch on what it has done.
+    fn for_command_many(
+        s: &Self::S,
+        c: &Self::C,
+        prior: &Cascade<Self::E>,
+        se: &mut Self::SE,
+    ) -> Option<Self::E>;
+}
+
+/// An `Fsm` that delegates all behaviour to `M` while running its commands as compound
+/// commands, see `Compound`.
+///
+/// `step_all` applies each event that `M::for_command_many` produces for a command in turn,
+/// until it returns None, returning all of them. A compound command also ends when one of
+/// its events is not applied, or when `MAX_CASCADE + 1` events, including follow-ups, have
+/// been applied for it. `step` and `for_command` produce just the first event. Events are
+/// stepped with `M::step_change`, so that any wrapper within `M` keeps its behaviour.
+pub struct Compounded<M>(PhantomData<M>);
+
+impl<M> Fsm for Compounded<M>
+where
+    M: Compound,
+{
+    type S = M::S;
+    type C = M::C;
+    type E = M::E;
+    type SE = M::SE;
+
+    fn for_command(s: &Self::S, c: Self::C, se: &mut Self::SE) -> Option<Self::E> {
+        M::for_command_many(s, &c, &Cascade::default(), se)
+    }
+
+    delegate!(
+        M, se => se;
+        on_event, apply, on_change, check_invariants, on_init, on_stop, follow_up
+    );
+
+    fn step_change(
+        s: &mut Self::S,
+        i: Input<Self::C, Self::E>,
+        se: &mut Self::SE,
+    ) -> Option<(Self::E, Option<Change>)> {
+        let e = match i {
+            Input::Command(c) => M::for_command_many(s, &c, &Cascade::default(), se)?,
+            Input::Event(e) => e,
+        };
+        M::step_change(s, Input::Event(e), se)
+    }
+
+    fn step_all(
+        s: &mut Self::S,
+        i: Input<Self::C, Self::E>,
+        se: &mut Self::SE,
+    ) -> Cascade<Self::E> {
+        let c = match i {
+            Input::Command(c) => c,
+            i => return M::step_all(s, i, se),
+        };
+        let mut cascade = Cascade::default();
+        while cascade.len <= MAX_CASCADE {
+            let Some(e) = M::for_command_many(s, &c, &cascade, se) else {
+                break;
+            };
+            let before = cascade.len;
+            let mut next = Some(e);
+            while let Some(e) = next.take() {
+                if let Some((e, change)) = M::step_change(s, Input::Event(e), se) {
+                    cascade.push(e);
+                    if matches!(change, Some(Change::Transitioned)) && cascade.len <= MAX_CASCADE {
+                        next = M::follow_up(s, se);
+                    }
+                }
+            }
+            if cascade.len == before {
+                break;
+            }
+        }
+        cascade
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{Applied, Change, Fsm, Input, Terminating};
+use core::marker::PhantomData;
+use uuid::Uuid;
+
+/// The ability to identify the request that an input originated from, for tracing.
+///
+/// This can be implemented by `Fsm::C` for use with `Correlating`.
+pub trait Correlated {
+    /// The correlation id of this input, if any.
+    fn correlation_id(&self) -> Option<Uuid>;
+}
+
+/// An event tagged with the correlation id of the command that produced it.
+///
+/// Events are logged in this form by a `Correlating` state machine, so that
+/// the event log can be traced back to originating commands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Correlation<E> {
+    /// The correlation id of the originating command, if any.
+    pub id: Option<Uuid>,
+    /// The event.
+    pub item: E,
+}
+
+impl<E> Correlated for Correlation<E> {
+    fn correlation_id(&self) -> Option<Uuid> {
+        self.id
+    }
+}
+
+impl<E> Terminating for Correlation<E>
+where
+    E: Terminating,
+{
+    fn terminating(&self) -> bool {
+        self.item.terminating()
+    }
+}
+
+/// An `Fsm` that delegates all behaviour to `M` while propagating the correlation id
+/// of each command to the events it produces.
+///
+/// The command type must implement `Correlated`. The effector, `Correlator`, holds the
+/// current correlation id. This is taken from the command in `for_command` and copied
+/// onto the event produced. When an event is applied, the current id is taken from
+/// the event, so an event given directly as input retains its own correlation id.
+pub struct Correlating<M>(PhantomData<M>);
+
+/// The effector of a `Correlating` state machine. This wraps the effector of the underlying
+/// state machine and holds the current correlation id.
+pub struct Correlator<SE> {
+    inner: SE,
+    current: Option<Uuid>,
+}
+
+impl<SE> Correlator<SE> {
+    /// Wrap the effector of the underlying state machine.
+    pub fn new(inner: SE) -> Self {
+        Self {
+            inner,
+            current: None,
+        }
+    }
+
+    /// The correlation id of the input most recently processed.
+    pub fn correlation_id(&self) -> Option<Uuid> {
+        self.current
+    }
+}
+
+effector!([SE] Correlator<SE>);
+
+impl<SE> Default for Correlator<SE>
+where
+    SE: Default,
+{
+    fn default() -> Self {
+        Self::new(SE::default())
+    }
+}
+
+impl<M> Fsm for Correlating<M>
+where
+    M: Fsm,
+    M::C: Correlated,
+{
+    type S = M::S;
+    type C = M::C;
+    type E = Correlation<M::E>;
+    type SE = Correlator<M::SE>;
+
+    fn for_command(s: &Self::S, c: Self::C, se: &mut Self::SE) -> Option<Self::E> {
+        se.current = c.correlation_id();
+        M::for_command(s, c, &mut se.inner).map(|item| Correlation {
+            id: se.current,
+            item,
+        })
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        M::on_event(s, &e.item)
+    }
+
+    fn apply(s: &mut Self::S, e: &Self::E) -> Applied {
+        M::apply(s, &e.item)
+    }
+
+    fn on_change(s: &Self::S, e: &Self::E, se: &mut Self::SE, change: Change) {
+        se.current = e.id;
+        M::on_change(s, &e.item, &mut se.inner, change)
+    }
+
+    delegate!(M, se => &mut se.inner; check_invariants, on_init, on_stop);
+
+    fn follow_up(s: &Self::S, se: &mut Self::SE) -> Option<Self::E> {
+        M::follow_up(s, &mut se.inner).map(|item| Correlation {
+            id: se.current,
+            item,
+        })
+    }
+
+    fn step_change(
+        s: &mut Self::S,
+        i: Input<Self::C, Self::E>,
+        se: &mut Self::SE,
+    ) -> Option<(Self::E, Option<Change>)> {
+        let i = match i {
+            Input::Command(c) => {
+                se.current = c.correlation_id();
+                Input::Command(c)
+            }
+            Input::Event(e) => {
+                se.current = e.id;
+                Input::Event(e.item)
+            }
+        };
+        let (item, change) = M::step_change(s, i, &mut se.inner)?;
+        let e = Correlation {
+            id: se.current,
+            item,
+        };
+        Some((e, change))
+    }
+}
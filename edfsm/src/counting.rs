@@ -0,0 +1,116 @@
+use crate::{Change, Fsm, Input, Named};
+use core::marker::PhantomData;
+
+/// The counts of commands with a given name, recorded by `Counting`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandCount {
+    /// The name of the command.
+    pub name: &'static str,
+    /// The number of commands that produced an event.
+    pub accepted: u64,
+    /// The number of commands that produced no event.
+    pub rejected: u64,
+}
+
+/// An `Fsm` that delegates all behaviour to `M` while counting the commands accepted
+/// and rejected for each command name, for monitoring.
+///
+/// The command type must implement `Named`. A command is accepted when stepping it
+/// produces an event, and rejected otherwise. Counts are held for up to `K` distinct
+/// command names in the effector, `Tally`. Once `K` names are held, commands with
+/// other names are not counted.
+pub struct Counting<M, const K: usize>(PhantomData<M>);
+
+/// The effector of a `Counting` state machine. This wraps the effector of the underlying
+/// state machine and holds the command counts.
+pub struct Tally<SE, const K: usize> {
+    inner: SE,
+    counts: [Option<CommandCount>; K],
+}
+
+impl<SE, const K: usize> Tally<SE, K> {
+    /// Wrap the effector of the underlying state machine.
+    pub fn new(inner: SE) -> Self {
+        Self {
+            inner,
+            counts: [None; K],
+        }
+    }
+
+    /// The counts for each command name, in the order first seen.
+    pub fn counts(&self) -> impl Iterator<Item = &CommandCount> {
+        self.counts.iter().flatten()
+    }
+
+    /// The counts for a command name, if any have been seen.
+    pub fn count(&self, name: &str) -> Option<&CommandCount> {
+        self.counts().find(|c| c.name == name)
+    }
+
+    fn record(&mut self, name: &'static str, accepted: bool) {
+        let slot = self
+            .counts
+            .iter_mut()
+            .find(|c| c.is_none_or(|c| c.name == name));
+        if let Some(slot) = slot {
+            let count = slot.get_or_insert(CommandCount {
+                name,
+                accepted: 0,
+                rejected: 0,
+            });
+            if accepted {
+                count.accepted += 1;
+            } else {
+                count.rejected += 1;
+            }
+        }
+    }
+}
+
+effector!([SE, const K: usize] Tally<SE, K>);
+
+impl<SE, const K: usize> Default for Tally<SE, K>
+where
+    SE: Default,
+{
+    fn default() -> Self {
+        Self::new(SE::default())
+    }
+}
+
+impl<M, const K: usize> Fsm for Counting<M, K>
+where
+    M: Fsm,
+    M::C: Named,
+{
+    type S = M::S;
+    type C = M::C;
+    type E = M::E;
+    type SE = Tally<M::SE, K>;
+
+    fn for_command(s: &Self::S, c: Self::C, se: &mut Self::SE) -> Option<Self::E> {
+        let name = c.name();
+        let e = M::for_command(s, c, &mut se.inner);
+        se.record(name, e.is_some());
+        e
+    }
+
+    delegate!(M, se => &mut se.inner;
+        on_event, apply, on_change, check_invariants, on_init, on_stop, follow_up);
+
+    fn step_change(
+        s: &mut Self::S,
+        i: Input<Self::C, Self::E>,
+        se: &mut Self::SE,
+    ) -> Option<(Self::E, Option<Change>)> {
+        match i {
+            Input::Command(c) => {
+                let name = c.name();
+                let step = M::step_change(s, Input::Command(c), &mut se.inner);
+                se.record(name, step.is_some());
+                step
+            }
+            i => M::step_change(s, i, &mut se.inner),
+        }
+    }
+}
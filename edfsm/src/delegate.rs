@@ -0,0 +1,105 @@
+//! Delegation shared by the state machines that wrap another, `M`.
+//!
+//! A wrapper that customises stepping overrides `step_change` and steps `M` with
+//! `M::step_change`, rather than `M::for_command` and `M::apply`, so that any wrapper
+//! within `M` keeps its own behaviour. A wrapper with nothing to add to a step
+//! delegates `step_change` with the others here.
+
+/// Delegate the named `Fsm` methods to `$m`, given an expression for the effector
+/// of `$m` in terms of the wrapper's effector, `$se`. The state, command and event
+/// types of the wrapper are those of `$m`.
+macro_rules! delegate {
+    ($m:ty, $se:ident => $inner:expr; $($method:ident),+ $(,)?) => {
+        $( delegate!(@$method $m, $se => $inner); )+
+    };
+    (@for_command $m:ty, $se:ident => $inner:expr) => {
+        fn for_command(s: &Self::S, c: Self::C, $se: &mut Self::SE) -> Option<Self::E> {
+            <$m>::for_command(s, c, $inner)
+        }
+    };
+    (@on_event $m:ty, $se:ident => $inner:expr) => {
+        fn on_event(s: &mut Self::S, e: &Self::E) -> Option<$crate::Change> {
+            <$m>::on_event(s, e)
+        }
+    };
+    (@apply $m:ty, $se:ident => $inner:expr) => {
+        fn apply(s: &mut Self::S, e: &Self::E) -> $crate::Applied {
+            <$m>::apply(s, e)
+        }
+    };
+    (@on_change $m:ty, $se:ident => $inner:expr) => {
+        fn on_change(s: &Self::S, e: &Self::E, $se: &mut Self::SE, change: $crate::Change) {
+            <$m>::on_change(s, e, $inner, change)
+        }
+    };
+    (@check_invariants $m:ty, $se:ident => $inner:expr) => {
+        fn check_invariants(s: &Self::S) -> Result<(), &'static str> {
+            <$m>::check_invariants(s)
+        }
+    };
+    (@on_init $m:ty, $se:ident => $inner:expr) => {
+        fn on_init(s: &Self::S, $se: &mut Self::SE) {
+            <$m>::on_init(s, $inner)
+        }
+    };
+    (@on_stop $m:ty, $se:ident => $inner:expr) => {
+        fn on_stop(s: &Self::S, $se: &mut Self::SE) {
+            <$m>::on_stop(s, $inner)
+        }
+    };
+    (@follow_up $m:ty, $se:ident => $inner:expr) => {
+        fn follow_up(s: &Self::S, $se: &mut Self::SE) -> Option<Self::E> {
+            <$m>::follow_up(s, $inner)
+        }
+    };
+    (@step_change $m:ty, $se:ident => $inner:expr) => {
+        fn step_change(
+            s: &mut Self::S,
+            i: $crate::Input<Self::C, Self::E>,
+            $se: &mut Self::SE,
+        ) -> Option<(Self::E, Option<$crate::Change>)> {
+            <$m>::step_change(s, i, $inner)
+        }
+    };
+}
+
+/// Implement the accessors, `Init` and `Drain` for the effector of a wrapper, given its
+/// generic parameters in brackets. The effector holds that of the underlying state
+/// machine, of type `SE`, in its `inner` field.
+macro_rules! effector {
+    ([$($gen:tt)*] $ty:ty $(where $($bound:tt)*)?) => {
+        impl<$($gen)*> $ty $(where $($bound)*)? {
+            /// The effector of the underlying state machine.
+            pub fn inner(&self) -> &SE {
+                &self.inner
+            }
+
+            /// The effector of the underlying state machine, mutably.
+            pub fn inner_mut(&mut self) -> &mut SE {
+                &mut self.inner
+            }
+        }
+
+        impl<S, $($gen)*> $crate::Init<S> for $ty
+        where
+            SE: $crate::Init<S>,
+            $($($bound)*)?
+        {
+            fn init(&mut self, state: &S) {
+                self.inner.init(state)
+            }
+        }
+
+        impl<$($gen)*> $crate::Drain for $ty
+        where
+            SE: $crate::Drain,
+            $($($bound)*)?
+        {
+            type Item = SE::Item;
+
+            fn drain_all(&mut self) -> impl Iterator<Item = Self::Item> + Send {
+                self.inner.drain_all()
+            }
+        }
+    };
+}
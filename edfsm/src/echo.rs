@@ -0,0 +1,103 @@
+use crate::{Applied, Change, Fsm, Input, Terminating};
+use core::marker::PhantomData;
+
+/// An event of a `CommandEcho` state machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Echoed<C, E> {
+    /// A command, followed by the event produced for it, if any.
+    Command(C, Option<E>),
+    /// An event given directly as input.
+    Event(E),
+}
+
+impl<C, E> Echoed<C, E> {
+    /// The event of the underlying state machine, if any.
+    pub fn event(&self) -> Option<&E> {
+        match self {
+            Echoed::Command(_, e) => e.as_ref(),
+            Echoed::Event(e) => Some(e),
+        }
+    }
+}
+
+impl<C, E> Terminating for Echoed<C, E>
+where
+    E: Terminating,
+{
+    fn terminating(&self) -> bool {
+        self.event().is_some_and(E::terminating)
+    }
+}
+
+/// An `Fsm` that delegates all behaviour to `M` while echoing each command as an event,
+/// so that an event log is a full audit log of the commands received.
+///
+/// As `step` produces one event per input, the echo of a command and the event produced
+/// for it are recorded together as `Echoed::Command`, command first. A command is echoed
+/// whether or not `M` produces an event for it. This requires `C: Clone`.
+///
+/// Echoes integrate with the normal event log and replay. When replayed, the event
+/// produced for a command is applied as usual and a command without one is ignored,
+/// so `M`'s state is rehydrated exactly as if it had not been wrapped.
+pub struct CommandEcho<M>(PhantomData<M>);
+
+impl<M> Fsm for CommandEcho<M>
+where
+    M: Fsm,
+    M::C: Clone,
+{
+    type S = M::S;
+    type C = M::C;
+    type E = Echoed<M::C, M::E>;
+    type SE = M::SE;
+
+    fn for_command(s: &Self::S, c: Self::C, se: &mut Self::SE) -> Option<Self::E> {
+        let e = M::for_command(s, c.clone(), se);
+        Some(Echoed::Command(c, e))
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        M::on_event(s, e.event()?)
+    }
+
+    fn apply(s: &mut Self::S, e: &Self::E) -> Applied {
+        match e.event() {
+            Some(e) => M::apply(s, e),
+            None => Applied::Ignored,
+        }
+    }
+
+    fn on_change(s: &Self::S, e: &Self::E, se: &mut Self::SE, change: Change) {
+        if let Some(e) = e.event() {
+            M::on_change(s, e, se, change)
+        }
+    }
+
+    delegate!(M, se => se; check_invariants, on_init, on_stop);
+
+    fn follow_up(s: &Self::S, se: &mut Self::SE) -> Option<Self::E> {
+        M::follow_up(s, se).map(Echoed::Event)
+    }
+
+    fn step_change(
+        s: &mut Self::S,
+        i: Input<Self::C, Self::E>,
+        se: &mut Self::SE,
+    ) -> Option<(Self::E, Option<Change>)> {
+        match i {
+            Input::Command(c) => match M::step_change(s, Input::Command(c.clone()), se) {
+                Some((e, change)) => Some((Echoed::Command(c, Some(e)), change)),
+                None => Some((Echoed::Command(c, None), None)),
+            },
+            Input::Event(Echoed::Command(c, Some(e))) => {
+                let (e, change) = M::step_change(s, Input::Event(e), se)?;
+                Some((Echoed::Command(c, Some(e)), change))
+            }
+            Input::Event(Echoed::Command(_, None)) => None,
+            Input::Event(Echoed::Event(e)) => {
+                let (e, change) = M::step_change(s, Input::Event(e), se)?;
+                Some((Echoed::Event(e), change))
+            }
+        }
+    }
+}
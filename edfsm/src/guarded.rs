@@ -0,0 +1,76 @@
+use crate::{Change, Fsm, Input, Named};
+use core::marker::PhantomData;
+
+/// An `Fsm` that delegates all behaviour to `M` while checking that each transition is
+/// one of a table of allowed `(from, to)` pairs of state names, panicking otherwise.
+///
+/// This guards against a state machine, perhaps generated, making a transition that
+/// its design does not permit. The state type must implement `Named`. The table is held
+/// by the effector, `Guard`, and is checked as `M` is stepped, given the name of the state
+/// that the step started from, so that any wrapper within `M` keeps its behaviour. The check
+/// follows the step of `M`, including its effects. Only `step_change` checks, so events
+/// replayed from a log, and those applied by a container that calls `on_change` itself,
+/// are not checked.
+pub struct Guarded<M>(PhantomData<M>);
+
+/// The effector of a `Guarded` state machine. This wraps the effector of the underlying
+/// state machine and holds the table of allowed transitions.
+pub struct Guard<SE> {
+    inner: SE,
+    from: &'static str,
+    allowed: &'static [(&'static str, &'static str)],
+}
+
+impl<SE> Guard<SE> {
+    /// Wrap the effector of the underlying state machine, allowing only the
+    /// given `(from, to)` transitions.
+    pub fn new(inner: SE, allowed: &'static [(&'static str, &'static str)]) -> Self {
+        Self {
+            inner,
+            from: "",
+            allowed,
+        }
+    }
+
+    /// The transition between the named states is allowed.
+    pub fn is_allowed(&self, from: &str, to: &str) -> bool {
+        self.allowed.iter().any(|&(f, t)| f == from && t == to)
+    }
+
+    fn check(&self, to: &str) {
+        assert!(
+            self.is_allowed(self.from, to),
+            "transition from {} to {to} is not allowed",
+            self.from
+        );
+    }
+}
+
+effector!([SE] Guard<SE>);
+
+impl<M> Fsm for Guarded<M>
+where
+    M: Fsm,
+    M::S: Named,
+{
+    type S = M::S;
+    type C = M::C;
+    type E = M::E;
+    type SE = Guard<M::SE>;
+
+    delegate!(M, se => &mut se.inner;
+        for_command, on_event, apply, on_change, check_invariants, on_init, on_stop, follow_up);
+
+    fn step_change(
+        s: &mut Self::S,
+        i: Input<Self::C, Self::E>,
+        se: &mut Self::SE,
+    ) -> Option<(Self::E, Option<Change>)> {
+        se.from = s.name();
+        let (e, change) = M::step_change(s, i, &mut se.inner)?;
+        if let Some(Change::Transitioned) = change {
+            se.check(s.name());
+        }
+        Some((e, change))
+    }
+}
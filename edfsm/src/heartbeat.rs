@@ -0,0 +1,110 @@
+use crate::{Applied, Change, Fsm, Input, Summarize, Terminating};
+use core::marker::PhantomData;
+
+/// A command of a `Heartbeating` state machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeartbeatCommand<C> {
+    /// A command for the underlying state machine.
+    Command(C),
+    /// Request a heartbeat event.
+    Heartbeat,
+}
+
+/// An event of a `Heartbeating` state machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeartbeatEvent<E, H> {
+    /// An event of the underlying state machine.
+    Event(E),
+    /// A heartbeat carrying a summary of the state when it was produced.
+    Heartbeat(H),
+}
+
+impl<E, H> Terminating for HeartbeatEvent<E, H>
+where
+    E: Terminating,
+{
+    fn terminating(&self) -> bool {
+        match self {
+            HeartbeatEvent::Event(e) => e.terminating(),
+            HeartbeatEvent::Heartbeat(_) => false,
+        }
+    }
+}
+
+/// An `Fsm` that delegates all behaviour to `M` and also produces heartbeat events,
+/// for liveness monitoring by a downstream watchdog.
+///
+/// A `HeartbeatCommand::Heartbeat` command produces a `HeartbeatEvent::Heartbeat` carrying
+/// a summary of the state, which requires `M::S: Summarize`. A heartbeat applies as
+/// `Change::Updated` without changing the state, so it is logged and passed on
+/// as any other event. It is not passed to `M`.
+///
+/// When replayed, a heartbeat is ignored in effect: it leaves the state unchanged,
+/// so a log containing heartbeats rehydrates the same state as one without.
+///
+/// Heartbeat commands are typically sent on an interval by `edfsm_machine::clock::heartbeat`.
+pub struct Heartbeating<M>(PhantomData<M>);
+
+impl<M> Fsm for Heartbeating<M>
+where
+    M: Fsm,
+    M::S: Summarize,
+{
+    type S = M::S;
+    type C = HeartbeatCommand<M::C>;
+    type E = HeartbeatEvent<M::E, <M::S as Summarize>::Summary>;
+    type SE = M::SE;
+
+    fn for_command(s: &Self::S, c: Self::C, se: &mut Self::SE) -> Option<Self::E> {
+        match c {
+            HeartbeatCommand::Command(c) => M::for_command(s, c, se).map(HeartbeatEvent::Event),
+            HeartbeatCommand::Heartbeat => Some(HeartbeatEvent::Heartbeat(s.summary())),
+        }
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        match e {
+            HeartbeatEvent::Event(e) => M::on_event(s, e),
+            HeartbeatEvent::Heartbeat(_) => Some(Change::Updated),
+        }
+    }
+
+    fn apply(s: &mut Self::S, e: &Self::E) -> Applied {
+        match e {
+            HeartbeatEvent::Event(e) => M::apply(s, e),
+            HeartbeatEvent::Heartbeat(_) => Applied::Updated,
+        }
+    }
+
+    fn on_change(s: &Self::S, e: &Self::E, se: &mut Self::SE, change: Change) {
+        if let HeartbeatEvent::Event(e) = e {
+            M::on_change(s, e, se, change)
+        }
+    }
+
+    delegate!(M, se => se; check_invariants, on_init, on_stop);
+
+    fn follow_up(s: &Self::S, se: &mut Self::SE) -> Option<Self::E> {
+        M::follow_up(s, se).map(HeartbeatEvent::Event)
+    }
+
+    fn step_change(
+        s: &mut Self::S,
+        i: Input<Self::C, Self::E>,
+        se: &mut Self::SE,
+    ) -> Option<(Self::E, Option<Change>)> {
+        let i = match i {
+            Input::Command(HeartbeatCommand::Command(c)) => Input::Command(c),
+            Input::Event(HeartbeatEvent::Event(e)) => Input::Event(e),
+            Input::Command(HeartbeatCommand::Heartbeat) => {
+                let e = HeartbeatEvent::Heartbeat(s.summary());
+                return Some((e, Some(Change::Updated)));
+            }
+            Input::Event(e @ HeartbeatEvent::Heartbeat(_)) => {
+                return Some((e, Some(Change::Updated)));
+            }
+        };
+        let (e, change) = M::step_change(s, i, se)?;
+        Some((HeartbeatEvent::Event(e), change))
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{Change, Fsm, Input};
+use core::marker::PhantomData;
+
+/// The ability to identify repeated deliveries of the same command.
+///
+/// This can be implemented by `Fsm::C` for use with `Idempotent`.
+pub trait IdempotencyKey {
+    /// The type of key identifying a command.
+    type Key: PartialEq;
+
+    /// The key of this command, if any. Commands with the same key are duplicates.
+    fn idempotency_key(&self) -> Option<Self::Key>;
+}
+
+/// An `Fsm` that delegates all behaviour to `M` while dropping duplicate commands,
+/// for exactly-once processing of commands delivered at least once.
+///
+/// The command type must implement `IdempotencyKey`. The keys of the `K` most recently seen
+/// commands that produced an event are retained in the effector, `Deduplicator`. A command
+/// with a retained key is dropped without calling `M::for_command`, and so produces no event
+/// or effect, and its key becomes the most recently seen. A command that produces no event,
+/// such as one that is rejected, is not retained, so a corrected retry with the same key
+/// is processed. Commands without a key are always processed. Events given as input are
+/// not deduplicated.
+///
+/// `K` bounds both memory and the window in which duplicates are detected: the least
+/// recently seen key is forgotten for another, and a duplicate of it is processed again. `K` should therefore cover
+/// the number of commands that may arrive within the redelivery period of the source.
+/// Retained keys are not part of the state, so they are not recovered when the state
+/// is rehydrated. Where duplicates must be detected across restarts, keys should
+/// instead be recorded in the state by events.
+pub struct Idempotent<M, const K: usize>(PhantomData<M>);
+
+/// The effector of an `Idempotent` state machine. This wraps the effector of the
+/// underlying state machine and holds the keys of recent commands.
+pub struct Deduplicator<SE, I, const K: usize> {
+    inner: SE,
+    keys: [Option<I>; K],
+    len: usize,
+}
+
+impl<SE, I, const K: usize> Deduplicator<SE, I, K> {
+    /// Wrap the effector of the underlying state machine.
+    pub fn new(inner: SE) -> Self {
+        Self {
+            inner,
+            keys: core::array::from_fn(|_| None),
+            len: 0,
+        }
+    }
+
+    /// The keys of recent commands, least recently seen first.
+    pub fn keys(&self) -> impl Iterator<Item = &I> {
+        self.keys[..self.len].iter().flatten()
+    }
+
+    /// The key is retained, in which case it becomes the most recently seen.
+    fn seen(&mut self, key: &I) -> bool
+    where
+        I: PartialEq,
+    {
+        let found = self.keys[..self.len]
+            .iter()
+            .position(|k| k.as_ref() == Some(key));
+        if let Some(i) = found {
+            self.keys[i..self.len].rotate_left(1);
+        }
+        found.is_some()
+    }
+
+    /// Retain the key as the most recently seen, forgetting the least recently seen if full.
+    fn retain(&mut self, key: I) {
+        if K == 0 {
+            return;
+        }
+        if self.len == K {
+            self.keys.rotate_left(1);
+            self.len -= 1;
+        }
+        self.keys[self.len] = Some(key);
+        self.len += 1;
+    }
+}
+
+effector!([SE, I, const K: usize] Deduplicator<SE, I, K>);
+
+impl<SE, I, const K: usize> Default for Deduplicator<SE, I, K>
+where
+    SE: Default,
+{
+    fn default() -> Self {
+        Self::new(SE::default())
+    }
+}
+
+impl<M, const K: usize> Fsm for Idempotent<M, K>
+where
+    M: Fsm,
+    M::C: IdempotencyKey,
+{
+    type S = M::S;
+    type C = M::C;
+    type E = M::E;
+    type SE = Deduplicator<M::SE, <M::C as IdempotencyKey>::Key, K>;
+
+    fn for_command(s: &Self::S, c: Self::C, se: &mut Self::SE) -> Option<Self::E> {
+        let key = c.idempotency_key();
+        if key.as_ref().is_some_and(|k| se.seen(k)) {
+            return None;
+        }
+        let e = M::for_command(s, c, &mut se.inner)?;
+        if let Some(key) = key {
+            se.retain(key);
+        }
+        Some(e)
+    }
+
+    delegate!(M, se => &mut se.inner;
+        on_event, apply, on_change, check_invariants, on_init, on_stop, follow_up);
+
+    fn step_change(
+        s: &mut Self::S,
+        i: Input<Self::C, Self::E>,
+        se: &mut Self::SE,
+    ) -> Option<(Self::E, Option<Change>)> {
+        let Input::Command(c) = i else {
+            return M::step_change(s, i, &mut se.inner);
+        };
+        let key = c.idempotency_key();
+        if key.as_ref().is_some_and(|k| se.seen(k)) {
+            return None;
+        }
+        let step = M::step_change(s, Input::Command(c), &mut se.inner)?;
+        if let Some(key) = key {
+            se.retain(key);
+        }
+        Some(step)
+    }
+}
@@ -1,8 +1,53 @@
 #![doc = include_str!("../README.md")]
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub use edfsm_macros::impl_fsm;
 
+#[macro_use]
+mod delegate;
+
+mod async_fsm;
+mod clock;
+mod compound;
+#[cfg(feature = "uuid")]
+mod correlated;
+mod counting;
+mod echo;
+mod guarded;
+mod heartbeat;
+mod idempotent;
+mod middleware;
+mod nested;
+mod rate_limited;
+mod ring;
+mod summary;
+#[cfg(feature = "alloc")]
+mod timers;
+mod traced;
+mod watchdog;
+
+pub use async_fsm::AsyncFsm;
+pub use clock::Clock;
+pub use compound::{Compound, Compounded};
+#[cfg(feature = "uuid")]
+pub use correlated::{Correlated, Correlating, Correlation, Correlator};
+pub use counting::{CommandCount, Counting, Tally};
+pub use echo::{CommandEcho, Echoed};
+pub use guarded::{Guard, Guarded};
+pub use heartbeat::{HeartbeatCommand, HeartbeatEvent, Heartbeating};
+pub use idempotent::{Deduplicator, IdempotencyKey, Idempotent};
+pub use middleware::{Decision, Middleware, WithMiddleware};
+pub use nested::{Lens, Nested};
+pub use rate_limited::{Limiter, RateLimited};
+pub use summary::{StateSummary, Summarize};
+#[cfg(feature = "alloc")]
+pub use timers::Timers;
+pub use traced::{Traced, Tracer, Transition};
+pub use watchdog::{Watch, Watchdog, WatchdogCommand, Watched};
+
 /// A type of input - commands or events.
 #[derive(Debug, Clone)]
 pub enum Input<C, E> {
@@ -17,6 +62,41 @@ pub enum Change {
     Updated,
 }
 
+/// The outcome of applying an event to a state.
+///
+/// This distinguishes an event that does not apply to a state from one that applies
+/// but does not transition, whereas `on_event` conveys the former as `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Applied {
+    /// The event does not apply and the state is unchanged.
+    Ignored,
+    /// The event applied and the state was updated.
+    Updated,
+    /// The event applied and the state transitioned.
+    Transitioned,
+}
+
+impl Applied {
+    /// The change made to the state, if the event applied.
+    pub fn change(self) -> Option<Change> {
+        match self {
+            Applied::Ignored => None,
+            Applied::Updated => Some(Change::Updated),
+            Applied::Transitioned => Some(Change::Transitioned),
+        }
+    }
+}
+
+impl From<Option<Change>> for Applied {
+    fn from(value: Option<Change>) -> Self {
+        match value {
+            None => Applied::Ignored,
+            Some(Change::Updated) => Applied::Updated,
+            Some(Change::Transitioned) => Applied::Transitioned,
+        }
+    }
+}
+
 /// Runs the state machine for a command or event, optionally performing effects,
 /// events, or receive events. These types of FSM can be broadly described as "Mealy" and "Moore" machines
 /// respectively. Along the way, effects can be performed given the receipt of a command or the application
@@ -36,6 +116,12 @@ pub trait Fsm {
     /// The state managed by the FSM
     type S;
     /// The command(s) that are able to be processed by the FSM
+    ///
+    /// Commands are taken by value. To avoid copying a large payload into a command,
+    /// give the implementing type a lifetime and borrow the payload, say with
+    /// `type C = Cow<'a, [u8]>` for `impl<'a> Fsm for Parser<'a>`. `step` may then be
+    /// called with borrowed data, while a `Machine`, which holds commands in a channel,
+    /// uses `Parser<'static>` with owned data.
     type C;
     /// The event emitted having performed a command
     type E;
@@ -53,35 +139,192 @@ pub trait Fsm {
     /// Returns some enumeration of the `Change` type if there is a state transition.
     fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change>;
 
+    /// Given a state and event, modify state and return the outcome.
+    /// This is `on_event` with the outcome made explicit, and it is what `step`
+    /// uses to determine whether an event is to be emitted.
+    fn apply(s: &mut Self::S, e: &Self::E) -> Applied {
+        Self::on_event(s, e).into()
+    }
+
     /// Given a state and event having been applied then handle any potential change
     /// and optionally perform side effects.
     /// This function is generally only called from the `step` function.
     fn on_change(s: &Self::S, e: &Self::E, se: &mut Self::SE, change: Change);
 
+    /// Check that the state is consistent, returning a description of the violation if not.
+    /// With the `debug-invariants` feature, `step` calls this after each state change
+    /// and panics on violation. Otherwise it is not called. The default is always `Ok`.
+    fn check_invariants(_s: &Self::S) -> Result<(), &'static str> {
+        Ok(())
+    }
+
+    /// Perform any initial effects given the starting state, for example to start hardware
+    /// if the rehydrated state is running. A state machine driver calls this once, after
+    /// the state is recovered and the effector is set up with `Init`, and before any input
+    /// is stepped. The default does nothing.
+    fn on_init(_s: &Self::S, _se: &mut Self::SE) {}
+
+    /// Perform any final effects once no more input is to be stepped, for example
+    /// to flush buffered effects or record a snapshot. A state machine driver calls this
+    /// at the end of its life, after its input is exhausted or a terminating event.
+    /// The default does nothing.
+    fn on_stop(_s: &Self::S, _se: &mut Self::SE) {}
+
+    /// Given a state that has just been transitioned to, optionally produce an event to be
+    /// applied immediately, for example the outcome of an attempt made on entry to the state.
+    /// This is called by `step_all` after each transition, and so a follow-up event may
+    /// itself lead to a transition and a further follow-up. The default produces none.
+    fn follow_up(_s: &Self::S, _se: &mut Self::SE) -> Option<Self::E> {
+        None
+    }
+
     /// This is the common entry point to the event driven FSM.
     /// Runs the state machine for a command input, optionally performing effects,
     /// possibly producing an event and possibly transitioning to a new state. Also
     /// applies any "Entry/" processing when arriving at a new state, and a change
     /// handler if there is a state change.
     fn step(s: &mut Self::S, i: Input<Self::C, Self::E>, se: &mut Self::SE) -> Option<Self::E> {
+        Self::step_change(s, i, se).map(|(e, _)| e)
+    }
+
+    /// Runs the state machine for an input as `step` does, also returning the change
+    /// made to the state. As for `step`, None is returned when no event is produced or
+    /// the event is ignored. A wrapper may still return an event that made no change, as
+    /// `CommandEcho` does for a command, and then the change is None.
+    ///
+    /// Implementations that customise stepping override this, rather than `step`, so
+    /// that `step_all` observes the customisation. A state machine that wraps another,
+    /// `M`, overrides this to step `M` with `M::step_change`, so that any customisation
+    /// of `M` is kept when the two are composed.
+    fn step_change(
+        s: &mut Self::S,
+        i: Input<Self::C, Self::E>,
+        se: &mut Self::SE,
+    ) -> Option<(Self::E, Option<Change>)> {
         let e = match i {
             Input::Command(c) => Self::for_command(s, c, se),
             Input::Event(e) => Some(e),
-        };
-        if let Some(e) = e {
-            let r = Self::on_event(s, &e);
-            if let Some(c) = r {
-                Self::on_change(s, &e, se, c);
-                Some(e)
-            } else {
-                None
+        }?;
+        let c = Self::apply(s, &e).change()?;
+        assert_invariants::<Self>(s);
+        Self::on_change(s, &e, se, c);
+        Some((e, Some(c)))
+    }
+
+    /// Runs the state machine for an input as `step` does, and then applies any follow-up
+    /// events (see `follow_up`), returning all of the events produced in order.
+    ///
+    /// Each follow-up event is stepped in turn, with its own effects. To guard against
+    /// an endless cascade, such as between two states that each follow up with an event
+    /// transitioning to the other, at most `MAX_CASCADE` follow-up events are applied
+    /// for an input. Any further follow-up is not requested.
+    fn step_all(
+        s: &mut Self::S,
+        i: Input<Self::C, Self::E>,
+        se: &mut Self::SE,
+    ) -> Cascade<Self::E> {
+        let mut cascade = Cascade::default();
+        let mut next = Some(i);
+        while let Some(i) = next.take() {
+            if let Some((e, c)) = Self::step_change(s, i, se) {
+                if matches!(c, Some(Change::Transitioned)) && cascade.len < MAX_CASCADE {
+                    next = Self::follow_up(s, se).map(Input::Event);
+                }
+                cascade.push(e);
             }
-        } else {
-            None
         }
+        cascade
     }
 }
 
+/// The maximum number of follow-up events that `Fsm::step_all` applies for an input.
+pub const MAX_CASCADE: usize = 8;
+
+/// The events produced by `Fsm::step_all`, in order. The first is for the input
+/// and the remainder are follow-up events.
+pub struct Cascade<E> {
+    events: [Option<E>; MAX_CASCADE + 1],
+    len: usize,
+    next: usize,
+}
+
+impl<E> Cascade<E> {
+    fn push(&mut self, e: E) {
+        self.events[self.len] = Some(e);
+        self.len += 1;
+    }
+
+    /// The number of events produced.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// No events were produced.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The events not yet taken by iterating, in order.
+    pub fn events(&self) -> impl Iterator<Item = &E> {
+        self.events[self.next.min(self.len)..self.len]
+            .iter()
+            .flatten()
+    }
+}
+
+impl<E> Default for Cascade<E> {
+    fn default() -> Self {
+        Self {
+            events: core::array::from_fn(|_| None),
+            len: 0,
+            next: 0,
+        }
+    }
+}
+
+impl<E> Iterator for Cascade<E> {
+    type Item = E;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let e = self.events.get_mut(self.next)?.take();
+        self.next += 1;
+        e
+    }
+}
+
+/// Panic if the state violates the invariants of the state machine,
+/// given the `debug-invariants` feature.
+#[inline]
+pub(crate) fn assert_invariants<M>(s: &M::S)
+where
+    M: Fsm + ?Sized,
+{
+    #[cfg(feature = "debug-invariants")]
+    if let Err(violation) = M::check_invariants(s) {
+        panic!("state invariant violated: {violation}");
+    }
+    #[cfg(not(feature = "debug-invariants"))]
+    let _ = s;
+}
+
+/// Apply a sequence of events to a default state and return the resulting state.
+///
+/// This is the projection of an event log onto a state, as when a machine is rehydrated.
+/// Each event is applied with `Fsm::apply`, so no effects are performed and events that
+/// are ignored leave the state unchanged. It is useful in unit and property tests.
+pub fn replay<'a, M>(events: impl IntoIterator<Item = &'a M::E>) -> M::S
+where
+    M: Fsm + ?Sized,
+    M::S: Default,
+    M::E: 'a,
+{
+    let mut s = M::S::default();
+    for e in events {
+        M::apply(&mut s, e);
+    }
+    s
+}
+
 // The following traits can be used with `Fsm` but are optional.
 
 /// The ability to perform initial effects given a starting state.
@@ -106,11 +349,26 @@ pub trait Terminating {
     fn terminating(&self) -> bool;
 }
 
+/// A key for compaction of an event log, which retains the latest record for each key.
+///
+/// This can be implemented by `Fsm::E` so that events are logged with a key,
+/// for example by `edfsm_machine::adapter::CompactedLog`. As only the latest event for
+/// a key survives compaction, that event alone must recover the state it applies to.
+pub trait CompactionKey {
+    /// The compaction key of this event.
+    fn compaction_key(&self) -> u64;
+}
+
 /// The ability to extract output messages from a state machine.
 ///
 /// This trait can be implement for `Fsm::SE`. A state machine driver
 /// that requires this will call `drain_all` after each `step` and
 /// forward the returned output messages.
+///
+/// The driver forwards each message as the iterator yields it, without collecting
+/// them first. An effector with large outputs can therefore generate messages lazily
+/// in its iterator, rather than accumulate them, and each is produced only once the
+/// previous message has been forwarded.
 pub trait Drain {
     /// Messages generated during a state machine `step`.
     type Item;
@@ -119,6 +377,15 @@ pub trait Drain {
     fn drain_all(&mut self) -> impl Iterator<Item = Self::Item> + Send;
 }
 
+/// A name for each variant of a type, typically the states or events of a state machine.
+///
+/// This can be implemented by `Fsm::S`, `Fsm::C` or `Fsm::E` for the purposes
+/// of diagnostics such as those recorded by `Traced`.
+pub trait Named {
+    /// The name of this variant.
+    fn name(&self) -> &'static str;
+}
+
 #[cfg(test)]
 mod test {
     use super::{Change, Fsm, Input};
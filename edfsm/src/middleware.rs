@@ -0,0 +1,81 @@
+use crate::{Change, Fsm, Input};
+use core::marker::PhantomData;
+
+/// The decision of a middleware function given a state and command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision<C> {
+    /// Pass the command on to the state machine.
+    Allow,
+    /// Discard the command.
+    Reject,
+    /// Pass this command on to the state machine instead.
+    Rewrite(C),
+}
+
+/// An `Fsm` that passes each command through a middleware function before
+/// delegating to `M`, for policies such as authorisation or feature flags
+/// that are independent of `M`.
+///
+/// The middleware function is held by the effector, `Middleware`, along with
+/// the effector of `M`. The function may retain context between commands.
+/// Events are not subject to the middleware.
+pub struct WithMiddleware<M, F>(PhantomData<(M, F)>);
+
+/// The effector of a `WithMiddleware` state machine.
+pub struct Middleware<F, SE> {
+    func: F,
+    inner: SE,
+}
+
+impl<F, SE> Middleware<F, SE> {
+    /// Combine a middleware function with the effector of the underlying state machine.
+    pub fn new(func: F, inner: SE) -> Self {
+        Self { func, inner }
+    }
+
+    /// Pass a command through the middleware function, returning the command
+    /// for the underlying state machine, if any.
+    fn decide<S, C>(&mut self, s: &S, c: C) -> Option<C>
+    where
+        F: FnMut(&S, &C) -> Decision<C>,
+    {
+        match (self.func)(s, &c) {
+            Decision::Allow => Some(c),
+            Decision::Reject => None,
+            Decision::Rewrite(c) => Some(c),
+        }
+    }
+}
+
+effector!([F, SE] Middleware<F, SE>);
+
+impl<M, F> Fsm for WithMiddleware<M, F>
+where
+    M: Fsm,
+    F: FnMut(&M::S, &M::C) -> Decision<M::C>,
+{
+    type S = M::S;
+    type C = M::C;
+    type E = M::E;
+    type SE = Middleware<F, M::SE>;
+
+    fn for_command(s: &Self::S, c: Self::C, se: &mut Self::SE) -> Option<Self::E> {
+        let c = se.decide(s, c)?;
+        M::for_command(s, c, &mut se.inner)
+    }
+
+    delegate!(M, se => &mut se.inner;
+        on_event, apply, on_change, check_invariants, on_init, on_stop, follow_up);
+
+    fn step_change(
+        s: &mut Self::S,
+        i: Input<Self::C, Self::E>,
+        se: &mut Self::SE,
+    ) -> Option<(Self::E, Option<Change>)> {
+        let i = match i {
+            Input::Command(c) => Input::Command(se.decide(s, c)?),
+            i => i,
+        };
+        M::step_change(s, i, &mut se.inner)
+    }
+}
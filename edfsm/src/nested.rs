@@ -0,0 +1,116 @@
+use crate::{Applied, Change, Fsm, Input};
+use core::marker::PhantomData;
+
+/// Focuses the state, commands and events of a parent state machine on those of a child `M`,
+/// whose state is a part of the parent's state and whose commands and events are variants
+/// of the parent's.
+pub trait Lens<M>
+where
+    M: Fsm,
+{
+    /// The state of the parent.
+    type S;
+    /// The commands of the parent.
+    type C;
+    /// The events of the parent.
+    type E;
+
+    /// The state of the child within the parent's state.
+    fn get(s: &Self::S) -> &M::S;
+
+    /// The state of the child within the parent's state, mutably.
+    fn get_mut(s: &mut Self::S) -> &mut M::S;
+
+    /// The command of the child, or None if the command is not for the child.
+    fn command(c: Self::C) -> Option<M::C>;
+
+    /// The event of the child, or None if the event is not for the child.
+    fn event(e: &Self::E) -> Option<&M::E>;
+
+    /// The owned event of the child, or None if the event is not for the child.
+    fn into_event(e: Self::E) -> Option<M::E>;
+
+    /// The event of the parent wrapping an event of the child.
+    fn wrap(e: M::E) -> Self::E;
+}
+
+/// An `Fsm` that runs the state machine `M` on a part of a larger state, as given by
+/// the `Lens`, so that state machines can be composed without flattening them into one.
+///
+/// Commands and events for the child are unwrapped and delegated to `M`, and the events that
+/// it produces are wrapped, with stepping delegated to `M` so that any wrapper within `M` keeps
+/// its behaviour. Other commands and events are ignored. The effector is that of
+/// the child. A parent state machine typically handles its own commands and events and
+/// delegates the rest to this, in the same way for each of its children:
+///
+/// ```rust,ignore
+/// fn for_command(s: &Plant, c: PlantCommand, se: &mut Effects) -> Option<PlantEvent> {
+///     match c {
+///         PlantCommand::SetTarget(t) => Some(PlantEvent::TargetSet(t)),
+///         c => Nested::<Switch, SwitchLens>::for_command(s, c, se),
+///     }
+/// }
+/// ```
+pub struct Nested<M, L>(PhantomData<(M, L)>);
+
+impl<M, L> Fsm for Nested<M, L>
+where
+    M: Fsm,
+    L: Lens<M>,
+{
+    type S = L::S;
+    type C = L::C;
+    type E = L::E;
+    type SE = M::SE;
+
+    fn for_command(s: &Self::S, c: Self::C, se: &mut Self::SE) -> Option<Self::E> {
+        let c = L::command(c)?;
+        M::for_command(L::get(s), c, se).map(L::wrap)
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        M::on_event(L::get_mut(s), L::event(e)?)
+    }
+
+    fn apply(s: &mut Self::S, e: &Self::E) -> Applied {
+        match L::event(e) {
+            Some(e) => M::apply(L::get_mut(s), e),
+            None => Applied::Ignored,
+        }
+    }
+
+    fn on_change(s: &Self::S, e: &Self::E, se: &mut Self::SE, change: Change) {
+        if let Some(e) = L::event(e) {
+            M::on_change(L::get(s), e, se, change)
+        }
+    }
+
+    fn check_invariants(s: &Self::S) -> Result<(), &'static str> {
+        M::check_invariants(L::get(s))
+    }
+
+    fn on_init(s: &Self::S, se: &mut Self::SE) {
+        M::on_init(L::get(s), se)
+    }
+
+    fn on_stop(s: &Self::S, se: &mut Self::SE) {
+        M::on_stop(L::get(s), se)
+    }
+
+    fn follow_up(s: &Self::S, se: &mut Self::SE) -> Option<Self::E> {
+        M::follow_up(L::get(s), se).map(L::wrap)
+    }
+
+    fn step_change(
+        s: &mut Self::S,
+        i: Input<Self::C, Self::E>,
+        se: &mut Self::SE,
+    ) -> Option<(Self::E, Option<Change>)> {
+        let i = match i {
+            Input::Command(c) => Input::Command(L::command(c)?),
+            Input::Event(e) => Input::Event(L::into_event(e)?),
+        };
+        let (e, change) = M::step_change(L::get_mut(s), i, se)?;
+        Some((L::wrap(e), change))
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{Applied, Change, Clock, Fsm, Input};
+use core::{marker::PhantomData, time::Duration};
+
+/// An `Fsm` that delegates all behaviour to `M` but transitions no more often than a
+/// minimum interval, for example to protect a physical actuator from rapid switching.
+///
+/// Within the interval following a transition, an event that would transition the state
+/// is suppressed: it is not applied and `step` returns `None`. Suppressed transitions are
+/// dropped, not queued. Events that only update the state are applied as usual. This acts
+/// on transitions regardless of the source of the input.
+///
+/// Whether a command transitions is known only from its event, and so within the interval
+/// a command is still handled by `M::for_command`. Any effects that it performs take place
+/// even when its event is then suppressed. The event is stepped as an event, so within the
+/// interval any customisation of how `M` steps a command does not apply.
+///
+/// The effector, `Limiter`, holds the `Clock` and the time that the interval ends. To find
+/// whether an event would transition, it is applied to a copy of the state, so the state
+/// type must implement `Clone`. The copy is made only within the interval. Otherwise `M`
+/// is stepped with `M::step_change`, so any wrapper within `M` keeps its behaviour. Events
+/// replayed from a log are applied without limit, as they record transitions that were
+/// allowed.
+pub struct RateLimited<M, K>(PhantomData<(M, K)>);
+
+/// The effector of a `RateLimited` state machine.
+pub struct Limiter<SE, K>
+where
+    K: Clock,
+{
+    inner: SE,
+    clock: K,
+    interval: Duration,
+    until: Option<K::Instant>,
+}
+
+impl<SE, K> Limiter<SE, K>
+where
+    K: Clock,
+{
+    /// Wrap the effector of the underlying state machine, allowing a transition
+    /// at most once per interval as measured by the clock.
+    pub fn new(inner: SE, clock: K, interval: Duration) -> Self {
+        Self {
+            inner,
+            clock,
+            interval,
+            until: None,
+        }
+    }
+
+    /// Transitions are currently suppressed.
+    pub fn is_limited(&self) -> bool {
+        self.until.is_some_and(|until| self.clock.now() < until)
+    }
+}
+
+effector!([SE, K] Limiter<SE, K> where K: Clock);
+
+impl<M, K> Fsm for RateLimited<M, K>
+where
+    M: Fsm,
+    M::S: Clone,
+    K: Clock,
+{
+    type S = M::S;
+    type C = M::C;
+    type E = M::E;
+    type SE = Limiter<M::SE, K>;
+
+    delegate!(M, se => &mut se.inner; for_command, on_event, apply);
+
+    fn on_change(s: &Self::S, e: &Self::E, se: &mut Self::SE, change: Change) {
+        if let Change::Transitioned = change {
+            se.until = Some(se.clock.deadline(se.interval));
+        }
+        M::on_change(s, e, &mut se.inner, change)
+    }
+
+    delegate!(M, se => &mut se.inner; check_invariants, on_init, on_stop, follow_up);
+
+    fn step_change(
+        s: &mut Self::S,
+        i: Input<Self::C, Self::E>,
+        se: &mut Self::SE,
+    ) -> Option<(Self::E, Option<Change>)> {
+        if !se.is_limited() {
+            let (e, change) = M::step_change(s, i, &mut se.inner)?;
+            if let Some(Change::Transitioned) = change {
+                se.until = Some(se.clock.deadline(se.interval));
+            }
+            return Some((e, change));
+        }
+
+        // Within the interval, only an event that would just update the state is stepped.
+        // A command has already performed its effects by the time its event is known.
+        let e = match i {
+            Input::Command(c) => M::for_command(s, c, &mut se.inner)?,
+            Input::Event(e) => e,
+        };
+        let mut trial = s.clone();
+        match M::apply(&mut trial, &e) {
+            Applied::Updated => M::step_change(s, Input::Event(e), &mut se.inner),
+            Applied::Transitioned | Applied::Ignored => None,
+        }
+    }
+}
@@ -0,0 +1,33 @@
+/// A fixed capacity ring buffer. Once full, pushing an item overwrites the oldest.
+#[derive(Debug, Clone)]
+pub(crate) struct Ring<T, const K: usize> {
+    items: [Option<T>; K],
+    next: usize,
+}
+
+impl<T, const K: usize> Default for Ring<T, K> {
+    fn default() -> Self {
+        Self {
+            items: core::array::from_fn(|_| None),
+            next: 0,
+        }
+    }
+}
+
+impl<T, const K: usize> Ring<T, K> {
+    /// Append an item, returning the oldest item if it was overwritten.
+    pub(crate) fn push(&mut self, item: T) -> Option<T> {
+        if K == 0 {
+            return Some(item);
+        }
+        let displaced = self.items[self.next].replace(item);
+        self.next = (self.next + 1) % K;
+        displaced
+    }
+
+    /// Iterate over the retained items, oldest first.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &T> {
+        let (newer, older) = self.items.split_at(self.next);
+        older.iter().chain(newer.iter()).flatten()
+    }
+}
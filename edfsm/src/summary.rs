@@ -0,0 +1,47 @@
+/// A summary of a state, such as is carried by a heartbeat.
+///
+/// This can be implemented by `Fsm::S` for use with `Heartbeating`, or to publish a
+/// summary of the state with `edfsm_machine::Machine::publish_summary`.
+pub trait Summarize {
+    /// The summary of a state.
+    type Summary;
+
+    /// Summarize this state.
+    fn summary(&self) -> Self::Summary;
+}
+
+/// A compact description of a state, for observability such as a health endpoint,
+/// without exposing the state type.
+///
+/// The name of the state's variant can be generated with `#[impl_fsm(state_name)]`,
+/// and the fields are those selected from the state. With the `serde` feature,
+/// this is serializable:
+///
+/// ```rust,ignore
+/// impl Summarize for State {
+///     type Summary = StateSummary<Option<u32>>;
+///
+///     fn summary(&self) -> Self::Summary {
+///         let fields = match self {
+///             State::Connected(s) => Some(s.session),
+///             _ => None,
+///         };
+///         StateSummary::new(MyFsm::state_name(self), fields)
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StateSummary<F> {
+    /// The name of the state.
+    pub state: &'static str,
+    /// The selected fields of the state.
+    pub fields: F,
+}
+
+impl<F> StateSummary<F> {
+    /// Summarize a state by its name and selected fields.
+    pub fn new(state: &'static str, fields: F) -> Self {
+        Self { state, fields }
+    }
+}
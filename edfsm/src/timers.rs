@@ -0,0 +1,91 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::time::Duration;
+
+/// Deadlines for a set of timers, each identified by an `Id`, independent of any runtime.
+///
+/// A driver that does not use `edfsm_machine`, such as on an embedded target, sets timers
+/// as its state machine requires and polls for those that have `expired`, mapping each
+/// to an event or command of the state machine. The instant type `I` is any ordered time,
+/// such as `Clock::Instant`, and defaults to a `Duration` since some epoch, such as boot.
+///
+/// Each id has at most one deadline, so setting a timer again replaces its deadline.
+/// Timers are kept in deadline order as well as by id, costing two entries per timer.
+#[derive(Debug, Clone)]
+pub struct Timers<Id, I = Duration> {
+    by_id: BTreeMap<Id, I>,
+    by_deadline: BTreeSet<(I, Id)>,
+}
+
+impl<Id, I> Default for Timers<Id, I> {
+    fn default() -> Self {
+        Self {
+            by_id: BTreeMap::new(),
+            by_deadline: BTreeSet::new(),
+        }
+    }
+}
+
+impl<Id, I> Timers<Id, I>
+where
+    Id: Ord + Clone,
+    I: Ord + Copy,
+{
+    /// Create an empty set of timers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the timer with the given id to expire at the given time, returning its
+    /// previous deadline if it was already set.
+    pub fn set(&mut self, id: Id, at: I) -> Option<I> {
+        let previous = self.by_id.insert(id.clone(), at);
+        if let Some(p) = previous {
+            self.by_deadline.remove(&(p, id.clone()));
+        }
+        self.by_deadline.insert((at, id));
+        previous
+    }
+
+    /// Cancel the timer with the given id, returning its deadline if it was set.
+    pub fn cancel(&mut self, id: &Id) -> Option<I> {
+        let at = self.by_id.remove(id)?;
+        self.by_deadline.remove(&(at, id.clone()));
+        Some(at)
+    }
+
+    /// The deadline of the timer with the given id, or None if it is not set.
+    pub fn deadline(&self, id: &Id) -> Option<I> {
+        self.by_id.get(id).copied()
+    }
+
+    /// The earliest deadline of the timers, or None if none are set. A driver
+    /// may sleep until this before polling again.
+    pub fn next_deadline(&self) -> Option<I> {
+        self.by_deadline.first().map(|(at, _)| *at)
+    }
+
+    /// Remove the timers that have expired by the given time, that is, whose deadline
+    /// is not after it, returning their ids in deadline order.
+    ///
+    /// Timers are removed as the iterator is advanced, so those not taken from it remain set.
+    pub fn expired(&mut self, now: I) -> impl Iterator<Item = Id> + '_ {
+        core::iter::from_fn(move || {
+            if self.next_deadline()? > now {
+                return None;
+            }
+            let (_, id) = self.by_deadline.pop_first()?;
+            self.by_id.remove(&id);
+            Some(id)
+        })
+    }
+
+    /// The number of timers set.
+    pub fn len(&self) -> usize {
+        self.by_id.len()
+    }
+
+    /// No timers are set.
+    pub fn is_empty(&self) -> bool {
+        self.by_id.is_empty()
+    }
+}
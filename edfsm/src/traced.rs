@@ -0,0 +1,89 @@
+use crate::{ring::Ring, Change, Fsm, Input, Named};
+use core::marker::PhantomData;
+
+/// A state transition recorded by `Traced`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transition {
+    /// The name of the state transitioned from.
+    pub from: &'static str,
+    /// The name of the event causing the transition.
+    pub input: &'static str,
+    /// The name of the state transitioned to.
+    pub to: &'static str,
+}
+
+/// An `Fsm` that delegates all behaviour to `M` while retaining the last `K`
+/// transitions in its effector, for inspection when diagnosing a failure.
+///
+/// The state and event types must implement `Named`. Transitions are recorded as `M` is
+/// stepped, given the name of the state that the step started from, so that any wrapper
+/// within `M` keeps its behaviour. Only `step_change` records: a container that applies
+/// events and calls `on_change` itself, such as a `KvStore`, records no transitions.
+pub struct Traced<M, const K: usize>(PhantomData<M>);
+
+/// The effector of a `Traced` state machine. This wraps the effector of the underlying
+/// state machine and holds the history of transitions.
+pub struct Tracer<SE, const K: usize> {
+    inner: SE,
+    from: &'static str,
+    history: Ring<Transition, K>,
+}
+
+impl<SE, const K: usize> Tracer<SE, K> {
+    /// Wrap the effector of the underlying state machine.
+    pub fn new(inner: SE) -> Self {
+        Self {
+            inner,
+            from: "",
+            history: Ring::default(),
+        }
+    }
+
+    /// The recent transitions, oldest first.
+    pub fn history(&self) -> impl Iterator<Item = &Transition> {
+        self.history.iter()
+    }
+}
+
+effector!([SE, const K: usize] Tracer<SE, K>);
+
+impl<SE, const K: usize> Default for Tracer<SE, K>
+where
+    SE: Default,
+{
+    fn default() -> Self {
+        Self::new(SE::default())
+    }
+}
+
+impl<M, const K: usize> Fsm for Traced<M, K>
+where
+    M: Fsm,
+    M::S: Named,
+    M::E: Named,
+{
+    type S = M::S;
+    type C = M::C;
+    type E = M::E;
+    type SE = Tracer<M::SE, K>;
+
+    delegate!(M, se => &mut se.inner;
+        for_command, on_event, apply, on_change, check_invariants, on_init, on_stop, follow_up);
+
+    fn step_change(
+        s: &mut Self::S,
+        i: Input<Self::C, Self::E>,
+        se: &mut Self::SE,
+    ) -> Option<(Self::E, Option<Change>)> {
+        se.from = s.name();
+        let (e, change) = M::step_change(s, i, &mut se.inner)?;
+        if let Some(Change::Transitioned) = change {
+            se.history.push(Transition {
+                from: se.from,
+                input: e.name(),
+                to: s.name(),
+            });
+        }
+        Some((e, change))
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{Change, Clock, Fsm, Input};
+use core::{marker::PhantomData, time::Duration};
+
+/// A state machine that can be failed by a `Watchdog`.
+pub trait Watched: Fsm {
+    /// The event to apply when no input has arrived within the timeout, for example
+    /// to mark a device offline. None if there is no such event for the state, for
+    /// example when the device is already offline.
+    fn on_timeout(s: &Self::S) -> Option<Self::E>;
+}
+
+/// A command of a `Watchdog` state machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchdogCommand<C> {
+    /// A command for the underlying state machine.
+    Command(C),
+    /// Check whether the timeout has passed.
+    Check,
+}
+
+/// An `Fsm` that delegates all behaviour to `M` and acts as a dead-man's switch,
+/// failing `M` when no input arrives within a timeout.
+///
+/// Any input other than `WatchdogCommand::Check` restarts the timeout. A check after the
+/// timeout has passed produces the event given by `Watched::on_timeout`, which is stepped
+/// as any other event of `M`, and restarts the timeout. So while no input arrives, this is
+/// repeated once per timeout until `on_timeout` returns None. A check within the timeout
+/// produces no event.
+///
+/// The effector, `Watch`, holds the `Clock` and the time that the timeout ends, which
+/// begins at `on_init`. Events are those of `M`, so a timeout is logged only when it
+/// produces an event, and replaying the log rehydrates the state as for `M`. Replayed
+/// events do not restart the timeout.
+///
+/// Check commands are typically sent on an interval by `edfsm_machine::clock::watchdog`.
+pub struct Watchdog<M, K>(PhantomData<(M, K)>);
+
+/// The effector of a `Watchdog` state machine.
+pub struct Watch<SE, K>
+where
+    K: Clock,
+{
+    inner: SE,
+    clock: K,
+    timeout: Duration,
+    until: Option<K::Instant>,
+}
+
+impl<SE, K> Watch<SE, K>
+where
+    K: Clock,
+{
+    /// Wrap the effector of the underlying state machine, failing it when no input
+    /// arrives within the timeout as measured by the clock.
+    pub fn new(inner: SE, clock: K, timeout: Duration) -> Self {
+        Self {
+            inner,
+            clock,
+            timeout,
+            until: None,
+        }
+    }
+
+    /// The timeout has passed.
+    pub fn is_expired(&self) -> bool {
+        self.until.is_some_and(|until| self.clock.now() >= until)
+    }
+
+    fn restart(&mut self) {
+        self.until = Some(self.clock.deadline(self.timeout));
+    }
+}
+
+effector!([SE, K] Watch<SE, K> where K: Clock);
+
+impl<M, K> Fsm for Watchdog<M, K>
+where
+    M: Watched,
+    K: Clock,
+{
+    type S = M::S;
+    type C = WatchdogCommand<M::C>;
+    type E = M::E;
+    type SE = Watch<M::SE, K>;
+
+    fn for_command(s: &Self::S, c: Self::C, se: &mut Self::SE) -> Option<Self::E> {
+        match c {
+            WatchdogCommand::Command(c) => M::for_command(s, c, &mut se.inner),
+            WatchdogCommand::Check if se.until.is_none() => {
+                se.restart();
+                None
+            }
+            WatchdogCommand::Check if se.is_expired() => {
+                se.restart();
+                M::on_timeout(s)
+            }
+            WatchdogCommand::Check => None,
+        }
+    }
+
+    delegate!(M, se => &mut se.inner; on_event, apply, on_change, check_invariants);
+
+    fn on_init(s: &Self::S, se: &mut Self::SE) {
+        se.restart();
+        M::on_init(s, &mut se.inner)
+    }
+
+    delegate!(M, se => &mut se.inner; on_stop, follow_up);
+
+    fn step_change(
+        s: &mut Self::S,
+        i: Input<Self::C, Self::E>,
+        se: &mut Self::SE,
+    ) -> Option<(Self::E, Option<Change>)> {
+        let i = match i {
+            Input::Command(WatchdogCommand::Check) => {
+                Input::Event(Self::for_command(s, WatchdogCommand::Check, se)?)
+            }
+            Input::Command(WatchdogCommand::Command(c)) => {
+                se.restart();
+                Input::Command(c)
+            }
+            Input::Event(e) => {
+                se.restart();
+                Input::Event(e)
+            }
+        };
+        M::step_change(s, i, &mut se.inner)
+    }
+}
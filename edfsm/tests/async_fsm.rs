@@ -0,0 +1,154 @@
+// Declare our state, commands and events
+
+use core::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+use edfsm::{impl_fsm, AsyncFsm, Input};
+
+struct Idle;
+struct Running;
+enum State {
+    Idle(Idle),
+    Running(Running),
+}
+
+struct Start;
+struct Stop;
+enum Command {
+    Start(Start),
+    Stop(Stop),
+}
+
+struct Started;
+struct Stopped;
+enum Event {
+    Started(Started),
+    Stopped(Stopped),
+}
+
+// An effect handler whose effects must be awaited
+
+#[derive(Default)]
+struct EffectHandlers {
+    log: Vec<&'static str>,
+}
+
+impl EffectHandlers {
+    async fn write(&mut self, line: &'static str) {
+        Yield(false).await;
+        self.log.push(line);
+    }
+}
+
+/// A future that is pending once before it is ready.
+struct Yield(bool);
+
+impl Future for Yield {
+    type Output = ();
+
+    fn poll(mut self: core::pin::Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            Poll::Pending
+        }
+    }
+}
+
+fn block_on<F: Future>(f: F) -> F::Output {
+    let mut f = pin!(f);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = f.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+// Declare the FSM itself
+
+struct MyFsm;
+
+#[impl_fsm(async, accepts)]
+impl AsyncFsm for MyFsm {
+    type S = State;
+    type C = Command;
+    type E = Event;
+    type SE = EffectHandlers;
+
+    state!(Running / entry);
+
+    command!(Idle    => Start => Started => Running);
+    command!(Running => Stop  => Stopped => Idle);
+
+    ignore_command!(Idle    => Stop);
+    ignore_command!(Running => Start);
+
+    event!(Running => Stopped => Idle / action);
+}
+
+impl MyFsm {
+    async fn on_entry_running(_s: &Running, se: &mut EffectHandlers) {
+        se.write("entered running").await;
+    }
+
+    async fn for_idle_start(_s: &Idle, _c: Start, se: &mut EffectHandlers) -> Option<Started> {
+        se.write("starting").await;
+        Some(Started)
+    }
+
+    async fn for_running_stop(_s: &Running, _c: Stop, _se: &mut EffectHandlers) -> Option<Stopped> {
+        Some(Stopped)
+    }
+
+    fn on_idle_started(_s: &Idle, _e: &Started) -> Option<Running> {
+        Some(Running)
+    }
+
+    fn on_running_stopped(_s: &Running, _e: &Stopped) -> Option<Idle> {
+        Some(Idle)
+    }
+
+    async fn on_change_idle_stopped(_s: &Idle, _e: &Stopped, se: &mut EffectHandlers) {
+        se.write("stopped").await;
+    }
+}
+
+#[test]
+fn async_fsm() {
+    let mut se = EffectHandlers::default();
+    let mut s = State::Idle(Idle);
+
+    let e = block_on(MyFsm::step(
+        &mut s,
+        Input::Command(Command::Start(Start)),
+        &mut se,
+    ));
+    assert!(matches!(e, Some(Event::Started(Started))));
+    assert!(matches!(s, State::Running(_)));
+
+    let e = block_on(MyFsm::step(
+        &mut s,
+        Input::Command(Command::Start(Start)),
+        &mut se,
+    ));
+    assert!(e.is_none());
+
+    let e = block_on(MyFsm::step(
+        &mut s,
+        Input::Command(Command::Stop(Stop)),
+        &mut se,
+    ));
+    assert!(matches!(e, Some(Event::Stopped(Stopped))));
+    assert!(matches!(s, State::Idle(_)));
+
+    // Effects were awaited in order
+    assert_eq!(se.log, ["starting", "entered running", "stopped"]);
+
+    // Events are applied synchronously, without effects
+    assert!(MyFsm::on_event(&mut s, &Event::Started(Started)).is_some());
+    assert!(MyFsm::accepts(&s, &Command::Stop(Stop)));
+}
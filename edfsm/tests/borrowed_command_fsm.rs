@@ -0,0 +1,66 @@
+use edfsm::{Change, Fsm, Input};
+use std::{borrow::Cow, marker::PhantomData};
+
+/// Counts the lines of documents, which are borrowed by commands where possible.
+struct Lines<'a>(PhantomData<&'a ()>);
+
+#[derive(Debug, Default)]
+struct State {
+    lines: usize,
+}
+
+enum Command<'a> {
+    Count(Cow<'a, str>),
+}
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Counted(usize),
+}
+
+/// Records whether each document was borrowed.
+#[derive(Default)]
+struct Effects {
+    borrowed: Vec<bool>,
+}
+
+impl<'a> Fsm for Lines<'a> {
+    type S = State;
+    type C = Command<'a>;
+    type E = Event;
+    type SE = Effects;
+
+    fn for_command(_s: &Self::S, c: Self::C, se: &mut Self::SE) -> Option<Self::E> {
+        let Command::Count(doc) = c;
+        se.borrowed.push(matches!(doc, Cow::Borrowed(_)));
+        Some(Event::Counted(doc.lines().count()))
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        let Event::Counted(n) = e;
+        s.lines += n;
+        Some(Change::Updated)
+    }
+
+    fn on_change(_s: &Self::S, _e: &Self::E, _se: &mut Self::SE, _change: Change) {}
+}
+
+#[test]
+fn borrowed_command_test() {
+    let mut s = State::default();
+    let mut se = Effects::default();
+
+    // A document owned by the caller is counted without copying it
+    let doc = String::from("one\ntwo\nthree");
+    let c = Command::Count(Cow::Borrowed(&doc));
+    let e = Lines::step(&mut s, Input::Command(c), &mut se);
+    assert_eq!(e, Some(Event::Counted(3)));
+
+    // The same machine with the static lifetime takes owned documents
+    let c = Command::Count(Cow::Owned(String::from("four")));
+    let e = Lines::<'static>::step(&mut s, Input::Command(c), &mut se);
+    assert_eq!(e, Some(Event::Counted(1)));
+
+    assert_eq!(s.lines, 4);
+    assert_eq!(se.borrowed, [true, false]);
+}
@@ -0,0 +1,78 @@
+// Declare our state, commands and events, with the states boxed as a large state would be
+
+use edfsm::{impl_fsm, Fsm, Input};
+
+struct Idle;
+struct Running {
+    readings: [u64; 64],
+}
+enum State {
+    Idle(Box<Idle>),
+    Running(Box<Running>),
+}
+
+struct Start;
+struct Stop;
+enum Command {
+    Start(Start),
+    Stop(Stop),
+}
+
+struct Started;
+struct Stopped;
+enum Event {
+    Started(Started),
+    Stopped(Stopped),
+}
+
+// Declare the FSM itself
+
+struct MyFsm;
+
+#[impl_fsm(state_eq)]
+impl Fsm for MyFsm {
+    type S = State;
+    type C = Command;
+    type E = Event;
+    type SE = ();
+
+    command!(Idle    => Start => Started => Running);
+    command!(Running => Stop  => Stopped => Idle);
+
+    ignore_command!(Idle    => Stop);
+    ignore_command!(Running => Start);
+}
+
+impl MyFsm {
+    fn for_idle_start(_s: &Idle, _c: Start, _se: &mut ()) -> Option<Started> {
+        Some(Started)
+    }
+
+    fn on_idle_started(_s: &Idle, _e: &Started) -> Option<Box<Running>> {
+        Some(Box::new(Running { readings: [0; 64] }))
+    }
+
+    fn for_running_stop(s: &Running, _c: Stop, _se: &mut ()) -> Option<Stopped> {
+        s.readings.iter().all(|r| *r == 0).then_some(Stopped)
+    }
+
+    fn on_running_stopped(_s: &Running, _e: &Stopped) -> Option<Box<Idle>> {
+        Some(Box::new(Idle))
+    }
+}
+
+#[test]
+fn boxed_state() {
+    let mut s = State::Idle(Box::new(Idle));
+
+    let e = MyFsm::step(&mut s, Input::Command(Command::Start(Start)), &mut ());
+    assert!(matches!(e, Some(Event::Started(_))));
+    assert!(MyFsm::state_eq(
+        &s,
+        &State::Running(Box::new(Running { readings: [0; 64] }))
+    ));
+
+    let e = MyFsm::step(&mut s, Input::Command(Command::Stop(Stop)), &mut ());
+    assert!(matches!(e, Some(Event::Stopped(_))));
+    assert!(MyFsm::state_eq(&s, &State::Idle(Box::new(Idle))));
+}
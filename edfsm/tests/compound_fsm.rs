@@ -0,0 +1,135 @@
+// Declare our state, commands and events
+
+use edfsm::{Cascade, Change, Compound, Compounded, Fsm, Input, MAX_CASCADE};
+
+#[derive(Debug, Default)]
+struct Account {
+    balance: u32,
+}
+
+enum Command {
+    Deposit(u32),
+    Withdraw(u32),
+}
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Deposited(u32),
+    Withdrawn(u32),
+}
+
+/// The most that may be withdrawn as one event.
+const LIMIT: u32 = 10;
+
+// Declare the FSM itself, withdrawing in amounts of at most the limit
+
+struct MyFsm;
+
+impl Fsm for MyFsm {
+    type S = Account;
+    type C = Command;
+    type E = Event;
+    type SE = ();
+
+    fn for_command(_s: &Self::S, _c: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
+        None
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        match e {
+            Event::Deposited(n) => s.balance += n,
+            Event::Withdrawn(n) => s.balance = s.balance.checked_sub(*n)?,
+        }
+        Some(Change::Updated)
+    }
+
+    fn on_change(_s: &Self::S, _e: &Self::E, _se: &mut Self::SE, _change: Change) {}
+}
+
+impl Compound for MyFsm {
+    fn for_command_many(
+        s: &Account,
+        c: &Command,
+        prior: &Cascade<Event>,
+        _se: &mut (),
+    ) -> Option<Event> {
+        match c {
+            Command::Deposit(n) => prior.is_empty().then_some(Event::Deposited(*n)),
+            Command::Withdraw(n) => {
+                let withdrawn: u32 = prior
+                    .events()
+                    .map(|e| match e {
+                        Event::Withdrawn(n) => *n,
+                        _ => 0,
+                    })
+                    .sum();
+                let next = (n - withdrawn).min(LIMIT).min(s.balance);
+                (next > 0).then_some(Event::Withdrawn(next))
+            }
+        }
+    }
+}
+
+type Atm = Compounded<MyFsm>;
+
+#[test]
+fn compound_command() {
+    let mut s = Account::default();
+
+    // A single event
+    let events: Vec<_> =
+        Atm::step_all(&mut s, Input::Command(Command::Deposit(50)), &mut ()).collect();
+    assert_eq!(events, [Event::Deposited(50)]);
+
+    // Each event is produced against the state that the prior ones resulted in
+    let events: Vec<_> =
+        Atm::step_all(&mut s, Input::Command(Command::Withdraw(25)), &mut ()).collect();
+    assert_eq!(
+        events,
+        [
+            Event::Withdrawn(10),
+            Event::Withdrawn(10),
+            Event::Withdrawn(5)
+        ]
+    );
+    assert_eq!(s.balance, 25);
+
+    // The command ends early when the handler returns None
+    let events: Vec<_> =
+        Atm::step_all(&mut s, Input::Command(Command::Withdraw(40)), &mut ()).collect();
+    assert_eq!(
+        events,
+        [
+            Event::Withdrawn(10),
+            Event::Withdrawn(10),
+            Event::Withdrawn(5)
+        ]
+    );
+    assert_eq!(s.balance, 0);
+
+    // Events are applied as usual
+    let events: Vec<_> =
+        Atm::step_all(&mut s, Input::Event(Event::Deposited(5)), &mut ()).collect();
+    assert_eq!(events, [Event::Deposited(5)]);
+    assert_eq!(s.balance, 5);
+}
+
+#[test]
+fn compound_command_single_step() {
+    let mut s = Account { balance: 30 };
+
+    // Stepping a command produces just its first event
+    let e = Atm::step(&mut s, Input::Command(Command::Withdraw(30)), &mut ());
+    assert_eq!(e, Some(Event::Withdrawn(10)));
+    assert_eq!(s.balance, 20);
+}
+
+#[test]
+fn compound_command_bounded() {
+    let mut s = Account { balance: 1000 };
+
+    // At most MAX_CASCADE + 1 events are applied for a command
+    let events = Atm::step_all(&mut s, Input::Command(Command::Withdraw(1000)), &mut ());
+    assert_eq!(events.len(), MAX_CASCADE + 1);
+    assert_eq!(s.balance, 1000 - LIMIT * (MAX_CASCADE as u32 + 1));
+}
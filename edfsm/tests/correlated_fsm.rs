@@ -0,0 +1,81 @@
+use edfsm::{Change, Correlated, Correlating, Correlation, Correlator, Fsm, Input};
+use uuid::Uuid;
+
+// A counter whose commands carry a correlation id
+
+struct Counter;
+
+struct Add {
+    request: Option<Uuid>,
+    amount: u32,
+}
+
+impl Correlated for Add {
+    fn correlation_id(&self) -> Option<Uuid> {
+        self.request
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Added(u32);
+
+impl Fsm for Counter {
+    type S = u32;
+    type C = Add;
+    type E = Added;
+    type SE = ();
+
+    fn for_command(_s: &Self::S, c: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
+        (c.amount > 0).then_some(Added(c.amount))
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        *s += e.0;
+        Some(Change::Updated)
+    }
+
+    fn on_change(_s: &Self::S, _e: &Self::E, _se: &mut Self::SE, _change: Change) {}
+}
+
+type Traceable = Correlating<Counter>;
+
+#[test]
+fn correlated() {
+    let mut s = 0;
+    let mut se = Correlator::new(());
+    let request = Uuid::from_u128(1);
+
+    // The id of a command is copied onto the event it produces
+    let c = Add {
+        request: Some(request),
+        amount: 2,
+    };
+    let e = Traceable::step(&mut s, Input::Command(c), &mut se);
+    assert_eq!(
+        e,
+        Some(Correlation {
+            id: Some(request),
+            item: Added(2)
+        })
+    );
+    assert_eq!(se.correlation_id(), Some(request));
+
+    // An uncorrelated command produces an uncorrelated event
+    let c = Add {
+        request: None,
+        amount: 1,
+    };
+    let e = Traceable::step(&mut s, Input::Command(c), &mut se);
+    assert_eq!(e.and_then(|e| e.correlation_id()), None);
+    assert_eq!(se.correlation_id(), None);
+
+    // An event given as input, say when replicated, retains its id
+    let other = Uuid::from_u128(2);
+    let e = Correlation {
+        id: Some(other),
+        item: Added(3),
+    };
+    Traceable::step(&mut s, Input::Event(e), &mut se);
+    assert_eq!(se.correlation_id(), Some(other));
+    assert_eq!(s, 6);
+}
@@ -0,0 +1,99 @@
+// Declare our state, commands and events
+
+use edfsm::{impl_fsm, CommandCount, Counting, Fsm, Input, Named, Tally};
+
+struct Idle;
+struct Running;
+enum State {
+    Idle(Idle),
+    Running(Running),
+}
+
+struct Start;
+struct Stop;
+enum Command {
+    Start(Start),
+    Stop(Stop),
+}
+
+impl Named for Command {
+    fn name(&self) -> &'static str {
+        match self {
+            Command::Start(_) => "Start",
+            Command::Stop(_) => "Stop",
+        }
+    }
+}
+
+struct Started;
+struct Stopped;
+enum Event {
+    Started(Started),
+    Stopped(Stopped),
+}
+
+// Declare the FSM itself
+
+struct MyFsm;
+
+#[impl_fsm]
+impl Fsm for MyFsm {
+    type S = State;
+    type C = Command;
+    type E = Event;
+    type SE = ();
+
+    command!(Idle    => Start => Started => Running);
+    command!(Running => Stop  => Stopped => Idle);
+
+    ignore_command!(Idle    => Stop);
+    ignore_command!(Running => Start);
+}
+
+impl MyFsm {
+    fn for_running_stop(_s: &Running, _c: Stop, _se: &mut ()) -> Option<Stopped> {
+        Some(Stopped)
+    }
+
+    fn on_running_stopped(_s: &Running, _e: &Stopped) -> Option<Idle> {
+        Some(Idle)
+    }
+
+    fn for_idle_start(_s: &Idle, _c: Start, _se: &mut ()) -> Option<Started> {
+        Some(Started)
+    }
+
+    fn on_idle_started(_s: &Idle, _e: &Started) -> Option<Running> {
+        Some(Running)
+    }
+}
+
+#[test]
+fn counting() {
+    type MyCountingFsm = Counting<MyFsm, 1>;
+
+    let mut se = Tally::<(), 1>::default();
+    let mut s = State::Idle(Idle);
+
+    MyCountingFsm::step(&mut s, Input::Command(Command::Start(Start)), &mut se);
+    MyCountingFsm::step(&mut s, Input::Command(Command::Start(Start)), &mut se);
+    MyCountingFsm::step(&mut s, Input::Command(Command::Start(Start)), &mut se);
+    MyCountingFsm::step(&mut s, Input::Command(Command::Stop(Stop)), &mut se);
+
+    // Starting when running is rejected, and with room for one name, stops are not counted.
+
+    assert_eq!(
+        se.count("Start"),
+        Some(&CommandCount {
+            name: "Start",
+            accepted: 1,
+            rejected: 2
+        })
+    );
+    assert_eq!(se.count("Stop"), None);
+
+    // Events are not counted.
+
+    MyCountingFsm::step(&mut s, Input::Event(Event::Stopped(Stopped)), &mut se);
+    assert_eq!(se.counts().count(), 1);
+}
@@ -0,0 +1,90 @@
+// Declare our state, commands and events
+
+use edfsm::{impl_fsm, Fsm};
+
+#[derive(Debug)]
+struct Idle;
+#[derive(Debug)]
+struct Running;
+#[derive(Debug)]
+enum State {
+    Idle(Idle),
+    Running(Running),
+}
+
+struct Start;
+struct Stop;
+#[allow(dead_code)]
+enum Command {
+    Start(Start),
+    Stop(Stop),
+}
+
+#[derive(Debug)]
+struct Started;
+#[derive(Debug)]
+struct Stopped;
+#[derive(Debug)]
+enum Event {
+    Started(Started),
+    Stopped(Stopped),
+}
+
+// Declare the FSM itself
+
+struct MyFsm;
+
+#[impl_fsm(describe)]
+impl Fsm for MyFsm {
+    type S = State;
+    type C = Command;
+    type E = Event;
+    type SE = ();
+
+    command!(Idle    => Start => Started => Running);
+    command!(Running => Stop  => Stopped => Idle);
+
+    ignore_command!(Idle    => Stop);
+    ignore_command!(Running => Start);
+}
+
+impl MyFsm {
+    fn for_running_stop(_s: &Running, _c: Stop, _se: &mut ()) -> Option<Stopped> {
+        Some(Stopped)
+    }
+
+    fn on_running_stopped(_s: &Running, _e: &Stopped) -> Option<Idle> {
+        Some(Idle)
+    }
+
+    fn for_idle_start(_s: &Idle, _c: Start, _se: &mut ()) -> Option<Started> {
+        Some(Started)
+    }
+
+    fn on_idle_started(_s: &Idle, _e: &Started) -> Option<Running> {
+        Some(Running)
+    }
+}
+
+#[test]
+fn describe_fsm() {
+    let description = MyFsm::describe();
+    println!("{description}");
+
+    for name in ["Idle", "Running", "Start", "Stop", "Started", "Stopped"] {
+        assert!(description.contains(name), "{name} is not described");
+    }
+    assert_eq!(
+        description,
+        "\
+Transitions:
+  state   command event   target
+  Idle    Start   Started Running
+  Running Stop    Stopped Idle
+Ignored commands:
+  state   command
+  Idle    Stop
+  Running Start
+"
+    );
+}
@@ -0,0 +1,66 @@
+use edfsm::{Change, CommandEcho, Echoed, Fsm, Input};
+
+// A counter that only accepts positive amounts
+
+struct Counter;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Add(u32);
+
+#[derive(Debug, Clone, PartialEq)]
+struct Added(u32);
+
+impl Fsm for Counter {
+    type S = u32;
+    type C = Add;
+    type E = Added;
+    type SE = ();
+
+    fn for_command(_s: &Self::S, c: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
+        (c.0 > 0).then_some(Added(c.0))
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        *s += e.0;
+        Some(Change::Updated)
+    }
+
+    fn on_change(_s: &Self::S, _e: &Self::E, _se: &mut Self::SE, _change: Change) {}
+}
+
+type Audited = CommandEcho<Counter>;
+
+#[test]
+fn command_echo() {
+    let mut s = 0;
+    let mut log = Vec::new();
+
+    // Each command is echoed, with the event produced for it
+    for c in [Add(2), Add(0), Add(3)] {
+        log.extend(Audited::step(&mut s, Input::Command(c), &mut ()));
+    }
+    log.extend(Audited::step(
+        &mut s,
+        Input::Event(Echoed::Event(Added(1))),
+        &mut (),
+    ));
+    assert_eq!(s, 6);
+    assert_eq!(
+        log,
+        [
+            Echoed::Command(Add(2), Some(Added(2))),
+            Echoed::Command(Add(0), None),
+            Echoed::Command(Add(3), Some(Added(3))),
+            Echoed::Event(Added(1)),
+        ]
+    );
+
+    // On replay, command echoes without an event are skipped
+    let mut replayed = 0;
+    let changes = log
+        .iter()
+        .filter(|e| Audited::on_event(&mut replayed, e).is_some())
+        .count();
+    assert_eq!(replayed, s);
+    assert_eq!(changes, 3);
+}
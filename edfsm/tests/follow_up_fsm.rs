@@ -0,0 +1,170 @@
+// Declare our state, commands and events
+
+use edfsm::{impl_fsm, Change, Fsm, Input, MAX_CASCADE};
+
+#[derive(Debug)]
+struct Idle;
+#[derive(Debug)]
+struct Connecting;
+#[derive(Debug)]
+struct Connected;
+#[derive(Debug)]
+struct Ready;
+#[derive(Debug)]
+enum State {
+    Idle(Idle),
+    Connecting(Connecting),
+    Connected(Connected),
+    Ready(Ready),
+}
+
+struct Connect;
+enum Command {
+    Connect(Connect),
+}
+
+#[derive(Debug)]
+struct Dialled;
+#[derive(Debug)]
+struct Established;
+#[derive(Debug)]
+struct Negotiated;
+#[derive(Debug)]
+enum Event {
+    Dialled(Dialled),
+    Established(Established),
+    Negotiated(Negotiated),
+}
+
+#[derive(Default)]
+struct Link {
+    up: bool,
+    attempts: u32,
+    ready: bool,
+}
+
+// Declare the FSM itself
+
+struct MyFsm;
+
+#[impl_fsm]
+impl Fsm for MyFsm {
+    type S = State;
+    type C = Command;
+    type E = Event;
+    type SE = Link;
+
+    state!(Connecting / entry => Established);
+    state!(Connected / entry => Negotiated);
+    state!(Ready / entry);
+
+    command!(Idle => Connect => Dialled => Connecting);
+
+    event!(Connecting => Established => Connected);
+    event!(Connected => Negotiated => Ready);
+}
+
+impl MyFsm {
+    fn for_idle_connect(_s: &Idle, _c: Connect, _se: &mut Link) -> Option<Dialled> {
+        Some(Dialled)
+    }
+
+    fn on_idle_dialled(_s: &Idle, _e: &Dialled) -> Option<Connecting> {
+        Some(Connecting)
+    }
+
+    fn on_connecting_established(_s: &Connecting, _e: &Established) -> Option<Connected> {
+        Some(Connected)
+    }
+
+    fn on_connected_negotiated(_s: &Connected, _e: &Negotiated) -> Option<Ready> {
+        Some(Ready)
+    }
+
+    fn on_entry_connecting(_s: &Connecting, se: &mut Link) -> Option<Established> {
+        se.attempts += 1;
+        se.up.then_some(Established)
+    }
+
+    fn on_entry_connected(_s: &Connected, _se: &mut Link) -> Option<Negotiated> {
+        Some(Negotiated)
+    }
+
+    fn on_entry_ready(_s: &Ready, se: &mut Link) {
+        se.ready = true;
+    }
+}
+
+#[test]
+fn entry_cascade() {
+    // Entering Connecting follows up with Established, and entering Connected with Negotiated
+    let mut s = State::Idle(Idle);
+    let mut se = Link {
+        up: true,
+        ..Default::default()
+    };
+    let events: Vec<Event> =
+        MyFsm::step_all(&mut s, Input::Command(Command::Connect(Connect)), &mut se).collect();
+    assert!(matches!(
+        events.as_slice(),
+        [
+            Event::Dialled(_),
+            Event::Established(_),
+            Event::Negotiated(_)
+        ]
+    ));
+    assert!(matches!(s, State::Ready(_)));
+    assert_eq!(se.attempts, 1);
+    assert!(se.ready);
+}
+
+#[test]
+fn entry_without_follow_up() {
+    // An entry handler that yields nothing ends the cascade
+    let mut s = State::Idle(Idle);
+    let mut se = Link::default();
+    let events: Vec<Event> =
+        MyFsm::step_all(&mut s, Input::Command(Command::Connect(Connect)), &mut se).collect();
+    assert!(matches!(events.as_slice(), [Event::Dialled(_)]));
+    assert!(matches!(s, State::Connecting(_)));
+    assert_eq!(se.attempts, 1);
+
+    // A single step does not apply follow-up events
+    let mut s = State::Idle(Idle);
+    se.up = true;
+    let e = MyFsm::step(&mut s, Input::Command(Command::Connect(Connect)), &mut se);
+    assert!(matches!(e, Some(Event::Dialled(_))));
+    assert!(matches!(s, State::Connecting(_)));
+}
+
+/// A state machine that toggles on every event and always follows up with another.
+struct Toggle;
+
+impl Fsm for Toggle {
+    type S = bool;
+    type C = ();
+    type E = ();
+    type SE = ();
+
+    fn for_command(_s: &Self::S, _c: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
+        None
+    }
+
+    fn on_event(s: &mut Self::S, _e: &Self::E) -> Option<Change> {
+        *s = !*s;
+        Some(Change::Transitioned)
+    }
+
+    fn on_change(_s: &Self::S, _e: &Self::E, _se: &mut Self::SE, _change: Change) {}
+
+    fn follow_up(_s: &Self::S, _se: &mut Self::SE) -> Option<Self::E> {
+        Some(())
+    }
+}
+
+#[test]
+fn cascade_depth_is_bounded() {
+    let mut s = false;
+    let events = Toggle::step_all(&mut s, Input::Event(()), &mut ()).count();
+    assert_eq!(events, MAX_CASCADE + 1);
+}
@@ -0,0 +1,148 @@
+// Declare our state, commands and events
+
+use edfsm::{impl_fsm, Fsm, Guard, Guarded, Input, Named, Traced, Tracer, Transition};
+
+struct Idle;
+struct Running;
+enum State {
+    Idle(Idle),
+    Running(Running),
+}
+
+impl Named for State {
+    fn name(&self) -> &'static str {
+        match self {
+            State::Idle(_) => "Idle",
+            State::Running(_) => "Running",
+        }
+    }
+}
+
+struct Start;
+struct Stop;
+enum Command {
+    Start(Start),
+    Stop(Stop),
+}
+
+struct Started;
+struct Stopped;
+enum Event {
+    Started(Started),
+    Stopped(Stopped),
+}
+
+impl Named for Event {
+    fn name(&self) -> &'static str {
+        match self {
+            Event::Started(_) => "Started",
+            Event::Stopped(_) => "Stopped",
+        }
+    }
+}
+
+// Declare the FSM itself
+
+struct MyFsm;
+
+#[impl_fsm]
+impl Fsm for MyFsm {
+    type S = State;
+    type C = Command;
+    type E = Event;
+    type SE = ();
+
+    command!(Idle    => Start => Started => Running);
+    command!(Running => Stop  => Stopped => Idle);
+
+    ignore_command!(Idle    => Stop);
+    ignore_command!(Running => Start);
+}
+
+impl MyFsm {
+    fn for_running_stop(_s: &Running, _c: Stop, _se: &mut ()) -> Option<Stopped> {
+        Some(Stopped)
+    }
+
+    fn on_running_stopped(_s: &Running, _e: &Stopped) -> Option<Idle> {
+        Some(Idle)
+    }
+
+    fn for_idle_start(_s: &Idle, _c: Start, _se: &mut ()) -> Option<Started> {
+        Some(Started)
+    }
+
+    fn on_idle_started(_s: &Idle, _e: &Started) -> Option<Running> {
+        Some(Running)
+    }
+}
+
+/// The stopping transition is deliberately left out of the table.
+const ALLOWED: &[(&str, &str)] = &[("Idle", "Running")];
+
+#[test]
+fn guarded() {
+    type MyGuardedFsm = Guarded<MyFsm>;
+
+    let mut se = Guard::new((), ALLOWED);
+    let mut s = State::Idle(Idle);
+
+    // An allowed transition, and a command that is ignored, pass the guard.
+    MyGuardedFsm::step(&mut s, Input::Command(Command::Start(Start)), &mut se);
+    MyGuardedFsm::step(&mut s, Input::Command(Command::Start(Start)), &mut se);
+    assert!(matches!(s, State::Running(_)));
+    assert!(!se.is_allowed("Running", "Idle"));
+}
+
+#[test]
+#[should_panic(expected = "transition from Running to Idle is not allowed")]
+fn guarded_out_of_table() {
+    type MyGuardedFsm = Guarded<MyFsm>;
+
+    let mut se = Guard::new((), ALLOWED);
+    let mut s = State::Running(Running);
+
+    MyGuardedFsm::step(&mut s, Input::Command(Command::Stop(Stop)), &mut se);
+}
+
+const STARTED: Transition = Transition {
+    from: "Idle",
+    input: "Started",
+    to: "Running",
+};
+
+#[test]
+fn guarded_within_traced() {
+    type MyFsms = Traced<Guarded<MyFsm>, 2>;
+
+    let mut se = Tracer::<_, 2>::new(Guard::new((), ALLOWED));
+    let mut s = State::Idle(Idle);
+
+    // Both wrappers see the transition from the state the step started from
+    MyFsms::step(&mut s, Input::Command(Command::Start(Start)), &mut se);
+    assert!(matches!(s, State::Running(_)));
+    assert_eq!(se.history().copied().collect::<Vec<_>>(), [STARTED]);
+}
+
+#[test]
+fn traced_within_guarded() {
+    type MyFsms = Guarded<Traced<MyFsm, 2>>;
+
+    let mut se = Guard::new(Tracer::<(), 2>::default(), ALLOWED);
+    let mut s = State::Idle(Idle);
+
+    MyFsms::step(&mut s, Input::Command(Command::Start(Start)), &mut se);
+    assert!(matches!(s, State::Running(_)));
+    assert_eq!(se.inner().history().copied().collect::<Vec<_>>(), [STARTED]);
+}
+
+#[test]
+#[should_panic(expected = "transition from Running to Idle is not allowed")]
+fn guarded_within_traced_out_of_table() {
+    type MyFsms = Traced<Guarded<MyFsm>, 2>;
+
+    let mut se = Tracer::<_, 2>::new(Guard::new((), ALLOWED));
+    let mut s = State::Running(Running);
+
+    MyFsms::step(&mut s, Input::Command(Command::Stop(Stop)), &mut se);
+}
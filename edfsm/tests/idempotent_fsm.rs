@@ -0,0 +1,100 @@
+use edfsm::{Change, Deduplicator, Fsm, IdempotencyKey, Idempotent, Input};
+
+// An account that is credited by commands delivered at least once
+
+struct Account;
+
+struct Credit {
+    id: Option<u32>,
+    amount: u32,
+}
+
+impl IdempotencyKey for Credit {
+    type Key = u32;
+
+    fn idempotency_key(&self) -> Option<Self::Key> {
+        self.id
+    }
+}
+
+struct Credited(u32);
+
+impl Fsm for Account {
+    type S = u32;
+    type C = Credit;
+    type E = Credited;
+    type SE = ();
+
+    fn for_command(_s: &Self::S, c: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
+        // An empty credit is rejected
+        (c.amount > 0).then_some(Credited(c.amount))
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        *s += e.0;
+        Some(Change::Updated)
+    }
+
+    fn on_change(_s: &Self::S, _e: &Self::E, _se: &mut Self::SE, _change: Change) {}
+}
+
+type MyIdempotentFsm = Idempotent<Account, 2>;
+
+fn credit(s: &mut u32, se: &mut Deduplicator<(), u32, 2>, id: Option<u32>) -> bool {
+    credit_amount(s, se, id, 10)
+}
+
+fn credit_amount(
+    s: &mut u32,
+    se: &mut Deduplicator<(), u32, 2>,
+    id: Option<u32>,
+    amount: u32,
+) -> bool {
+    let c = Credit { id, amount };
+    MyIdempotentFsm::step(s, Input::Command(c), se).is_some()
+}
+
+#[test]
+fn idempotent() {
+    let mut se = Deduplicator::default();
+    let mut s = 0;
+
+    // A repeated key is dropped
+    assert!(credit(&mut s, &mut se, Some(1)));
+    assert!(!credit(&mut s, &mut se, Some(1)));
+    assert!(credit(&mut s, &mut se, Some(2)));
+    assert!(!credit(&mut s, &mut se, Some(1)));
+    assert_eq!(s, 20);
+
+    // Commands without a key are always processed
+    assert!(credit(&mut s, &mut se, None));
+    assert!(credit(&mut s, &mut se, None));
+    assert_eq!(s, 40);
+
+    // A repeated key becomes the most recently seen, and the least recently seen
+    // key is forgotten beyond the window
+    assert_eq!(se.keys().copied().collect::<Vec<_>>(), [2, 1]);
+    assert!(credit(&mut s, &mut se, Some(3)));
+    assert_eq!(se.keys().copied().collect::<Vec<_>>(), [1, 3]);
+    assert!(!credit(&mut s, &mut se, Some(1)));
+    assert!(credit(&mut s, &mut se, Some(2)));
+    assert_eq!(s, 60);
+
+    // Events are not deduplicated
+    MyIdempotentFsm::step(&mut s, Input::Event(Credited(5)), &mut se);
+    MyIdempotentFsm::step(&mut s, Input::Event(Credited(5)), &mut se);
+    assert_eq!(s, 70);
+}
+
+#[test]
+fn idempotent_rejected() {
+    let mut se = Deduplicator::default();
+    let mut s = 0;
+
+    // A rejected command is not retained, so its corrected retry is processed
+    assert!(!credit_amount(&mut s, &mut se, Some(1), 0));
+    assert_eq!(se.keys().count(), 0);
+    assert!(credit_amount(&mut s, &mut se, Some(1), 10));
+    assert!(!credit_amount(&mut s, &mut se, Some(1), 10));
+    assert_eq!(s, 10);
+}
@@ -0,0 +1,53 @@
+use edfsm::{Change, Fsm, Input};
+
+/// A counter whose count must not become negative.
+struct Counter;
+
+enum Event {
+    Increment,
+    Decrement,
+}
+
+impl Fsm for Counter {
+    type S = i32;
+    type C = ();
+    type E = Event;
+    type SE = ();
+
+    fn for_command(_s: &Self::S, _c: Self::C, _se: &mut Self::SE) -> Option<Self::E> {
+        None
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        match e {
+            Event::Increment => *s += 1,
+            Event::Decrement => *s -= 1, // Deliberately unguarded
+        }
+        Some(Change::Updated)
+    }
+
+    fn on_change(_s: &Self::S, _e: &Self::E, _se: &mut Self::SE, _change: Change) {}
+
+    fn check_invariants(s: &Self::S) -> Result<(), &'static str> {
+        if *s >= 0 {
+            Ok(())
+        } else {
+            Err("count must not be negative")
+        }
+    }
+}
+
+#[test]
+fn invariants_hold() {
+    let mut s = 0;
+    Counter::step(&mut s, Input::Event(Event::Increment), &mut ());
+    Counter::step(&mut s, Input::Event(Event::Decrement), &mut ());
+    assert_eq!(s, 0);
+}
+
+#[test]
+#[should_panic(expected = "count must not be negative")]
+fn invariants_violated() {
+    let mut s = 0;
+    Counter::step(&mut s, Input::Event(Event::Decrement), &mut ());
+}
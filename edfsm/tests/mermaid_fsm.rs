@@ -0,0 +1,95 @@
+use edfsm::{impl_fsm, Fsm, Input};
+
+struct Idle;
+struct Running;
+struct Failed;
+enum State {
+    Idle(Idle),
+    Running(Running),
+    Failed(Failed),
+}
+
+struct Start;
+struct Stop;
+enum Command {
+    Start(Start),
+    Stop(Stop),
+}
+
+struct Started;
+struct Stopped;
+struct Faulted;
+enum Event {
+    Started(Started),
+    Stopped(Stopped),
+    Faulted(Faulted),
+}
+
+struct MyFsm;
+
+#[impl_fsm(mermaid)]
+impl Fsm for MyFsm {
+    type S = State;
+    type C = Command;
+    type E = Event;
+    type SE = ();
+
+    command!(Idle    => Start => Started => Running);
+    command!(Running => Stop  => Stopped => Idle);
+
+    event!(_ => Faulted => Failed);
+
+    ignore_command!(Idle    => Stop);
+    ignore_command!(Running => Start);
+    ignore_command!(Failed  => Start);
+    ignore_command!(Failed  => Stop);
+}
+
+impl MyFsm {
+    fn for_idle_start(_s: &Idle, _c: Start, _se: &mut ()) -> Option<Started> {
+        Some(Started)
+    }
+
+    fn on_idle_started(_s: &Idle, _e: &Started) -> Option<Running> {
+        Some(Running)
+    }
+
+    fn for_running_stop(_s: &Running, _c: Stop, _se: &mut ()) -> Option<Stopped> {
+        Some(Stopped)
+    }
+
+    fn on_running_stopped(_s: &Running, _e: &Stopped) -> Option<Idle> {
+        Some(Idle)
+    }
+
+    fn on_any_faulted(_s: &State, _e: &Faulted) -> Option<Failed> {
+        Some(Failed)
+    }
+}
+
+#[test]
+fn mermaid_diagram() {
+    // Remaining failed is a transition, and so is drawn
+    assert_eq!(
+        MyFsm::MERMAID,
+        "stateDiagram-v2
+    Idle --> Running : Start/Started
+    Running --> Idle : Stop/Stopped
+    Idle --> Failed : Faulted
+    Running --> Failed : Faulted
+    Failed --> Failed : Faulted
+"
+    );
+}
+
+#[test]
+fn diagrammed_fsm() {
+    let mut s = State::Idle(Idle);
+    MyFsm::step(&mut s, Input::Command(Command::Start(Start)), &mut ());
+    assert!(matches!(s, State::Running(_)));
+    MyFsm::step(&mut s, Input::Event(Event::Faulted(Faulted)), &mut ());
+    assert!(matches!(s, State::Failed(_)));
+    MyFsm::step(&mut s, Input::Command(Command::Stop(Stop)), &mut ());
+    assert!(matches!(s, State::Failed(_)));
+    assert!(MyFsm::step(&mut s, Input::Event(Event::Stopped(Stopped)), &mut ()).is_none());
+}
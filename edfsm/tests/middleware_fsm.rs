@@ -0,0 +1,96 @@
+// Declare our state, commands and events
+
+use edfsm::{impl_fsm, Decision, Fsm, Input, Middleware, WithMiddleware};
+
+struct Idle;
+struct Running;
+enum State {
+    Idle(Idle),
+    Running(Running),
+}
+
+struct Start;
+struct Stop;
+enum Command {
+    Start(Start),
+    Stop(Stop),
+}
+
+struct Started;
+struct Stopped;
+enum Event {
+    Started(Started),
+    Stopped(Stopped),
+}
+
+// Declare the FSM itself
+
+struct MyFsm;
+
+#[impl_fsm]
+impl Fsm for MyFsm {
+    type S = State;
+    type C = Command;
+    type E = Event;
+    type SE = ();
+
+    command!(Idle    => Start => Started => Running);
+    command!(Running => Stop  => Stopped => Idle);
+
+    ignore_command!(Idle    => Stop);
+    ignore_command!(Running => Start);
+}
+
+impl MyFsm {
+    fn for_running_stop(_s: &Running, _c: Stop, _se: &mut ()) -> Option<Stopped> {
+        Some(Stopped)
+    }
+
+    fn on_running_stopped(_s: &Running, _e: &Stopped) -> Option<Idle> {
+        Some(Idle)
+    }
+
+    fn for_idle_start(_s: &Idle, _c: Start, _se: &mut ()) -> Option<Started> {
+        Some(Started)
+    }
+
+    fn on_idle_started(_s: &Idle, _e: &Started) -> Option<Running> {
+        Some(Running)
+    }
+}
+
+#[test]
+fn middleware() {
+    // A policy that permits only one stop, and treats a stop when idle as a start.
+    let mut stops = 0;
+    let policy = move |s: &State, c: &Command| match (s, c) {
+        (State::Idle(_), Command::Stop(_)) => Decision::Rewrite(Command::Start(Start)),
+        (_, Command::Stop(_)) if stops > 0 => Decision::Reject,
+        (_, Command::Stop(_)) => {
+            stops += 1;
+            Decision::Allow
+        }
+        _ => Decision::Allow,
+    };
+
+    let mut se = Middleware::new(policy, ());
+    let mut s = State::Idle(Idle);
+
+    fn step<F>(s: &mut State, c: Command, se: &mut Middleware<F, ()>) -> Option<Event>
+    where
+        F: FnMut(&State, &Command) -> Decision<Command>,
+    {
+        WithMiddleware::<MyFsm, F>::step(s, Input::Command(c), se)
+    }
+
+    let e = step(&mut s, Command::Stop(Stop), &mut se);
+    assert!(matches!(e, Some(Event::Started(Started))));
+
+    let e = step(&mut s, Command::Stop(Stop), &mut se);
+    assert!(matches!(e, Some(Event::Stopped(Stopped))));
+
+    step(&mut s, Command::Start(Start), &mut se);
+    let e = step(&mut s, Command::Stop(Stop), &mut se);
+    assert!(e.is_none());
+    assert!(matches!(s, State::Running(_)));
+}
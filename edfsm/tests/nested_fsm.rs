@@ -0,0 +1,231 @@
+// Declare the start/stop state machine to be embedded
+
+use edfsm::{impl_fsm, Change, Counting, Fsm, Input, Lens, Named, Nested, Tally};
+
+#[derive(Debug)]
+struct Idle;
+#[derive(Debug)]
+struct Running;
+#[derive(Debug)]
+enum State {
+    Idle(Idle),
+    Running(Running),
+}
+
+struct Start;
+struct Stop;
+#[allow(dead_code)]
+enum Command {
+    Start(Start),
+    Stop(Stop),
+}
+
+impl Named for Command {
+    fn name(&self) -> &'static str {
+        match self {
+            Command::Start(_) => "Start",
+            Command::Stop(_) => "Stop",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Started;
+#[derive(Debug)]
+struct Stopped;
+#[derive(Debug)]
+enum Event {
+    Started(Started),
+    Stopped(Stopped),
+}
+
+// Declare the FSM itself
+
+struct MyFsm;
+
+#[impl_fsm(state_eq)]
+impl Fsm for MyFsm {
+    type S = State;
+    type C = Command;
+    type E = Event;
+    type SE = ();
+
+    command!(Idle    => Start => Started => Running);
+    command!(Running => Stop  => Stopped => Idle);
+
+    ignore_command!(Idle    => Stop);
+    ignore_command!(Running => Start);
+}
+
+impl MyFsm {
+    fn for_running_stop(_s: &Running, _c: Stop, _se: &mut ()) -> Option<Stopped> {
+        Some(Stopped)
+    }
+
+    fn on_running_stopped(_s: &Running, _e: &Stopped) -> Option<Idle> {
+        Some(Idle)
+    }
+
+    fn for_idle_start(_s: &Idle, _c: Start, _se: &mut ()) -> Option<Started> {
+        Some(Started)
+    }
+
+    fn on_idle_started(_s: &Idle, _e: &Started) -> Option<Running> {
+        Some(Running)
+    }
+}
+
+// A plant that embeds the start/stop state machine alongside a state of its own
+
+#[derive(Debug)]
+struct Plant {
+    motor: State,
+    target: u32,
+}
+
+enum PlantCommand {
+    Motor(Command),
+    SetTarget(u32),
+}
+
+#[derive(Debug)]
+enum PlantEvent {
+    Motor(Event),
+    TargetSet(u32),
+}
+
+struct MotorLens;
+
+impl<M> Lens<M> for MotorLens
+where
+    M: Fsm<S = State, C = Command, E = Event>,
+{
+    type S = Plant;
+    type C = PlantCommand;
+    type E = PlantEvent;
+
+    fn get(s: &Plant) -> &State {
+        &s.motor
+    }
+
+    fn get_mut(s: &mut Plant) -> &mut State {
+        &mut s.motor
+    }
+
+    fn command(c: PlantCommand) -> Option<Command> {
+        match c {
+            PlantCommand::Motor(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    fn event(e: &PlantEvent) -> Option<&Event> {
+        match e {
+            PlantEvent::Motor(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    fn into_event(e: PlantEvent) -> Option<Event> {
+        match e {
+            PlantEvent::Motor(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    fn wrap(e: Event) -> PlantEvent {
+        PlantEvent::Motor(e)
+    }
+}
+
+type Motor = Nested<MyFsm, MotorLens>;
+
+struct PlantFsm;
+
+impl Fsm for PlantFsm {
+    type S = Plant;
+    type C = PlantCommand;
+    type E = PlantEvent;
+    type SE = ();
+
+    fn for_command(s: &Self::S, c: Self::C, se: &mut Self::SE) -> Option<Self::E> {
+        match c {
+            PlantCommand::SetTarget(t) => Some(PlantEvent::TargetSet(t)),
+            c => Motor::for_command(s, c, se),
+        }
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        match e {
+            PlantEvent::TargetSet(t) => {
+                s.target = *t;
+                Some(Change::Updated)
+            }
+            e => Motor::on_event(s, e),
+        }
+    }
+
+    fn on_change(s: &Self::S, e: &Self::E, se: &mut Self::SE, change: Change) {
+        Motor::on_change(s, e, se, change)
+    }
+}
+
+#[test]
+fn nested() {
+    let mut s = Plant {
+        motor: State::Idle(Idle),
+        target: 0,
+    };
+    let mut se = ();
+
+    // Commands for the child are delegated through the lens, and its events wrapped
+    let e = PlantFsm::step(
+        &mut s,
+        Input::Command(PlantCommand::Motor(Command::Start(Start))),
+        &mut se,
+    );
+    assert!(matches!(e, Some(PlantEvent::Motor(Event::Started(_)))));
+    assert!(MyFsm::state_eq(&s.motor, &State::Running(Running)));
+
+    // The parent handles its own commands
+    PlantFsm::step(&mut s, Input::Command(PlantCommand::SetTarget(42)), &mut se);
+    assert_eq!(s.target, 42);
+
+    // The nested state machine ignores inputs that are not for the child
+    let e = Motor::step(&mut s, Input::Command(PlantCommand::SetTarget(7)), &mut se);
+    assert!(e.is_none());
+    assert_eq!(s.target, 42);
+
+    // Events for the child are applied to its part of the state
+    Motor::step(
+        &mut s,
+        Input::Event(PlantEvent::Motor(Event::Stopped(Stopped))),
+        &mut se,
+    );
+    assert!(MyFsm::state_eq(&s.motor, &State::Idle(Idle)));
+}
+
+#[test]
+fn nested_wrapper() {
+    type CountedMotor = Nested<Counting<MyFsm, 2>, MotorLens>;
+
+    let mut s = Plant {
+        motor: State::Idle(Idle),
+        target: 0,
+    };
+    let mut se = Tally::<(), 2>::default();
+
+    // Stepping is delegated to the wrapped child, which counts its commands
+    let e = CountedMotor::step(
+        &mut s,
+        Input::Command(PlantCommand::Motor(Command::Start(Start))),
+        &mut se,
+    );
+    assert!(matches!(e, Some(PlantEvent::Motor(Event::Started(_)))));
+    assert!(MyFsm::state_eq(&s.motor, &State::Running(Running)));
+    assert_eq!(se.count("Start").map(|c| c.accepted), Some(1));
+
+    // Commands that are not for the child are neither stepped nor counted
+    CountedMotor::step(&mut s, Input::Command(PlantCommand::SetTarget(7)), &mut se);
+    assert_eq!(se.counts().count(), 1);
+}
@@ -0,0 +1,181 @@
+use core::future::{ready, Future};
+use edfsm::{
+    Change, Clock, Decision, Fsm, Input, Limiter, Middleware, RateLimited, WithMiddleware,
+};
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+/// A virtual clock that is advanced by the test.
+#[derive(Clone, Default)]
+struct ManualClock(Rc<Cell<Duration>>);
+
+impl ManualClock {
+    fn advance(&self, by: Duration) {
+        self.0.set(self.0.get() + by);
+    }
+}
+
+impl Clock for ManualClock {
+    type Instant = Duration;
+
+    fn now(&self) -> Self::Instant {
+        self.0.get()
+    }
+
+    fn deadline(&self, after: Duration) -> Self::Instant {
+        self.now() + after
+    }
+
+    fn sleep_until(&self, _deadline: Self::Instant) -> impl Future<Output = ()> + Send {
+        ready(())
+    }
+}
+
+// A switch that counts how often it is adjusted, and whose effector counts its commands
+
+struct Switch;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct State {
+    on: bool,
+    adjustments: u32,
+}
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Toggle,
+    Adjust,
+}
+
+impl Fsm for Switch {
+    type S = State;
+    type C = Event;
+    type E = Event;
+    type SE = u32;
+
+    fn for_command(_s: &Self::S, c: Self::C, se: &mut Self::SE) -> Option<Self::E> {
+        *se += 1;
+        Some(c)
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        match e {
+            Event::Toggle => {
+                s.on = !s.on;
+                Some(Change::Transitioned)
+            }
+            Event::Adjust => {
+                s.adjustments += 1;
+                Some(Change::Updated)
+            }
+        }
+    }
+
+    fn on_change(_s: &Self::S, _e: &Self::E, _se: &mut Self::SE, _change: Change) {}
+}
+
+type Limited = RateLimited<Switch, ManualClock>;
+
+#[test]
+fn rate_limited() {
+    let clock = ManualClock::default();
+    let mut se = Limiter::new(0, clock.clone(), Duration::from_secs(1));
+    let mut s = State::default();
+
+    assert_eq!(
+        Limited::step(&mut s, Input::Event(Event::Toggle), &mut se),
+        Some(Event::Toggle)
+    );
+    assert!(s.on);
+    assert!(se.is_limited());
+
+    // Within the interval, transitions are dropped but updates apply
+    clock.advance(Duration::from_millis(500));
+    assert_eq!(
+        Limited::step(&mut s, Input::Event(Event::Toggle), &mut se),
+        None
+    );
+    assert_eq!(
+        Limited::step(&mut s, Input::Event(Event::Adjust), &mut se),
+        Some(Event::Adjust)
+    );
+    assert_eq!(
+        s,
+        State {
+            on: true,
+            adjustments: 1
+        }
+    );
+
+    // After the interval, transitions apply again
+    clock.advance(Duration::from_millis(500));
+    assert!(!se.is_limited());
+    assert_eq!(
+        Limited::step(&mut s, Input::Event(Event::Toggle), &mut se),
+        Some(Event::Toggle)
+    );
+    assert!(!s.on);
+}
+
+#[test]
+fn rate_limited_within_wrapper() {
+    type Allow = fn(&State, &Event) -> Decision<Event>;
+    type Wrapped = WithMiddleware<Limited, Allow>;
+
+    let clock = ManualClock::default();
+    let limiter = Limiter::new(0, clock.clone(), Duration::from_secs(1));
+    let mut se = Middleware::new((|_, _| Decision::Allow) as Allow, limiter);
+    let mut s = State::default();
+
+    // The limit applies when stepped by an outer wrapper
+    assert_eq!(
+        Wrapped::step(&mut s, Input::Event(Event::Toggle), &mut se),
+        Some(Event::Toggle)
+    );
+    assert!(se.inner().is_limited());
+    assert_eq!(
+        Wrapped::step(&mut s, Input::Event(Event::Toggle), &mut se),
+        None
+    );
+    assert_eq!(
+        Wrapped::step(&mut s, Input::Event(Event::Adjust), &mut se),
+        Some(Event::Adjust)
+    );
+    assert!(s.on);
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(
+        Wrapped::step(&mut s, Input::Event(Event::Toggle), &mut se),
+        Some(Event::Toggle)
+    );
+    assert!(!s.on);
+}
+
+#[test]
+fn rate_limited_command() {
+    let clock = ManualClock::default();
+    let mut se = Limiter::new(0, clock.clone(), Duration::from_secs(1));
+    let mut s = State::default();
+
+    assert_eq!(
+        Limited::step(&mut s, Input::Command(Event::Toggle), &mut se),
+        Some(Event::Toggle)
+    );
+
+    // Within the interval, a command still performs its effects though its event is dropped
+    assert_eq!(
+        Limited::step(&mut s, Input::Command(Event::Toggle), &mut se),
+        None
+    );
+    assert_eq!(
+        Limited::step(&mut s, Input::Command(Event::Adjust), &mut se),
+        Some(Event::Adjust)
+    );
+    assert_eq!(*se.inner(), 3);
+    assert_eq!(
+        s,
+        State {
+            on: true,
+            adjustments: 1
+        }
+    );
+}
@@ -0,0 +1,123 @@
+// Declare our state, commands and events
+
+use edfsm::{impl_fsm, Fsm, Input};
+
+struct Idle;
+struct Running;
+enum State {
+    Idle(Idle),
+    Running(Running),
+}
+
+struct Start;
+struct Restart;
+struct Refresh;
+enum Command {
+    Start(Start),
+    Restart(Restart),
+    Refresh(Refresh),
+}
+
+struct Started;
+struct Restarted;
+struct Refreshed;
+enum Event {
+    Started(Started),
+    Restarted(Restarted),
+    Refreshed(Refreshed),
+}
+
+// Declare an object to handle effects as we step through the FSM
+
+#[derive(Default)]
+struct EffectHandlers {
+    entered_running: u32,
+}
+
+// Declare the FSM itself
+
+struct MyFsm;
+
+#[impl_fsm]
+impl Fsm for MyFsm {
+    type S = State;
+    type C = Command;
+    type E = Event;
+    type SE = EffectHandlers;
+
+    state!(Running / entry);
+
+    command!(Idle    => Start   => Started   => Running);
+    command!(Running => Restart => Restarted => Running / reentry);
+    command!(Running => Refresh => Refreshed => Running);
+}
+
+impl MyFsm {
+    fn on_entry_running(_to_s: &Running, se: &mut EffectHandlers) {
+        se.entered_running += 1;
+    }
+
+    fn for_idle_start(_s: &Idle, _c: Start, _se: &mut EffectHandlers) -> Option<Started> {
+        Some(Started)
+    }
+
+    fn on_idle_started(_s: &Idle, _e: &Started) -> Option<Running> {
+        Some(Running)
+    }
+
+    fn for_running_restart(
+        _s: &Running,
+        _c: Restart,
+        _se: &mut EffectHandlers,
+    ) -> Option<Restarted> {
+        Some(Restarted)
+    }
+
+    fn on_running_restarted(_s: &Running, _e: &Restarted) -> Option<Running> {
+        Some(Running)
+    }
+
+    fn for_running_refresh(
+        _s: &Running,
+        _c: Refresh,
+        _se: &mut EffectHandlers,
+    ) -> Option<Refreshed> {
+        Some(Refreshed)
+    }
+
+    fn on_running_refreshed(_s: &Running, _e: &Refreshed) -> Option<Running> {
+        Some(Running)
+    }
+}
+
+#[test]
+fn reentry() {
+    let mut se = EffectHandlers::default();
+    let mut s = State::Idle(Idle);
+
+    let e = MyFsm::step(&mut s, Input::Command(Command::Start(Start)), &mut se);
+    assert!(matches!(e, Some(Event::Started(Started))));
+    assert_eq!(se.entered_running, 1);
+
+    // An explicit self-transition runs the entry handler again.
+
+    let e = MyFsm::step(&mut s, Input::Command(Command::Restart(Restart)), &mut se);
+    assert!(matches!(e, Some(Event::Restarted(Restarted))));
+    assert_eq!(se.entered_running, 2);
+
+    assert!(matches!(
+        MyFsm::on_event(&mut s, &Event::Restarted(Restarted)),
+        Some(edfsm::Change::Transitioned)
+    ));
+
+    // A self-transition without the qualifier is also a transition, as it has always been.
+
+    let e = MyFsm::step(&mut s, Input::Command(Command::Refresh(Refresh)), &mut se);
+    assert!(matches!(e, Some(Event::Refreshed(Refreshed))));
+    assert_eq!(se.entered_running, 3);
+
+    assert!(matches!(
+        MyFsm::on_event(&mut s, &Event::Refreshed(Refreshed)),
+        Some(edfsm::Change::Transitioned)
+    ));
+}
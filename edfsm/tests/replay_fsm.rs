@@ -0,0 +1,103 @@
+// Declare our state, commands and events
+
+use edfsm::{impl_fsm, replay, Fsm, Input};
+
+#[derive(Debug)]
+struct Idle;
+#[derive(Debug)]
+struct Running;
+#[derive(Debug)]
+enum State {
+    Idle(Idle),
+    Running(Running),
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State::Idle(Idle)
+    }
+}
+
+struct Start;
+struct Stop;
+enum Command {
+    Start(Start),
+    Stop(Stop),
+}
+
+struct Started;
+struct Stopped;
+enum Event {
+    Started(Started),
+    Stopped(Stopped),
+}
+
+// Declare the FSM itself
+
+struct MyFsm;
+
+#[impl_fsm]
+impl Fsm for MyFsm {
+    type S = State;
+    type C = Command;
+    type E = Event;
+    type SE = ();
+
+    command!(Idle    => Start => Started => Running);
+    command!(Running => Stop  => Stopped => Idle);
+
+    ignore_command!(Idle    => Stop);
+    ignore_command!(Running => Start);
+}
+
+impl MyFsm {
+    fn for_running_stop(_s: &Running, _c: Stop, _se: &mut ()) -> Option<Stopped> {
+        Some(Stopped)
+    }
+
+    fn on_running_stopped(_s: &Running, _e: &Stopped) -> Option<Idle> {
+        Some(Idle)
+    }
+
+    fn for_idle_start(_s: &Idle, _c: Start, _se: &mut ()) -> Option<Started> {
+        Some(Started)
+    }
+
+    fn on_idle_started(_s: &Idle, _e: &Started) -> Option<Running> {
+        Some(Running)
+    }
+}
+
+#[test]
+fn replay_events() {
+    let s = replay::<MyFsm>(&[Event::Started(Started), Event::Stopped(Stopped)]);
+    assert!(matches!(s, State::Idle(_)));
+
+    let s = replay::<MyFsm>(&[Event::Started(Started)]);
+    assert!(matches!(s, State::Running(_)));
+
+    // Ignored events leave the state unchanged
+    let s = replay::<MyFsm>(&[Event::Stopped(Stopped), Event::Started(Started)]);
+    assert!(matches!(s, State::Running(_)));
+
+    let s = replay::<MyFsm>(&[]);
+    assert!(matches!(s, State::Idle(_)));
+}
+
+#[test]
+fn replay_stepped_events() {
+    // The events produced by stepping commands replay to the same state
+    let mut s = State::default();
+    let events: Vec<Event> = [
+        Command::Start(Start),
+        Command::Start(Start),
+        Command::Stop(Stop),
+        Command::Start(Start),
+    ]
+    .into_iter()
+    .filter_map(|c| MyFsm::step(&mut s, Input::Command(c), &mut ()))
+    .collect();
+    assert_eq!(events.len(), 3);
+    assert!(matches!(s, State::Running(_)));
+    assert!(matches!(replay::<MyFsm>(&events), State::Running(_)));
+}
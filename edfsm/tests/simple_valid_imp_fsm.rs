@@ -1,6 +1,6 @@
 // Declare our state, commands and events
 
-use edfsm::{impl_fsm, Fsm, Input};
+use edfsm::{impl_fsm, Applied, Fsm, Input};
 
 struct Idle;
 struct Running;
@@ -49,7 +49,7 @@ impl EffectHandlers {
 
 struct MyFsm;
 
-#[impl_fsm]
+#[impl_fsm(state_eq, state_name, accepts)]
 impl Fsm for MyFsm {
     type S = State;
     type C = Command;
@@ -141,3 +141,46 @@ fn main() {
     assert_eq!(se.stopped, 1);
     assert_eq!(se.transitioned_stopped_to_started, 1);
 }
+
+#[test]
+fn accepts() {
+    assert!(MyFsm::accepts(&State::Idle(Idle), &Command::Start(Start)));
+    assert!(!MyFsm::accepts(&State::Idle(Idle), &Command::Stop(Stop)));
+    assert!(MyFsm::accepts(
+        &State::Running(Running),
+        &Command::Stop(Stop)
+    ));
+    assert!(!MyFsm::accepts(
+        &State::Running(Running),
+        &Command::Start(Start)
+    ));
+}
+
+#[test]
+fn apply() {
+    let mut s = State::Idle(Idle);
+    assert_eq!(
+        MyFsm::apply(&mut s, &Event::Stopped(Stopped)),
+        Applied::Ignored
+    );
+    assert_eq!(
+        MyFsm::apply(&mut s, &Event::Started(Started)),
+        Applied::Transitioned
+    );
+    assert!(matches!(s, State::Running(_)));
+}
+
+#[test]
+fn state_eq() {
+    assert!(MyFsm::state_eq(&State::Idle(Idle), &State::Idle(Idle)));
+    assert!(!MyFsm::state_eq(
+        &State::Idle(Idle),
+        &State::Running(Running)
+    ));
+}
+
+#[test]
+fn state_name() {
+    assert_eq!(MyFsm::state_name(&State::Idle(Idle)), "Idle");
+    assert_eq!(MyFsm::state_name(&State::Running(Running)), "Running");
+}
@@ -0,0 +1,161 @@
+use core::time::Duration;
+use edfsm::{Change, Fsm, Input, Timers};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Timer {
+    Retry,
+    Offline,
+}
+
+const fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+#[test]
+fn timers_set_and_fire() {
+    let mut timers = Timers::new();
+    assert!(timers.is_empty());
+    assert_eq!(timers.set(Timer::Offline, ms(300)), None);
+    assert_eq!(timers.set(Timer::Retry, ms(100)), None);
+    assert_eq!(timers.len(), 2);
+    assert_eq!(timers.next_deadline(), Some(ms(100)));
+
+    // Nothing has expired yet
+    assert_eq!(timers.expired(ms(99)).count(), 0);
+
+    // A deadline at the time polled has expired, and the timer is removed
+    assert_eq!(timers.expired(ms(100)).collect::<Vec<_>>(), [Timer::Retry]);
+    assert_eq!(timers.deadline(&Timer::Retry), None);
+    assert_eq!(timers.next_deadline(), Some(ms(300)));
+
+    // Timers expire in deadline order
+    timers.set(Timer::Retry, ms(200));
+    assert_eq!(
+        timers.expired(ms(500)).collect::<Vec<_>>(),
+        [Timer::Retry, Timer::Offline]
+    );
+    assert!(timers.is_empty());
+}
+
+#[test]
+fn timers_reset_and_cancel() {
+    let mut timers = Timers::new();
+    timers.set(Timer::Retry, ms(100));
+    timers.set(Timer::Offline, ms(300));
+
+    // Setting a timer again replaces its deadline
+    assert_eq!(timers.set(Timer::Retry, ms(400)), Some(ms(100)));
+    assert_eq!(timers.len(), 2);
+    assert_eq!(
+        timers.expired(ms(300)).collect::<Vec<_>>(),
+        [Timer::Offline]
+    );
+
+    // A cancelled timer does not expire
+    assert_eq!(timers.cancel(&Timer::Retry), Some(ms(400)));
+    assert_eq!(timers.cancel(&Timer::Retry), None);
+    assert_eq!(timers.expired(ms(1000)).count(), 0);
+
+    // Timers not taken from the iterator remain set
+    timers.set(Timer::Retry, ms(100));
+    timers.set(Timer::Offline, ms(200));
+    assert_eq!(timers.expired(ms(1000)).next(), Some(Timer::Retry));
+    assert_eq!(timers.deadline(&Timer::Offline), Some(ms(200)));
+}
+
+// A state machine driven by timers, without a runtime
+
+#[derive(Debug, Default, PartialEq)]
+enum State {
+    #[default]
+    Online,
+    Offline,
+}
+
+enum Command {
+    Heard,
+    Expired(Timer),
+}
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    WentOnline,
+    WentOffline,
+}
+
+/// The timers to set, with the time now.
+struct Effects {
+    now: Duration,
+    timers: Timers<Timer>,
+}
+
+const TIMEOUT: Duration = ms(300);
+
+struct MyFsm;
+
+impl Fsm for MyFsm {
+    type S = State;
+    type C = Command;
+    type E = Event;
+    type SE = Effects;
+
+    fn for_command(s: &Self::S, c: Self::C, se: &mut Self::SE) -> Option<Self::E> {
+        match (s, c) {
+            (State::Online, Command::Heard) => {
+                se.timers.set(Timer::Offline, se.now + TIMEOUT);
+                None
+            }
+            (State::Offline, Command::Heard) => Some(Event::WentOnline),
+            (State::Online, Command::Expired(Timer::Offline)) => Some(Event::WentOffline),
+            _ => None,
+        }
+    }
+
+    fn on_event(s: &mut Self::S, e: &Self::E) -> Option<Change> {
+        *s = match e {
+            Event::WentOnline => State::Online,
+            Event::WentOffline => State::Offline,
+        };
+        Some(Change::Transitioned)
+    }
+
+    fn on_change(_s: &Self::S, e: &Self::E, se: &mut Self::SE, _change: Change) {
+        if let Event::WentOnline = e {
+            se.timers.set(Timer::Offline, se.now + TIMEOUT);
+        }
+    }
+}
+
+/// Advance to the given time, stepping the state machine with any expired timers.
+fn poll(s: &mut State, se: &mut Effects, now: Duration) -> Vec<Event> {
+    se.now = now;
+    let expired: Vec<_> = se.timers.expired(now).collect();
+    expired
+        .into_iter()
+        .filter_map(|t| MyFsm::step(s, Input::Command(Command::Expired(t)), se))
+        .collect()
+}
+
+#[test]
+fn timers_drive_fsm() {
+    let mut s = State::Online;
+    let mut se = Effects {
+        now: ms(0),
+        timers: Timers::new(),
+    };
+    MyFsm::step(&mut s, Input::Command(Command::Heard), &mut se);
+
+    // Hearing from the device again postpones the timeout
+    assert!(poll(&mut s, &mut se, ms(200)).is_empty());
+    MyFsm::step(&mut s, Input::Command(Command::Heard), &mut se);
+    assert!(poll(&mut s, &mut se, ms(400)).is_empty());
+
+    // Until it is silent for the timeout
+    assert_eq!(poll(&mut s, &mut se, ms(500)), [Event::WentOffline]);
+    assert_eq!(s, State::Offline);
+    assert!(se.timers.is_empty());
+
+    let e = MyFsm::step(&mut s, Input::Command(Command::Heard), &mut se);
+    assert_eq!(e, Some(Event::WentOnline));
+    assert_eq!(se.timers.deadline(&Timer::Offline), Some(ms(800)));
+}
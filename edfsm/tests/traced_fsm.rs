@@ -0,0 +1,137 @@
+// Declare our state, commands and events
+
+use edfsm::{
+    impl_fsm, Decision, Fsm, Input, Middleware, Named, Traced, Tracer, Transition, WithMiddleware,
+};
+
+struct Idle;
+struct Running;
+enum State {
+    Idle(Idle),
+    Running(Running),
+}
+
+impl Named for State {
+    fn name(&self) -> &'static str {
+        match self {
+            State::Idle(_) => "Idle",
+            State::Running(_) => "Running",
+        }
+    }
+}
+
+struct Start;
+struct Stop;
+enum Command {
+    Start(Start),
+    Stop(Stop),
+}
+
+struct Started;
+struct Stopped;
+enum Event {
+    Started(Started),
+    Stopped(Stopped),
+}
+
+impl Named for Event {
+    fn name(&self) -> &'static str {
+        match self {
+            Event::Started(_) => "Started",
+            Event::Stopped(_) => "Stopped",
+        }
+    }
+}
+
+// Declare the FSM itself
+
+struct MyFsm;
+
+#[impl_fsm]
+impl Fsm for MyFsm {
+    type S = State;
+    type C = Command;
+    type E = Event;
+    type SE = ();
+
+    command!(Idle    => Start => Started => Running);
+    command!(Running => Stop  => Stopped => Idle);
+
+    ignore_command!(Idle    => Stop);
+    ignore_command!(Running => Start);
+}
+
+impl MyFsm {
+    fn for_running_stop(_s: &Running, _c: Stop, _se: &mut ()) -> Option<Stopped> {
+        Some(Stopped)
+    }
+
+    fn on_running_stopped(_s: &Running, _e: &Stopped) -> Option<Idle> {
+        Some(Idle)
+    }
+
+    fn for_idle_start(_s: &Idle, _c: Start, _se: &mut ()) -> Option<Started> {
+        Some(Started)
+    }
+
+    fn on_idle_started(_s: &Idle, _e: &Started) -> Option<Running> {
+        Some(Running)
+    }
+}
+
+#[test]
+fn traced() {
+    type MyTracedFsm = Traced<MyFsm, 2>;
+
+    let mut se = Tracer::<(), 2>::default();
+    let mut s = State::Idle(Idle);
+
+    MyTracedFsm::step(&mut s, Input::Command(Command::Start(Start)), &mut se);
+    MyTracedFsm::step(&mut s, Input::Command(Command::Start(Start)), &mut se);
+    MyTracedFsm::step(&mut s, Input::Command(Command::Stop(Stop)), &mut se);
+    MyTracedFsm::step(&mut s, Input::Event(Event::Started(Started)), &mut se);
+
+    // Only the last two transitions are retained, and ignored commands are not recorded.
+
+    let history: Vec<Transition> = se.history().copied().collect();
+    assert_eq!(
+        history,
+        [
+            Transition {
+                from: "Running",
+                input: "Stopped",
+                to: "Idle"
+            },
+            Transition {
+                from: "Idle",
+                input: "Started",
+                to: "Running"
+            },
+        ]
+    );
+}
+
+#[test]
+fn traced_within_wrapper() {
+    type Allow = fn(&State, &Command) -> Decision<Command>;
+    type MyWrappedFsm = WithMiddleware<Traced<MyFsm, 2>, Allow>;
+
+    let mut se = Middleware::new(
+        (|_, _| Decision::Allow) as Allow,
+        Tracer::<(), 2>::default(),
+    );
+    let mut s = State::Idle(Idle);
+
+    // Transitions are recorded when stepped by an outer wrapper
+    MyWrappedFsm::step(&mut s, Input::Command(Command::Start(Start)), &mut se);
+
+    let history: Vec<Transition> = se.inner().history().copied().collect();
+    assert_eq!(
+        history,
+        [Transition {
+            from: "Idle",
+            input: "Started",
+            to: "Running"
+        }]
+    );
+}